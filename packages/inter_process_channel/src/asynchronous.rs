@@ -0,0 +1,266 @@
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{Codec, Error, JsonLines, LengthPrefixed, Result};
+
+/// Bytes read towards the next frame.
+///
+/// [`AsyncReceiver`] keeps this between calls to [`AsyncReceiver::recv`], so a cancelled `recv`
+/// loses nothing and the next call continues where it stopped.
+#[derive(Debug, Default)]
+pub struct FrameBuffer {
+    pub bytes: Vec<u8>,
+    /// Bytes of an oversized frame which were already thrown away.
+    pub skipped: usize,
+}
+
+/// A codec that can tell where a frame ends in the bytes received so far.
+///
+/// The frame is split off the buffer and then handed to [`Codec::decode`] as a whole.
+pub trait AsyncCodec: Codec {
+    /// Removes the first complete frame from `buffer`, or returns `None` if more bytes are
+    /// needed. An oversized frame is dropped as it arrives and reported once it has been skipped.
+    fn split_frame(&mut self, buffer: &mut FrameBuffer) -> Option<Result<Vec<u8>>>;
+}
+
+impl AsyncCodec for JsonLines {
+    fn split_frame(&mut self, buffer: &mut FrameBuffer) -> Option<Result<Vec<u8>>> {
+        let max_len = self.max_len as usize;
+
+        let Some(end) = buffer.bytes.iter().position(|byte| *byte == b'\n') else {
+            if buffer.bytes.len() > max_len {
+                buffer.skipped += buffer.bytes.len();
+                buffer.bytes.clear();
+            }
+            return None;
+        };
+
+        let len = buffer.skipped + end;
+        buffer.skipped = 0;
+
+        // The newline stays on the frame so that decoding sees a whole line
+        let frame: Vec<u8> = buffer.bytes.drain(..=end).collect();
+
+        if len > max_len {
+            return Some(Err(Error::FrameTooLarge(len)));
+        }
+
+        Some(Ok(frame))
+    }
+}
+
+impl AsyncCodec for LengthPrefixed {
+    fn split_frame(&mut self, buffer: &mut FrameBuffer) -> Option<Result<Vec<u8>>> {
+        let prefix: [u8; 4] = buffer.bytes.get(..4)?.try_into().ok()?;
+        let len = u32::from_le_bytes(prefix) as usize;
+
+        if len > self.max_len as usize {
+            // Skip the payload so that the next frame is read from its start
+            let available = (buffer.bytes.len() - 4).min(len - buffer.skipped);
+            buffer.bytes.drain(4..4 + available);
+            buffer.skipped += available;
+
+            if buffer.skipped < len {
+                return None;
+            }
+
+            buffer.bytes.drain(..4);
+            buffer.skipped = 0;
+            return Some(Err(Error::FrameTooLarge(len)));
+        }
+
+        if buffer.bytes.len() < 4 + len {
+            return None;
+        }
+
+        Some(Ok(buffer.bytes.drain(..4 + len).collect()))
+    }
+}
+
+pub struct AsyncSender<T, W, C = JsonLines>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+    C: Codec,
+{
+    to: W,
+    codec: C,
+    buffer: Vec<u8>,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, W, C> AsyncSender<T, W, C>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+    C: Codec,
+{
+    pub async fn send(&mut self, data: T) -> Result<()> {
+        self.buffer.clear();
+        self.codec.encode(&mut self.buffer, &data)?;
+        self.to.write_all(&self.buffer).await?;
+        self.to.flush().await?;
+        Ok(())
+    }
+}
+
+pub fn async_sender<T, W>(to: W) -> AsyncSender<T, W>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    async_sender_with_codec(to, JsonLines::new())
+}
+
+pub fn async_sender_with_codec<T, W, C>(to: W, codec: C) -> AsyncSender<T, W, C>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+    C: Codec,
+{
+    AsyncSender {
+        to,
+        codec,
+        buffer: Vec::new(),
+        phantom: std::marker::PhantomData,
+    }
+}
+
+pub struct AsyncReceiver<T, R, C = JsonLines>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+    C: AsyncCodec,
+{
+    from: R,
+    codec: C,
+    buffer: FrameBuffer,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, R, C> AsyncReceiver<T, R, C>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+    C: AsyncCodec,
+{
+    /// Receives the next message.
+    ///
+    /// This is cancel safe: the bytes of a partially received frame are kept in the receiver, so
+    /// it can be used in `tokio::select!` without losing messages.
+    pub async fn recv(&mut self) -> Result<T> {
+        loop {
+            if let Some(frame) = self.codec.split_frame(&mut self.buffer) {
+                return self.codec.decode(&mut frame?.as_slice());
+            }
+
+            if self.from.read_buf(&mut self.buffer.bytes).await? == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+    }
+}
+
+pub fn async_receiver<T, R>(from: R) -> AsyncReceiver<T, R>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    async_receiver_with_codec(from, JsonLines::new())
+}
+
+pub fn async_receiver_with_codec<T, R, C>(from: R, codec: C) -> AsyncReceiver<T, R, C>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+    C: AsyncCodec,
+{
+    AsyncReceiver {
+        from,
+        codec,
+        buffer: FrameBuffer::default(),
+        phantom: std::marker::PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::{Context, Waker};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct SomeNiceType {
+        value: u64,
+    }
+
+    #[tokio::test]
+    async fn send_and_recv_works() {
+        let (client, server) = tokio::io::duplex(64);
+
+        let mut sender = async_sender::<SomeNiceType, _>(client);
+        let mut receiver = async_receiver::<SomeNiceType, _>(server);
+
+        sender.send(SomeNiceType { value: 42 }).await.unwrap();
+        sender.send(SomeNiceType { value: 43 }).await.unwrap();
+        drop(sender);
+
+        assert_eq!(receiver.recv().await.unwrap().value, 42);
+        assert_eq!(receiver.recv().await.unwrap().value, 43);
+        assert!(receiver.recv().await.unwrap_err().is_eof());
+    }
+
+    #[tokio::test]
+    async fn length_prefixed_send_and_recv_works() {
+        let (client, server) = tokio::io::duplex(64);
+
+        let mut sender =
+            async_sender_with_codec::<SomeNiceType, _, _>(client, LengthPrefixed::new());
+        let mut receiver =
+            async_receiver_with_codec::<SomeNiceType, _, _>(server, LengthPrefixed::new());
+
+        sender.send(SomeNiceType { value: 42 }).await.unwrap();
+        sender.send(SomeNiceType { value: 43 }).await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap().value, 42);
+        assert_eq!(receiver.recv().await.unwrap().value, 43);
+    }
+
+    #[tokio::test]
+    async fn cancelled_recv_keeps_partial_frame() {
+        let (mut client, server) = tokio::io::duplex(64);
+
+        let mut receiver = async_receiver::<SomeNiceType, _>(server);
+
+        client.write_all(b"{\"value\": ").await.unwrap();
+
+        {
+            let recv = std::pin::pin!(receiver.recv());
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(recv.poll(&mut cx).is_pending());
+        }
+
+        client.write_all(b"42}\n").await.unwrap();
+
+        assert_eq!(receiver.recv().await.unwrap().value, 42);
+    }
+
+    #[tokio::test]
+    async fn oversized_frames_are_skipped() {
+        let (mut client, server) = tokio::io::duplex(256);
+
+        let mut receiver =
+            async_receiver_with_codec::<SomeNiceType, _, _>(server, JsonLines::new().max_len(16));
+
+        client.write_all(&[b' '; 100]).await.unwrap();
+        client.write_all(b"\n{\"value\": 42}\n").await.unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            Err(Error::FrameTooLarge(100))
+        ));
+        assert_eq!(receiver.recv().await.unwrap().value, 42);
+    }
+}
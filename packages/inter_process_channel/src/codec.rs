@@ -0,0 +1,180 @@
+use std::io::{Read, Write};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Error, Result};
+
+/// Framing and serialization of messages on the wire.
+pub trait Codec {
+    fn encode<T, W>(&mut self, to: &mut W, data: &T) -> Result<()>
+    where
+        T: Serialize,
+        W: Write;
+
+    fn decode<T, R>(&mut self, from: &mut R) -> Result<T>
+    where
+        T: DeserializeOwned,
+        R: Read;
+}
+
+/// One JSON document per line. Readable by anything that speaks JSON, but the payload must not
+/// contain a raw newline.
+///
+/// Every line is read in full before it is parsed, so a malformed line is skipped as a whole and
+/// the next one is read normally.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonLines {
+    pub(crate) max_len: u32,
+}
+
+impl JsonLines {
+    /// Lines longer than this are skipped instead of being buffered.
+    pub const DEFAULT_MAX_LEN: u32 = 16 * 1024 * 1024;
+
+    pub fn new() -> Self {
+        Self {
+            max_len: Self::DEFAULT_MAX_LEN,
+        }
+    }
+
+    #[must_use]
+    pub fn max_len(mut self, max_len: u32) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Default for JsonLines {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for JsonLines {
+    fn encode<T, W>(&mut self, to: &mut W, data: &T) -> Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        serde_json::to_writer(&mut *to, data)?;
+        to.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn decode<T, R>(&mut self, from: &mut R) -> Result<T>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        let line = read_line(from, self.max_len as usize)?;
+
+        Ok(serde_json::from_slice(&line)?)
+    }
+}
+
+/// MessagePack payloads prefixed with their length as a little endian `u32`.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthPrefixed {
+    pub(crate) max_len: u32,
+}
+
+impl LengthPrefixed {
+    /// Frames larger than this are rejected instead of being allocated.
+    pub const DEFAULT_MAX_LEN: u32 = 16 * 1024 * 1024;
+
+    pub fn new() -> Self {
+        Self {
+            max_len: Self::DEFAULT_MAX_LEN,
+        }
+    }
+
+    #[must_use]
+    pub fn max_len(mut self, max_len: u32) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Default for LengthPrefixed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Codec for LengthPrefixed {
+    fn encode<T, W>(&mut self, to: &mut W, data: &T) -> Result<()>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let payload = rmp_serde::to_vec_named(data)?;
+
+        let len = u32::try_from(payload.len())
+            .ok()
+            .filter(|len| *len <= self.max_len)
+            .ok_or(Error::FrameTooLarge(payload.len()))?;
+
+        to.write_all(&len.to_le_bytes())?;
+        to.write_all(&payload)?;
+        Ok(())
+    }
+
+    fn decode<T, R>(&mut self, from: &mut R) -> Result<T>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        let mut len = [0; 4];
+        from.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len);
+
+        if len > self.max_len {
+            // Skip the payload so that the next frame is read from its start
+            std::io::copy(&mut from.take(u64::from(len)), &mut std::io::sink())?;
+            return Err(Error::FrameTooLarge(len as usize));
+        }
+
+        let mut payload = vec![0; len as usize];
+        from.read_exact(&mut payload)?;
+
+        Ok(rmp_serde::from_slice(&payload)?)
+    }
+}
+
+/// Reads up to the next newline, which is consumed but not returned. A line longer than `max_len`
+/// is consumed entirely and reported as [`Error::FrameTooLarge`].
+fn read_line<R>(from: &mut R, max_len: usize) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut line = Vec::new();
+    let mut len = 0;
+
+    loop {
+        let mut byte = [0; 1];
+
+        if from.read(&mut byte)? == 0 {
+            // A last line without a newline still counts
+            if len == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            break;
+        }
+
+        if byte[0] == b'\n' {
+            break;
+        }
+
+        len += 1;
+
+        if len <= max_len {
+            line.push(byte[0]);
+        }
+    }
+
+    if len > max_len {
+        return Err(Error::FrameTooLarge(len));
+    }
+
+    Ok(line)
+}
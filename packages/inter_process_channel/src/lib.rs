@@ -1,30 +1,110 @@
 use std::io::{Read, Write};
 
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde::{Serialize, de::DeserializeOwned};
 
-pub use serde_json::{Result, Error};
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
+pub mod codec;
+pub mod transport;
 
-pub struct Sender<T, W>
+#[cfg(feature = "tokio")]
+pub use asynchronous::{
+    AsyncCodec, AsyncReceiver, AsyncSender, FrameBuffer, async_receiver, async_receiver_with_codec,
+    async_sender, async_sender_with_codec,
+};
+pub use codec::{Codec, JsonLines, LengthPrefixed};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Encode(rmp_serde::encode::Error),
+    Decode(rmp_serde::decode::Error),
+    /// The frame is larger than the codec accepts.
+    FrameTooLarge(usize),
+}
+
+impl Error {
+    /// Whether the other end closed the channel. A frame which was read in full but ends early,
+    /// such as a truncated JSON line, is a decoding error instead.
+    pub fn is_eof(&self) -> bool {
+        match self {
+            Error::Io(e) => e.kind() == std::io::ErrorKind::UnexpectedEof,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {e}"),
+            Error::Json(e) => write!(f, "json error: {e}"),
+            Error::Encode(e) => write!(f, "encode error: {e}"),
+            Error::Decode(e) => write!(f, "decode error: {e}"),
+            Error::FrameTooLarge(len) => write!(f, "frame of {len} bytes is too large"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Encode(e) => Some(e),
+            Error::Decode(e) => Some(e),
+            Error::FrameTooLarge(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(e: rmp_serde::encode::Error) -> Self {
+        Error::Encode(e)
+    }
+}
+
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(e: rmp_serde::decode::Error) -> Self {
+        Error::Decode(e)
+    }
+}
+
+pub struct Sender<T, W, C = JsonLines>
 where
     T: Serialize,
     W: Write,
+    C: Codec,
 {
     to: W,
+    codec: C,
     phantom: std::marker::PhantomData<T>,
 }
 
-impl<T, W> Sender<T, W>
+impl<T, W, C> Sender<T, W, C>
 where
     T: Serialize,
     W: Write,
+    C: Codec,
 {
-    pub fn send<'a>(&mut self, data: T) -> Result<()>
-    where
-        T: Serialize + Deserialize<'a>,
-    {
-        serde_json::to_writer(&mut self.to, &data)?;
-        self.to.write_all(b"\n").map_err(Error::io)?;
-        self.to.flush().map_err(Error::io)?;
+    pub fn send(&mut self, data: T) -> Result<()> {
+        self.codec.encode(&mut self.to, &data)?;
+        self.to.flush()?;
         Ok(())
     }
 }
@@ -33,70 +113,91 @@ pub fn sender<T, W>(to: W) -> Sender<T, W>
 where
     T: Serialize,
     W: Write,
+{
+    sender_with_codec(to, JsonLines::new())
+}
+
+pub fn sender_with_codec<T, W, C>(to: W, codec: C) -> Sender<T, W, C>
+where
+    T: Serialize,
+    W: Write,
+    C: Codec,
 {
     Sender {
         to,
+        codec,
         phantom: std::marker::PhantomData,
     }
 }
 
-struct ReadUntilNewline<R>
+pub struct Receiver<T, R, C = JsonLines>
 where
+    T: DeserializeOwned,
     R: Read,
+    C: Codec,
 {
-    reader: R,
+    from: R,
+    codec: C,
+    phantom: std::marker::PhantomData<T>,
 }
 
-impl<R> ReadUntilNewline<R>
+impl<T, R, C> Receiver<T, R, C>
 where
+    T: DeserializeOwned,
     R: Read,
+    C: Codec,
 {
-    pub fn new(reader: R) -> Self {
-        ReadUntilNewline { reader }
+    pub fn recv(&mut self) -> Result<T> {
+        self.codec.decode(&mut self.from)
     }
-}
 
-impl<R> Read for ReadUntilNewline<R>
-where
-    R: Read,
-{
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let mut total_read = 0;
-        loop {
-            let mut byte = [0; 1];
-            let bytes_read = self.reader.read(&mut byte)?;
-            if bytes_read == 0 || byte[0] == b'\n' {
-                break;
-            }
-            if total_read < buf.len() {
-                buf[total_read] = byte[0];
-                total_read += 1;
-            }
-
-            if total_read == buf.len() {
-                break;
-            }
+    /// Receives frames until the other end closes the channel.
+    ///
+    /// Frames which fail to decode are yielded as errors and reading continues with the next
+    /// frame. An I/O error other than the end of the stream is yielded once and ends the
+    /// iteration, since the stream cannot be trusted afterwards.
+    pub fn iter(&mut self) -> Iter<'_, T, R, C> {
+        Iter {
+            receiver: self,
+            done: false,
         }
-        Ok(total_read)
     }
 }
 
-pub struct Receiver<T, R>
+pub struct Iter<'a, T, R, C>
 where
     T: DeserializeOwned,
     R: Read,
+    C: Codec,
 {
-    from: R,
-    phantom: std::marker::PhantomData<T>,
+    receiver: &'a mut Receiver<T, R, C>,
+    done: bool,
 }
 
-impl<T, R> Receiver<T, R>
+impl<T, R, C> Iterator for Iter<'_, T, R, C>
 where
     T: DeserializeOwned,
     R: Read,
+    C: Codec,
 {
-    pub fn recv(&mut self) -> Result<T> {
-        serde_json::from_reader(ReadUntilNewline::new(&mut self.from))
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.receiver.recv() {
+            Err(e) if e.is_eof() => {
+                self.done = true;
+                None
+            }
+            Err(e @ Error::Io(_)) => {
+                self.done = true;
+                Some(Err(e))
+            }
+            result => Some(result),
+        }
     }
 }
 
@@ -104,9 +205,19 @@ pub fn receiver<T, R>(from: R) -> Receiver<T, R>
 where
     T: DeserializeOwned,
     R: Read,
+{
+    receiver_with_codec(from, JsonLines::new())
+}
+
+pub fn receiver_with_codec<T, R, C>(from: R, codec: C) -> Receiver<T, R, C>
+where
+    T: DeserializeOwned,
+    R: Read,
+    C: Codec,
 {
     Receiver {
         from,
+        codec,
         phantom: std::marker::PhantomData,
     }
 }
@@ -120,6 +231,8 @@ mod tests {
         rc::Rc,
     };
 
+    use serde::Deserialize;
+
     use super::*;
 
     #[derive(Serialize, Deserialize, Debug)]
@@ -127,6 +240,11 @@ mod tests {
         value: u64,
     }
 
+    #[derive(Serialize, Deserialize, Debug)]
+    struct SomeTextType {
+        text: String,
+    }
+
     #[derive(Clone, Debug)]
     struct MockPipe {
         data: Rc<RefCell<VecDeque<u8>>>,
@@ -180,4 +298,124 @@ mod tests {
         assert_eq!(receiver.recv().unwrap().value, 43);
         assert_eq!(receiver.recv().unwrap().value, 44);
     }
+
+    #[test]
+    fn length_prefixed_send_and_recv_works() {
+        let pipe = MockPipe::new();
+
+        let mut sender =
+            sender_with_codec::<SomeTextType, _, _>(pipe.clone(), LengthPrefixed::new());
+        let mut receiver =
+            receiver_with_codec::<SomeTextType, _, _>(pipe.clone(), LengthPrefixed::new());
+
+        sender
+            .send(SomeTextType {
+                text: "first\nsecond".to_string(),
+            })
+            .unwrap();
+        sender
+            .send(SomeTextType {
+                text: "third".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(receiver.recv().unwrap().text, "first\nsecond");
+        assert_eq!(receiver.recv().unwrap().text, "third");
+        assert!(receiver.recv().unwrap_err().is_eof());
+    }
+
+    #[test]
+    fn length_prefixed_rejects_oversized_frames() {
+        let pipe = MockPipe::new();
+
+        let mut sender =
+            sender_with_codec::<SomeTextType, _, _>(pipe.clone(), LengthPrefixed::new());
+        let mut receiver = receiver_with_codec::<SomeTextType, _, _>(
+            pipe.clone(),
+            LengthPrefixed::new().max_len(16),
+        );
+
+        sender
+            .send(SomeTextType {
+                text: "longer than eight bytes".to_string(),
+            })
+            .unwrap();
+
+        assert!(matches!(receiver.recv(), Err(Error::FrameTooLarge(_))));
+
+        sender
+            .send(SomeTextType {
+                text: "short".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(receiver.recv().unwrap().text, "short");
+    }
+
+    #[test]
+    fn iter_skips_malformed_lines() {
+        let mut pipe = MockPipe::new();
+
+        pipe.write_all(b"{\"value\": 1}\n{\"value\": oops}\n{\"value\": 3}\n")
+            .unwrap();
+
+        let mut receiver = receiver::<SomeNiceType, _>(pipe.clone());
+        let results: Vec<_> = receiver.iter().collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().value, 1);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().value, 3);
+    }
+
+    #[test]
+    fn iter_skips_blank_lines() {
+        let mut pipe = MockPipe::new();
+
+        pipe.write_all(b"\n{\"value\": 2}\n").unwrap();
+
+        let mut receiver = receiver::<SomeNiceType, _>(pipe.clone());
+        let results: Vec<_> = receiver.iter().collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Err(Error::Json(_))));
+        assert_eq!(results[1].as_ref().unwrap().value, 2);
+    }
+
+    #[test]
+    fn iter_skips_truncated_lines() {
+        let mut pipe = MockPipe::new();
+
+        pipe.write_all(b"{\"value\": 1\n{\"value\": 2}\n").unwrap();
+
+        let mut receiver = receiver::<SomeNiceType, _>(pipe.clone());
+        let results: Vec<_> = receiver.iter().collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Err(Error::Json(_))));
+        assert_eq!(results[1].as_ref().unwrap().value, 2);
+    }
+
+    #[test]
+    fn oversized_lines_are_skipped() {
+        let pipe = MockPipe::new();
+
+        let mut sender = sender::<SomeTextType, _>(pipe.clone());
+        let mut receiver =
+            receiver_with_codec::<SomeTextType, _, _>(pipe.clone(), JsonLines::new().max_len(32));
+
+        sender
+            .send(SomeTextType {
+                text: "longer than thirty two bytes in total".to_string(),
+            })
+            .unwrap();
+        sender
+            .send(SomeTextType {
+                text: "short".to_string(),
+            })
+            .unwrap();
+
+        assert!(matches!(receiver.recv(), Err(Error::FrameTooLarge(_))));
+        assert_eq!(receiver.recv().unwrap().text, "short");
+    }
 }
@@ -0,0 +1,407 @@
+//! Local endpoints addressed by name: a named pipe on Windows and a Unix domain socket elsewhere.
+//!
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! let (mut sender, mut receiver) =
+//!     inter_process_channel::transport::connect::<String, String>("oscpie-control")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Codec, JsonLines, Receiver, Sender, receiver_with_codec, sender_with_codec};
+
+pub use platform::{Listener, Stream};
+
+pub type Channel<S, R, C = JsonLines> = (Sender<S, Stream, C>, Receiver<R, Stream, C>);
+
+/// Connects to the endpoint a [`Listener`] with the same name is waiting on.
+pub fn connect<S, R>(name: &str) -> io::Result<Channel<S, R>>
+where
+    S: Serialize,
+    R: DeserializeOwned,
+{
+    connect_with_codec(name, JsonLines::new())
+}
+
+pub fn connect_with_codec<S, R, C>(name: &str, codec: C) -> io::Result<Channel<S, R, C>>
+where
+    S: Serialize,
+    R: DeserializeOwned,
+    C: Codec + Clone,
+{
+    split(platform::connect(name)?, codec)
+}
+
+/// Starts accepting connections on the endpoint called `name`.
+pub fn listen(name: &str) -> io::Result<Listener> {
+    Listener::bind(name)
+}
+
+impl Listener {
+    /// Waits for the next client.
+    pub fn accept<S, R>(&self) -> io::Result<Channel<S, R>>
+    where
+        S: Serialize,
+        R: DeserializeOwned,
+    {
+        self.accept_with_codec(JsonLines::new())
+    }
+
+    pub fn accept_with_codec<S, R, C>(&self, codec: C) -> io::Result<Channel<S, R, C>>
+    where
+        S: Serialize,
+        R: DeserializeOwned,
+        C: Codec + Clone,
+    {
+        split(self.accept_stream()?, codec)
+    }
+}
+
+fn split<S, R, C>(stream: Stream, codec: C) -> io::Result<Channel<S, R, C>>
+where
+    S: Serialize,
+    R: DeserializeOwned,
+    C: Codec + Clone,
+{
+    let read_half = stream.try_clone()?;
+
+    Ok((
+        sender_with_codec(stream, codec.clone()),
+        receiver_with_codec(read_half, codec),
+    ))
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{
+        ffi::OsStr,
+        io,
+        os::windows::{ffi::OsStrExt, fs::OpenOptionsExt, io::IntoRawHandle},
+        sync::{Arc, Mutex},
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{
+            CloseHandle, ERROR_BROKEN_PIPE, ERROR_IO_PENDING, ERROR_PIPE_CONNECTED, GetLastError,
+            HANDLE, INVALID_HANDLE_VALUE,
+        },
+        Storage::FileSystem::{FILE_FLAG_OVERLAPPED, PIPE_ACCESS_DUPLEX, ReadFile, WriteFile},
+        System::{
+            IO::{GetOverlappedResult, OVERLAPPED},
+            Pipes::{
+                ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE,
+                PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+            },
+            Threading::CreateEventW,
+        },
+    };
+
+    const BUFFER_SIZE: u32 = 64 * 1024;
+
+    fn pipe_path(name: &str) -> String {
+        format!(r"\\.\pipe\{name}")
+    }
+
+    /// End of a pipe opened for overlapped I/O, so that one thread can read from it while another
+    /// writes to it. Blocking pipes serialize the two, which deadlocks a duplex channel.
+    pub struct Stream {
+        handle: Arc<PipeHandle>,
+    }
+
+    impl Stream {
+        /// Another handle to the same pipe, for the other direction.
+        pub fn try_clone(&self) -> io::Result<Self> {
+            Ok(Self {
+                handle: self.handle.clone(),
+            })
+        }
+    }
+
+    impl io::Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+
+            match wait_overlapped(self.handle.0, |overlapped| unsafe {
+                ReadFile(
+                    self.handle.0,
+                    buf.as_mut_ptr(),
+                    len,
+                    std::ptr::null_mut(),
+                    overlapped,
+                )
+            }) {
+                // The other end closed the pipe
+                Err(e) if e.raw_os_error() == Some(ERROR_BROKEN_PIPE as i32) => Ok(0),
+                result => result,
+            }
+        }
+    }
+
+    impl io::Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+
+            wait_overlapped(self.handle.0, |overlapped| unsafe {
+                WriteFile(
+                    self.handle.0,
+                    buf.as_ptr(),
+                    len,
+                    std::ptr::null_mut(),
+                    overlapped,
+                )
+            })
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Starts an overlapped operation with `start` and waits for it to complete. Returns the number
+    /// of bytes transferred.
+    fn wait_overlapped(
+        handle: HANDLE,
+        start: impl FnOnce(*mut OVERLAPPED) -> i32,
+    ) -> io::Result<usize> {
+        let event = unsafe { CreateEventW(std::ptr::null(), 1, 0, std::ptr::null()) };
+
+        if event.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        let event = PipeHandle(event);
+        let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+        overlapped.hEvent = event.0;
+
+        if start(&mut overlapped) == 0 && unsafe { GetLastError() } != ERROR_IO_PENDING {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut transferred = 0;
+
+        if unsafe { GetOverlappedResult(handle, &overlapped, &mut transferred, 1) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(transferred as usize)
+    }
+
+    pub fn connect(name: &str) -> io::Result<Stream> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(FILE_FLAG_OVERLAPPED)
+            .open(pipe_path(name))?;
+
+        Ok(Stream {
+            handle: Arc::new(PipeHandle(file.into_raw_handle())),
+        })
+    }
+
+    struct PipeHandle(HANDLE);
+
+    // Overlapped operations may run on the same handle from several threads at once
+    unsafe impl Send for PipeHandle {}
+    unsafe impl Sync for PipeHandle {}
+
+    /// A Windows pipe instance serves a single client, so one instance is always kept waiting
+    /// for the next client to connect to.
+    pub struct Listener {
+        path: Vec<u16>,
+        next: Mutex<PipeHandle>,
+    }
+
+    impl Listener {
+        pub fn bind(name: &str) -> io::Result<Self> {
+            let path: Vec<u16> = OsStr::new(&pipe_path(name))
+                .encode_wide()
+                .chain(Some(0))
+                .collect();
+            let next = create_instance(&path)?;
+
+            Ok(Self {
+                path,
+                next: Mutex::new(next),
+            })
+        }
+
+        pub(super) fn accept_stream(&self) -> io::Result<Stream> {
+            let mut next = self.next.lock().unwrap();
+            let handle = std::mem::replace(&mut *next, create_instance(&self.path)?);
+
+            match wait_overlapped(handle.0, |overlapped| unsafe {
+                ConnectNamedPipe(handle.0, overlapped)
+            }) {
+                Ok(_) => {}
+                // A client which connected before this call is reported as an error
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_CONNECTED as i32) => {}
+                Err(e) => return Err(e),
+            }
+
+            Ok(Stream {
+                handle: Arc::new(handle),
+            })
+        }
+    }
+
+    impl Drop for PipeHandle {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    fn create_instance(path: &[u16]) -> io::Result<PipeHandle> {
+        let handle = unsafe {
+            CreateNamedPipeW(
+                path.as_ptr(),
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_OVERLAPPED,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                std::ptr::null(),
+            )
+        };
+
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(PipeHandle(handle))
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::{
+        ffi::OsString,
+        io,
+        os::unix::{
+            fs::{DirBuilderExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::PathBuf,
+    };
+
+    pub type Stream = UnixStream;
+
+    /// Directory only the current user can enter: the session's `XDG_RUNTIME_DIR`, or one created
+    /// for the user in the temporary directory otherwise.
+    fn socket_dir() -> io::Result<PathBuf> {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+            return Ok(PathBuf::from(dir));
+        }
+
+        let user = std::env::var_os("USER").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Neither XDG_RUNTIME_DIR nor USER is set",
+            )
+        })?;
+
+        let mut dir_name = OsString::from("inter_process_channel-");
+        dir_name.push(user);
+        let dir = std::env::temp_dir().join(dir_name);
+
+        match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+
+        // It may have been there already, made by someone else
+        let metadata = std::fs::symlink_metadata(&dir)?;
+
+        if !metadata.is_dir() || metadata.permissions().mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is not private to the current user", dir.display()),
+            ));
+        }
+
+        Ok(dir)
+    }
+
+    fn socket_path(name: &str) -> io::Result<PathBuf> {
+        Ok(socket_dir()?.join(format!("{name}.sock")))
+    }
+
+    pub fn connect(name: &str) -> io::Result<Stream> {
+        UnixStream::connect(socket_path(name)?)
+    }
+
+    /// Removes the socket file when dropped.
+    pub struct Listener {
+        listener: UnixListener,
+        path: PathBuf,
+    }
+
+    impl Listener {
+        pub fn bind(name: &str) -> io::Result<Self> {
+            let path = socket_path(name)?;
+
+            // Left behind by a previous run which did not shut down cleanly
+            if UnixStream::connect(&path).is_err() {
+                let _ = std::fs::remove_file(&path);
+            }
+
+            Ok(Self {
+                listener: UnixListener::bind(&path)?,
+                path,
+            })
+        }
+
+        pub(super) fn accept_stream(&self) -> io::Result<Stream> {
+            Ok(self.listener.accept()?.0)
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct SomeNiceType {
+        value: u64,
+    }
+
+    #[test]
+    fn connect_and_listen_works() {
+        let name = format!("inter_process_channel_test_{}", std::process::id());
+        let listener = listen(&name).unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (mut sender, mut receiver) =
+                listener.accept::<SomeNiceType, SomeNiceType>().unwrap();
+            let request = receiver.recv().unwrap();
+            sender
+                .send(SomeNiceType {
+                    value: request.value + 1,
+                })
+                .unwrap();
+        });
+
+        let (mut sender, mut receiver) = connect::<SomeNiceType, SomeNiceType>(&name).unwrap();
+
+        sender.send(SomeNiceType { value: 41 }).unwrap();
+        assert_eq!(receiver.recv().unwrap().value, 42);
+
+        server.join().unwrap();
+    }
+}
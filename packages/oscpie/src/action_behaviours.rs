@@ -1,2 +1,19 @@
+pub mod custom;
 pub mod exec;
 pub mod key_stroke;
+#[cfg(feature = "osc")]
+pub mod osc_float;
+#[cfg(feature = "osc")]
+pub mod osc_send;
+pub mod system_volume;
+pub mod toggle;
+
+/// Registers the behaviours of optional features, which are looked up like custom ones.
+#[cfg_attr(not(feature = "osc"), allow(unused_variables))]
+pub fn register_optional(registry: &mut custom::BehaviourRegistry) {
+    #[cfg(feature = "osc")]
+    {
+        osc_send::register(registry);
+        osc_float::register(registry);
+    }
+}
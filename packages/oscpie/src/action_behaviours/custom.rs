@@ -0,0 +1,226 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::anyhow;
+
+use crate::{
+    config,
+    menu::{ActionContext, ActionResult, MenuActionBehaviour},
+    prelude::*,
+    resource::get_behaviours,
+};
+
+type Factory = Box<
+    dyn Fn(serde_json::Value) -> Result<Rc<RefCell<dyn MenuActionBehaviour<bool>>>> + Send + Sync,
+>;
+type SliderFactory = Box<
+    dyn Fn(serde_json::Value) -> Result<Rc<RefCell<dyn MenuActionBehaviour<f32>>>> + Send + Sync,
+>;
+
+/// Behaviours for action types which are not built in, by their `type` in the config.
+#[derive(Default)]
+pub struct BehaviourRegistry {
+    factories: HashMap<String, Factory>,
+    slider_factories: HashMap<String, SliderFactory>,
+}
+
+impl BehaviourRegistry {
+    /// A registry with the behaviours of the optional features which are built in.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::default();
+        crate::action_behaviours::register_optional(&mut registry);
+        registry
+    }
+
+    pub fn register<B, F>(&mut self, kind: impl Into<String>, factory: F)
+    where
+        B: MenuActionBehaviour<bool> + 'static,
+        F: Fn(serde_json::Value) -> Result<B> + Send + Sync + 'static,
+    {
+        let kind = kind.into();
+
+        let factory: Factory = Box::new(move |params| {
+            let behaviour: Rc<RefCell<dyn MenuActionBehaviour<bool>>> =
+                Rc::new(RefCell::new(factory(params)?));
+            Ok(behaviour)
+        });
+
+        if self.factories.insert(kind.clone(), factory).is_some() {
+            log::warn!("Behaviour for action type '{kind}' registered twice, the last one wins");
+        }
+    }
+
+    /// Like [`BehaviourRegistry::register`], for the `type` of a slider's target.
+    pub fn register_slider<B, F>(&mut self, kind: impl Into<String>, factory: F)
+    where
+        B: MenuActionBehaviour<f32> + 'static,
+        F: Fn(serde_json::Value) -> Result<B> + Send + Sync + 'static,
+    {
+        let kind = kind.into();
+
+        let factory: SliderFactory = Box::new(move |params| {
+            let behaviour: Rc<RefCell<dyn MenuActionBehaviour<f32>>> =
+                Rc::new(RefCell::new(factory(params)?));
+            Ok(behaviour)
+        });
+
+        if self
+            .slider_factories
+            .insert(kind.clone(), factory)
+            .is_some()
+        {
+            log::warn!("Behaviour for slider target '{kind}' registered twice, the last one wins");
+        }
+    }
+
+    fn create(
+        &self,
+        action: &config::types::CustomAction,
+    ) -> Result<Rc<RefCell<dyn MenuActionBehaviour<bool>>>> {
+        let factory = self.factories.get(&action.kind).ok_or_else(|| {
+            anyhow!(
+                "Unknown action type '{}', it may belong to a feature which is not built in",
+                action.kind
+            )
+        })?;
+
+        factory(serde_json::Value::Object(action.params.clone()))
+            .map_err(|e| anyhow!("Invalid '{}' action: {e}", action.kind))
+    }
+
+    pub(crate) fn create_slider(
+        &self,
+        kind: &str,
+        params: serde_json::Value,
+    ) -> Result<Rc<RefCell<dyn MenuActionBehaviour<f32>>>> {
+        let factory = self.slider_factories.get(kind).ok_or_else(|| {
+            anyhow!(
+                "Unknown slider target '{kind}', it may belong to a feature which is not built in"
+            )
+        })?;
+
+        factory(params).map_err(|e| anyhow!("Invalid '{kind}' slider: {e}"))
+    }
+}
+
+/// Creates the registered behaviour for a custom action. An action which cannot be created
+/// becomes one that fails when it runs, so that the rest of the menu keeps working.
+pub fn create(action: &config::types::CustomAction) -> Rc<RefCell<dyn MenuActionBehaviour<bool>>> {
+    match get_behaviours().create(action) {
+        Ok(behaviour) => behaviour,
+        Err(e) => {
+            log::warn!("{e}");
+
+            Rc::new(RefCell::new(UnavailableAction {
+                kind: action.kind.clone(),
+                reason: e.to_string(),
+            }))
+        }
+    }
+}
+
+/// Creates the registered behaviour for a slider target, or nothing when it cannot be created.
+pub fn create_slider(
+    kind: &str,
+    params: serde_json::Value,
+) -> Option<Rc<RefCell<dyn MenuActionBehaviour<f32>>>> {
+    match get_behaviours().create_slider(kind, params) {
+        Ok(behaviour) => Some(behaviour),
+        Err(e) => {
+            log::warn!("{e}");
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct UnavailableAction {
+    kind: String,
+    reason: String,
+}
+
+impl MenuActionBehaviour<bool> for UnavailableAction {
+    fn value(&self) -> bool {
+        false
+    }
+
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        ActionResult::Failure(self.reason.clone())
+    }
+
+    fn describe(&self) -> String {
+        format!("{} (unavailable)", self.kind)
+    }
+
+    fn validate(&self) -> Result<()> {
+        Err(anyhow!(self.reason.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Greet {
+        name: String,
+    }
+
+    impl MenuActionBehaviour<bool> for Greet {
+        fn value(&self) -> bool {
+            false
+        }
+
+        fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+            ActionResult::Success
+        }
+
+        fn describe(&self) -> String {
+            format!("Greet {}", self.name)
+        }
+    }
+
+    fn custom_action(json: &str) -> config::types::CustomAction {
+        let config::types::MenuItemAction::Custom(action) = serde_json::from_str(json).unwrap()
+        else {
+            panic!("Expected a custom action");
+        };
+
+        action
+    }
+
+    #[test]
+    fn test_builtin_types_are_not_custom() {
+        let action: config::types::MenuItemAction =
+            serde_json::from_str(r#"{ "type": "StartTutorial" }"#).unwrap();
+
+        assert!(matches!(
+            action,
+            config::types::MenuItemAction::StartTutorial
+        ));
+    }
+
+    #[test]
+    fn test_create() {
+        let mut registry = BehaviourRegistry::default();
+        registry.register("Greet", |params| {
+            Ok(Greet {
+                name: params["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("name is missing"))?
+                    .to_string(),
+            })
+        });
+
+        let greet = registry
+            .create(&custom_action(r#"{ "type": "Greet", "name": "world" }"#))
+            .unwrap();
+        assert_eq!(greet.borrow().describe(), "Greet world");
+
+        assert!(registry
+            .create(&custom_action(r#"{ "type": "Greet" }"#))
+            .is_err());
+        assert!(registry
+            .create(&custom_action(r#"{ "type": "Wave" }"#))
+            .is_err());
+    }
+}
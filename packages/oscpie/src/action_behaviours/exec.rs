@@ -1,6 +1,13 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
-use crate::menu::MenuActionBehaviour;
+use crate::{
+    menu::{ActionContext, ActionResult, MenuActionBehaviour},
+    prelude::*,
+    side_effects::{self, SideEffect},
+};
 
 #[derive(Debug, Clone)]
 pub struct ExecOneShotButtonAction {
@@ -24,15 +31,21 @@ impl MenuActionBehaviour<bool> for ExecOneShotButtonAction {
         *self.active.lock().unwrap()
     }
 
-    fn on_change(&mut self, _value: bool) {
-        std::process::Command::new(&self.program_path)
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        if side_effects::intercept(|| SideEffect::Exec {
+            program_path: self.program_path.clone(),
+            args: self.args.clone(),
+        }) {
+            return ActionResult::Success;
+        }
+
+        if let Err(e) = std::process::Command::new(&self.program_path)
             .args(&self.args)
             .spawn()
-            .map_err(|e| {
-                log::error!("Failed to execute program {}: {}", self.program_path, e);
-                e
-            })
-            .ok();
+        {
+            log::error!("Failed to execute program {}: {}", self.program_path, e);
+            return ActionResult::Failure(format!("Failed to execute {}: {e}", self.program_path));
+        }
 
         let active = self.active.clone();
 
@@ -44,5 +57,65 @@ impl MenuActionBehaviour<bool> for ExecOneShotButtonAction {
 
             *active.lock().unwrap() = false;
         });
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        format!("Execute {} {}", self.program_path, self.args.join(" "))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if find_program(&self.program_path).is_none() {
+            return Err(anyhow!("Program not found: {}", self.program_path));
+        }
+
+        Ok(())
+    }
+}
+
+fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+
+    let candidates = if cfg!(windows) && path.extension().is_none() {
+        vec![path.to_path_buf(), path.with_extension("exe")]
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    if let Some(found) = candidates.iter().find(|candidate| candidate.is_file()) {
+        return Some(found.clone());
+    }
+
+    if path.components().count() > 1 {
+        return None;
+    }
+
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths).find_map(|directory| {
+        candidates
+            .iter()
+            .map(|candidate| directory.join(candidate))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_missing_program() {
+        let action = ExecOneShotButtonAction::new("no_such_program_oscpie".to_string(), vec![]);
+
+        assert!(action.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_existing_program() {
+        let action = ExecOneShotButtonAction::new("Cargo.toml".to_string(), vec![]);
+
+        assert!(action.validate().is_ok());
     }
 }
@@ -1,13 +1,21 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
 use crate::prelude::*;
 
 use windows_sys::Win32::System::Diagnostics::Debug::{
     FORMAT_MESSAGE_ALLOCATE_BUFFER, FORMAT_MESSAGE_FROM_SYSTEM,
 };
 
-use crate::config;
+use crate::{
+    config,
+    side_effects::{self, SideEffect},
+};
 
 type ScanCode = u16;
 
+/// Keys which were sent down and not up again. They stay down in the OS until released.
+static HELD_KEYS: Mutex<BTreeSet<ScanCode>> = Mutex::new(BTreeSet::new());
+
 #[derive(Debug, Clone)]
 pub enum KeyAction {
     Down(ScanCode),
@@ -32,6 +40,18 @@ impl From<Vec<config::types::KeyAction>> for KeyStroke {
     }
 }
 
+impl KeyStroke {
+    /// Applies the key actions to the set of keys held down.
+    fn update_held(&self, held: &mut BTreeSet<ScanCode>) {
+        for key_action in &self.0 {
+            match key_action {
+                KeyAction::Down(scan_code) => held.insert(*scan_code),
+                KeyAction::Up(scan_code) => held.remove(scan_code),
+            };
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyStrokeButtonAction {
     key_stroke: KeyStroke,
@@ -43,18 +63,69 @@ impl KeyStrokeButtonAction {
     }
 }
 
+impl Drop for KeyStrokeButtonAction {
+    fn drop(&mut self) {
+        let mut pressed = BTreeSet::new();
+        self.key_stroke.update_held(&mut pressed);
+
+        release_held_keys_where(|scan_code| pressed.contains(&scan_code));
+    }
+}
+
 impl MenuActionBehaviour<bool> for KeyStrokeButtonAction {
     fn value(&self) -> bool {
         false
     }
 
-    fn on_change(&mut self, _value: bool) {
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        if side_effects::intercept(|| SideEffect::KeyStroke {
+            description: self.describe(),
+        }) {
+            return ActionResult::Success;
+        }
+
         if let Err(err) = send_keystroke(&self.key_stroke) {
             log::error!("Failed to send keystroke: {err}");
+            return ActionResult::Failure(format!("Failed to send keystroke: {err}"));
         }
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        let actions: Vec<String> = self
+            .key_stroke
+            .0
+            .iter()
+            .map(|key_action| match key_action {
+                KeyAction::Down(scan_code) => format!("Down({scan_code:#04x})"),
+                KeyAction::Up(scan_code) => format!("Up({scan_code:#04x})"),
+            })
+            .collect();
+
+        format!("Key stroke {}", actions.join(" "))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.key_stroke.0.is_empty() {
+            return Err(anyhow!("Key stroke is empty"));
+        }
+
+        for key_action in &self.key_stroke.0 {
+            let (KeyAction::Down(scan_code) | KeyAction::Up(scan_code)) = key_action;
+
+            if !VALID_SCAN_CODES.contains(scan_code) {
+                return Err(anyhow!("Invalid scan code: {scan_code:#04x}"));
+            }
+        }
+
+        Ok(())
     }
 }
 
+/// Make codes of scan code set 1.
+const VALID_SCAN_CODES: std::ops::RangeInclusive<ScanCode> = 0x01..=0x7f;
+
 fn send_keystroke(key_stroke: &KeyStroke) -> Result<()> {
     let mut input: Vec<windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT> = Vec::new();
 
@@ -64,9 +135,58 @@ fn send_keystroke(key_stroke: &KeyStroke) -> Result<()> {
 
     send_input(&input)?;
 
+    key_stroke.update_held(&mut HELD_KEYS.lock().unwrap());
+
     Ok(())
 }
 
+/// Sends key up for every key a key stroke left down, so that none stays stuck once the menu is
+/// closed or oscpie exits.
+pub fn release_held_keys() {
+    release_held_keys_where(|_| true);
+}
+
+fn release_held_keys_where(mut filter: impl FnMut(ScanCode) -> bool) {
+    let Ok(mut held) = HELD_KEYS.lock() else {
+        return;
+    };
+
+    let released: Vec<ScanCode> = held
+        .iter()
+        .copied()
+        .filter(|scan_code| filter(*scan_code))
+        .collect();
+
+    if released.is_empty() {
+        return;
+    }
+
+    log::info!("Releasing held keys: {released:02x?}");
+
+    let input: Vec<_> = released
+        .iter()
+        .map(|scan_code| key_action_to_input(&KeyAction::Up(*scan_code)))
+        .collect();
+
+    if let Err(e) = send_input(&input) {
+        log::error!("Failed to release held keys: {e}");
+        return;
+    }
+
+    for scan_code in released {
+        held.remove(&scan_code);
+    }
+}
+
+/// Releases held keys when dropped, including while unwinding from a panic.
+pub struct HeldKeysGuard;
+
+impl Drop for HeldKeysGuard {
+    fn drop(&mut self) {
+        release_held_keys();
+    }
+}
+
 fn key_action_to_input(
     key_action: &KeyAction,
 ) -> windows_sys::Win32::UI::Input::KeyboardAndMouse::INPUT {
@@ -150,3 +270,24 @@ fn get_last_error() -> String {
         log_string
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_held() {
+        let mut held = BTreeSet::new();
+
+        KeyStroke(vec![
+            KeyAction::Down(0x2a),
+            KeyAction::Down(0x1e),
+            KeyAction::Up(0x1e),
+        ])
+        .update_held(&mut held);
+        assert_eq!(held, BTreeSet::from([0x2a]));
+
+        KeyStroke(vec![KeyAction::Up(0x2a)]).update_held(&mut held);
+        assert!(held.is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::anyhow;
+
+use crate::{
+    action_behaviours::custom::BehaviourRegistry,
+    menu::{ActionContext, ActionResult, MenuActionBehaviour},
+    osc::{self, OscValue},
+    side_effects::{self, SideEffect},
+};
+
+/// Sets a float avatar parameter from a slider.
+#[derive(Debug, Clone)]
+pub struct OscFloatSliderAction {
+    parameter: String,
+    value: f32,
+}
+
+impl OscFloatSliderAction {
+    pub fn new(parameter: String) -> Self {
+        OscFloatSliderAction {
+            parameter,
+            value: 0.0,
+        }
+    }
+}
+
+/// Provides the `OscFloat` slider target.
+pub fn register(registry: &mut BehaviourRegistry) {
+    registry.register_slider("OscFloat", |params| {
+        let parameter = params["parameter"]
+            .as_str()
+            .ok_or_else(|| anyhow!("parameter is missing"))?;
+
+        Ok(OscFloatSliderAction::new(parameter.to_string()))
+    });
+}
+
+impl MenuActionBehaviour<f32> for OscFloatSliderAction {
+    /// The value reported by the game, or the last one sent when it has not reported any.
+    fn value(&self) -> f32 {
+        match osc::get_avatar_parameter(&self.parameter) {
+            Some(OscValue::Float(value)) => value,
+            _ => self.value,
+        }
+    }
+
+    fn on_change(&mut self, value: f32, _ctx: &mut ActionContext) -> ActionResult {
+        let osc_value = OscValue::Float(value);
+        let address = osc::avatar_parameter_address(&self.parameter);
+
+        let intercepted = side_effects::intercept(|| SideEffect::OscSend {
+            address: address.clone(),
+            value: osc_value,
+        });
+
+        if !intercepted {
+            let Some(sender) = osc::get_sender() else {
+                return ActionResult::Failure("OSC is not configured".to_string());
+            };
+
+            if let Err(e) = sender.send(&address, osc_value) {
+                log::error!("Failed to send OSC to {}: {e}", self.parameter);
+                return ActionResult::Failure(format!("Failed to send {}: {e}", self.parameter));
+            }
+        }
+
+        self.value = value;
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        format!("Slide {}", self.parameter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_change_sends_float() {
+        let mut action = OscFloatSliderAction::new("Volume".to_string());
+
+        let (result, effects) = side_effects::capture(|| {
+            action.on_change(0.25, &mut ActionContext::new(std::sync::mpsc::channel().0))
+        });
+
+        assert_eq!(result, ActionResult::Success);
+        assert_eq!(
+            effects,
+            vec![SideEffect::OscSend {
+                address: osc::avatar_parameter_address("Volume"),
+                value: OscValue::Float(0.25),
+            }]
+        );
+        assert!((action.value - 0.25).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = BehaviourRegistry::default();
+        register(&mut registry);
+
+        let slider = registry
+            .create_slider("OscFloat", serde_json::json!({ "parameter": "Volume" }))
+            .unwrap();
+        assert_eq!(slider.borrow().describe(), "Slide Volume");
+
+        assert!(registry
+            .create_slider("OscFloat", serde_json::json!({}))
+            .is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    action_behaviours::custom::BehaviourRegistry,
+    config,
+    menu::{ActionContext, ActionResult, MenuActionBehaviour},
+    osc::{self, OscValue},
+    side_effects::{self, SideEffect},
+};
+
+/// Sets an avatar parameter, or flips it between its value and off when `toggle` is set.
+#[derive(Debug, Clone)]
+pub struct OscSendButtonAction {
+    parameter: String,
+    value: OscValue,
+    toggle: bool,
+    is_on: bool,
+}
+
+impl OscSendButtonAction {
+    pub fn new(parameter: String, value: OscValue, toggle: bool) -> Self {
+        OscSendButtonAction {
+            parameter,
+            value,
+            toggle,
+            is_on: false,
+        }
+    }
+
+    /// Whether the parameter is currently on, preferring the state reported by the game.
+    fn is_on(&self) -> bool {
+        match osc::get_avatar_parameter(&self.parameter) {
+            Some(current) if self.toggle => is_on(current),
+            Some(current) => current == self.value,
+            None => self.toggle && self.is_on,
+        }
+    }
+
+    fn next_value(&self) -> OscValue {
+        if !self.toggle || !self.is_on() {
+            return self.value;
+        }
+
+        match self.value {
+            OscValue::Int(_) => OscValue::Int(0),
+            OscValue::Float(_) => OscValue::Float(0.0),
+            OscValue::Bool(value) => OscValue::Bool(!value),
+        }
+    }
+}
+
+/// Provides the `OscSend` action type.
+pub fn register(registry: &mut BehaviourRegistry) {
+    registry.register("OscSend", |params| {
+        let action: config::types::OscSendAction = serde_json::from_value(params)?;
+
+        Ok(OscSendButtonAction::new(
+            action.parameter,
+            action.value.into(),
+            action.toggle,
+        ))
+    });
+}
+
+impl MenuActionBehaviour<bool> for OscSendButtonAction {
+    fn value(&self) -> bool {
+        self.is_on()
+    }
+
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        let value = self.next_value();
+        let address = osc::avatar_parameter_address(&self.parameter);
+
+        let intercepted = side_effects::intercept(|| SideEffect::OscSend {
+            address: address.clone(),
+            value,
+        });
+
+        if !intercepted {
+            let Some(sender) = osc::get_sender() else {
+                return ActionResult::Failure("OSC is not configured".to_string());
+            };
+
+            if let Err(e) = sender.send(&address, value) {
+                log::error!("Failed to send OSC to {}: {e}", self.parameter);
+                return ActionResult::Failure(format!("Failed to send {}: {e}", self.parameter));
+            }
+        }
+
+        if self.toggle {
+            self.is_on = is_on(value);
+        }
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        if self.toggle {
+            format!("Toggle {} {:?}", self.parameter, self.value)
+        } else {
+            format!("Set {} {:?}", self.parameter, self.value)
+        }
+    }
+}
+
+fn is_on(value: OscValue) -> bool {
+    match value {
+        OscValue::Int(value) => value != 0,
+        OscValue::Float(value) => value != 0.0,
+        OscValue::Bool(value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_alternates_value() {
+        let mut action = OscSendButtonAction::new("Mode".to_string(), OscValue::Int(2), true);
+
+        assert_eq!(action.next_value(), OscValue::Int(2));
+
+        action.is_on = true;
+
+        assert_eq!(action.next_value(), OscValue::Int(0));
+    }
+}
@@ -0,0 +1,103 @@
+use windows::Win32::{
+    Media::Audio::{
+        eConsole, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator, MMDeviceEnumerator,
+    },
+    System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED},
+};
+
+use crate::{
+    menu::{ActionContext, ActionResult, MenuActionBehaviour},
+    prelude::*,
+    side_effects::{self, SideEffect},
+};
+
+/// Sets the master volume of the default playback device from a slider.
+#[derive(Debug, Default)]
+pub struct SystemVolumeSliderAction {
+    /// Volume of the device the last time it was read or set, for when it cannot be read.
+    volume: f32,
+}
+
+impl SystemVolumeSliderAction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MenuActionBehaviour<f32> for SystemVolumeSliderAction {
+    fn value(&self) -> f32 {
+        match endpoint_volume().and_then(|endpoint| {
+            // SAFETY: The endpoint is a valid interface pointer for the duration of the call
+            unsafe { endpoint.GetMasterVolumeLevelScalar() }
+                .map_err(|e| anyhow!("Failed to get the volume: {e}"))
+        }) {
+            Ok(volume) => volume,
+            Err(e) => {
+                log::debug!("{e}");
+                self.volume
+            }
+        }
+    }
+
+    fn on_change(&mut self, value: f32, _ctx: &mut ActionContext) -> ActionResult {
+        let volume = value.clamp(0.0, 1.0);
+
+        if !side_effects::intercept(|| SideEffect::SystemVolume { volume }) {
+            let result = endpoint_volume().and_then(|endpoint| {
+                // SAFETY: The endpoint is a valid interface pointer for the duration of the call
+                unsafe { endpoint.SetMasterVolumeLevelScalar(volume, std::ptr::null()) }
+                    .map_err(|e| anyhow!("Failed to set the volume: {e}"))
+            });
+
+            if let Err(e) = result {
+                log::error!("{e}");
+                return ActionResult::Failure(e.to_string());
+            }
+        }
+
+        self.volume = volume;
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        "System volume".to_string()
+    }
+}
+
+/// Volume control of the default playback device, which may change while oscpie runs.
+fn endpoint_volume() -> Result<IAudioEndpointVolume> {
+    // SAFETY: COM is initialized for this thread before any object is created. Initializing it
+    // again is harmless, so the result is not checked.
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| anyhow!("Failed to enumerate audio devices: {e}"))?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| anyhow!("No default playback device: {e}"))?;
+
+        device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| anyhow!("Failed to open the volume control: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_change_is_clamped() {
+        let mut action = SystemVolumeSliderAction::new();
+
+        let (result, effects) = side_effects::capture(|| {
+            action.on_change(1.5, &mut ActionContext::new(std::sync::mpsc::channel().0))
+        });
+
+        assert_eq!(result, ActionResult::Success);
+        assert_eq!(effects, vec![SideEffect::SystemVolume { volume: 1.0 }]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{cell::RefCell, collections::BTreeMap, path::Path, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{prelude::*, storage::Storage, versioned::Versioned};
+
+/// File name of the persisted toggle states, relative to the config directory.
+pub const TOGGLES_FILE: &str = "toggles.json";
+
+type Behaviour = Rc<RefCell<dyn MenuActionBehaviour<bool>>>;
+
+/// Runs `on` and `off` in turn, and shows whether it is on.
+#[derive(Debug, Clone)]
+pub struct ToggleButtonAction {
+    on: Behaviour,
+    off: Behaviour,
+    is_on: bool,
+    /// Name the state is kept under between runs.
+    persist: Option<String>,
+}
+
+impl ToggleButtonAction {
+    pub fn new(on: Behaviour, off: Behaviour, persist: Option<String>) -> Self {
+        let is_on = persist.as_deref().is_some_and(|name| {
+            load(&toggles_path())
+                .map(|states| states.get(name).copied().unwrap_or_default())
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to load the state of toggle '{name}': {e}");
+                    false
+                })
+        });
+
+        ToggleButtonAction {
+            on,
+            off,
+            is_on,
+            persist,
+        }
+    }
+}
+
+impl MenuActionBehaviour<bool> for ToggleButtonAction {
+    fn value(&self) -> bool {
+        self.is_on
+    }
+
+    fn on_change(&mut self, value: bool, ctx: &mut ActionContext) -> ActionResult {
+        let next = if self.is_on { &self.off } else { &self.on };
+
+        let result = next.borrow_mut().on_change(value, ctx);
+
+        if let ActionResult::Failure(_) = result {
+            return result;
+        }
+
+        self.is_on = !self.is_on;
+
+        if let Some(name) = &self.persist {
+            if let Err(e) = save_state(&toggles_path(), name, self.is_on) {
+                log::warn!("Failed to save the state of toggle '{name}': {e}");
+            }
+        }
+
+        result
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Toggle {} / {}",
+            self.on.borrow().describe(),
+            self.off.borrow().describe()
+        )
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.on.borrow().validate()?;
+        self.off.borrow().validate()
+    }
+}
+
+fn toggles_path() -> std::path::PathBuf {
+    resolve_path(crate::CONFIG_PATH, TOGGLES_FILE)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "toggles_version", content = "toggles")]
+enum TogglesFile {
+    V1(BTreeMap<String, bool>),
+}
+
+impl Versioned<u32> for TogglesFile {
+    fn version(&self) -> u32 {
+        match self {
+            TogglesFile::V1(_) => 1,
+        }
+    }
+}
+
+fn storage(path: &Path) -> Storage<TogglesFile> {
+    Storage::new(path, 1)
+}
+
+/// Loads the persisted states, none when nothing has been toggled yet.
+fn load(path: &Path) -> Result<BTreeMap<String, bool>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let TogglesFile::V1(states) = storage(path).read()?;
+
+    Ok(states)
+}
+
+fn save_state(path: &Path, name: &str, is_on: bool) -> Result<()> {
+    let mut states = load(path)?;
+    states.insert(name.to_string(), is_on);
+
+    storage(path).write(&TogglesFile::V1(states))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+    use crate::{
+        action_behaviours::key_stroke::{KeyStroke, KeyStrokeButtonAction},
+        config,
+        side_effects::{self, SideEffect},
+    };
+
+    fn key_stroke(scan_code: u16) -> Behaviour {
+        Rc::new(RefCell::new(KeyStrokeButtonAction::new(KeyStroke::from(
+            vec![config::types::KeyAction::Down(scan_code)],
+        ))))
+    }
+
+    #[test]
+    fn test_toggle_alternates() {
+        let mut toggle = ToggleButtonAction::new(key_stroke(0x1e), key_stroke(0x30), None);
+        let mut ctx = ActionContext::new(channel().0);
+
+        let ((), effects) = side_effects::capture(|| {
+            for _ in 0..3 {
+                toggle.on_change(true, &mut ctx);
+            }
+        });
+
+        assert!(toggle.value());
+        assert_eq!(
+            effects,
+            vec![
+                SideEffect::KeyStroke {
+                    description: "Key stroke Down(0x1e)".to_string()
+                },
+                SideEffect::KeyStroke {
+                    description: "Key stroke Down(0x30)".to_string()
+                },
+                SideEffect::KeyStroke {
+                    description: "Key stroke Down(0x1e)".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_state() {
+        let directory = std::env::temp_dir().join("oscpie_test_toggle_state");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(TOGGLES_FILE);
+
+        save_state(&path, "mute", true).unwrap();
+        save_state(&path, "hat", false).unwrap();
+
+        assert_eq!(
+            load(&path).unwrap(),
+            BTreeMap::from([("hat".to_string(), false), ("mute".to_string(), true)])
+        );
+    }
+}
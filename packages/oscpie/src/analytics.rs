@@ -0,0 +1,191 @@
+//! Opt-in usage statistics, kept next to the config and never sent anywhere.
+
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tiny_skia::{Color, Paint, Pixmap, Rect, Transform};
+
+use crate::{prelude::*, storage::Storage, versioned::Versioned};
+
+/// File name of the statistics, relative to the config directory.
+pub const ANALYTICS_FILE: &str = "analytics.json";
+
+/// Number of items listed in the report.
+const TOP_ITEMS: usize = 8;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Analytics {
+    pub opens: u64,
+    /// How often each action fired, by its description.
+    pub items: BTreeMap<String, u64>,
+    pub selections: u64,
+    /// Time from opening the menu, or from the previous selection, summed over all selections.
+    pub total_select_ms: u64,
+}
+
+impl Analytics {
+    pub fn record_open(&mut self) {
+        self.opens += 1;
+    }
+
+    pub fn record_selection(&mut self, action: String, time_to_select: Duration) {
+        *self.items.entry(action).or_default() += 1;
+        self.selections += 1;
+        self.total_select_ms += u64::try_from(time_to_select.as_millis()).unwrap_or(u64::MAX);
+    }
+
+    pub fn average_select_ms(&self) -> Option<u64> {
+        self.total_select_ms.checked_div(self.selections)
+    }
+
+    /// Most used actions first, ties in name order.
+    pub fn most_used(&self, count: usize) -> Vec<(&str, u64)> {
+        let mut items: Vec<_> = self
+            .items
+            .iter()
+            .map(|(action, uses)| (action.as_str(), *uses))
+            .collect();
+
+        items.sort_by(|a, b| b.1.cmp(&a.1));
+        items.truncate(count);
+        items
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "analytics_version", content = "analytics")]
+enum AnalyticsFile {
+    V1(Analytics),
+}
+
+impl Versioned<u32> for AnalyticsFile {
+    fn version(&self) -> u32 {
+        match self {
+            AnalyticsFile::V1(_) => 1,
+        }
+    }
+}
+
+fn storage(path: &Path) -> Storage<AnalyticsFile> {
+    Storage::new(path, 1)
+}
+
+/// Loads the statistics, starting from scratch when nothing has been recorded yet.
+pub fn load(path: &Path) -> Result<Analytics> {
+    if !path.exists() {
+        return Ok(Analytics::default());
+    }
+
+    let AnalyticsFile::V1(analytics) = storage(path).read()?;
+
+    Ok(analytics)
+}
+
+pub fn save(path: &Path, analytics: &Analytics) -> Result<()> {
+    storage(path).write(&AnalyticsFile::V1(analytics.clone()))
+}
+
+pub fn print_report(analytics: &Analytics) {
+    println!("Menu opened: {} times", analytics.opens);
+    println!("Actions fired: {}", analytics.selections);
+
+    if let Some(average) = analytics.average_select_ms() {
+        println!("Average time to select: {average}ms");
+    }
+
+    println!("Most used:");
+
+    for (rank, (action, uses)) in analytics.most_used(TOP_ITEMS).iter().enumerate() {
+        println!("  {}. {action}: {uses}", rank + 1);
+    }
+}
+
+/// Draws the most used actions as bars, in the order printed by [`print_report`].
+#[allow(clippy::cast_precision_loss)]
+pub fn render_report(analytics: &Analytics, pixmap: &mut Pixmap) {
+    let items = analytics.most_used(TOP_ITEMS);
+    let Some(max_uses) = items.first().map(|(_, uses)| *uses) else {
+        return;
+    };
+
+    let margin = 32.0;
+    let row_height = (pixmap.height() as f32 - margin * 2.0) / TOP_ITEMS as f32;
+    let max_width = pixmap.width() as f32 - margin * 2.0;
+
+    let mut paint = Paint::default();
+
+    for (row, (_, uses)) in items.iter().enumerate() {
+        let t = row as f32 / TOP_ITEMS as f32;
+        paint.set_color(Color::from_rgba(0.2 + 0.5 * t, 0.4 + 0.3 * t, 0.9, 1.0).unwrap());
+
+        let width = max_width * (*uses as f32 / max_uses as f32);
+
+        if let Some(rect) = Rect::from_xywh(
+            margin,
+            margin + row_height * row as f32,
+            width,
+            row_height * 0.7,
+        ) {
+            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+    }
+}
+
+/// Prints the statistics at `path` and renders them to `output` as a PNG when given.
+pub fn report(path: &Path, output: Option<&Path>) -> Result<()> {
+    let analytics = load(path)?;
+
+    print_report(&analytics);
+
+    if let Some(output) = output {
+        oscpie_ui::story::render_png(output, |pixmap| render_report(&analytics, pixmap))?;
+        println!("{}", output.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut analytics = Analytics::default();
+
+        analytics.record_open();
+        analytics.record_selection("a".to_string(), Duration::from_millis(300));
+        analytics.record_selection("b".to_string(), Duration::from_millis(100));
+        analytics.record_selection("b".to_string(), Duration::from_millis(200));
+
+        assert_eq!(analytics.opens, 1);
+        assert_eq!(analytics.average_select_ms(), Some(200));
+        assert_eq!(analytics.most_used(1), vec![("b", 2)]);
+    }
+
+    #[test]
+    fn test_average_without_selections() {
+        assert_eq!(Analytics::default().average_select_ms(), None);
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use oscpie_ui::story::story;
+
+    #[test]
+    fn story_analytics_report() {
+        let mut analytics = Analytics::default();
+
+        for (action, uses) in [("a", 12), ("b", 7), ("c", 3), ("d", 1)] {
+            for _ in 0..uses {
+                analytics.record_selection(action.to_string(), Duration::from_millis(500));
+            }
+        }
+
+        story("analytics_report", |pixmap| {
+            render_report(&analytics, pixmap)
+        });
+    }
+}
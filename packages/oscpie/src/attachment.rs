@@ -0,0 +1,116 @@
+//! Attaches the menu to a tracked device, so that SteamVR moves it along with the device instead of
+//! it being placed every frame.
+
+use anyhow::Result;
+use glam::Affine3A;
+
+use crate::{
+    config,
+    hand::Hand,
+    openvr::{self, Handle, Overlay, SystemInterface, TrackedControllerRole},
+    overlay_layers::OverlayLayer,
+};
+
+#[derive(Debug)]
+pub struct Attachment {
+    target: config::types::AttachmentTarget,
+    /// Placement relative to the target, already moved to the menu's layer.
+    offset: Affine3A,
+    /// Device the overlay is currently attached to.
+    attached_to: Option<u32>,
+}
+
+impl Attachment {
+    pub fn from_config(config: &config::types::AttachmentConfig) -> Self {
+        let offset = openvr::from_hmd_matrix34_t(openvr_sys::HmdMatrix34_t { m: config.offset });
+
+        Self {
+            target: config.target,
+            offset: OverlayLayer::Menu.offset(offset),
+            attached_to: None,
+        }
+    }
+
+    /// Controller role of the target, `None` for the headset.
+    fn role(&self, active: Hand) -> Option<TrackedControllerRole> {
+        match self.target {
+            config::types::AttachmentTarget::ActiveHand => Some(match active {
+                Hand::Left => TrackedControllerRole::LeftHand,
+                Hand::Right => TrackedControllerRole::RightHand,
+            }),
+            config::types::AttachmentTarget::LeftHand => Some(TrackedControllerRole::LeftHand),
+            config::types::AttachmentTarget::RightHand => Some(TrackedControllerRole::RightHand),
+            config::types::AttachmentTarget::Hmd => None,
+        }
+    }
+
+    /// Attaches `overlay` again when the target device changed, such as after a controller
+    /// reconnected or the active hand switched. Returns where the menu currently is in tracking
+    /// space, `None` while the target is not tracked.
+    pub fn update(
+        &mut self,
+        overlay: &Overlay,
+        system: &Handle<SystemInterface>,
+        active: Hand,
+    ) -> Result<Option<Affine3A>> {
+        let device_index = match self.role(active) {
+            Some(role) => system.controller_index(role),
+            None => Some(openvr::HMD_DEVICE_INDEX),
+        };
+
+        let Some(device_index) = device_index else {
+            return Ok(None);
+        };
+
+        if self.attached_to != Some(device_index) {
+            log::info!("Attaching the menu to device {device_index}");
+            overlay.set_overlay_transform_tracked_device_relative(device_index, self.offset)?;
+            self.attached_to = Some(device_index);
+        }
+
+        Ok(system
+            .device_pose(
+                device_index,
+                openvr::TrackingUniverseOrigin::RawAndUncalibrated,
+            )
+            .map(|pose| pose * self.offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3A;
+
+    use super::*;
+
+    #[test]
+    fn test_offset_is_row_major() {
+        let attachment = Attachment::from_config(&config::types::AttachmentConfig {
+            target: config::types::AttachmentTarget::Hmd,
+            offset: [
+                [1.0, 0.0, 0.0, 0.1],
+                [0.0, 1.0, 0.0, 0.2],
+                [0.0, 0.0, 1.0, -0.5],
+            ],
+        });
+
+        let expected =
+            OverlayLayer::Menu.offset(Affine3A::IDENTITY).translation + Vec3A::new(0.1, 0.2, -0.5);
+
+        assert!(attachment.offset.translation.abs_diff_eq(expected, 1e-6));
+    }
+
+    #[test]
+    fn test_active_hand_follows_the_selector() {
+        let attachment = Attachment::from_config(&config::types::AttachmentConfig::default());
+
+        assert_eq!(
+            attachment.role(Hand::Right),
+            Some(TrackedControllerRole::RightHand)
+        );
+        assert_eq!(
+            attachment.role(Hand::Left),
+            Some(TrackedControllerRole::LeftHand)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, OnceLock},
+    time::SystemTime,
+};
+
+use crate::menu::ActionResult;
+
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub time: SystemTime,
+    pub action: String,
+    pub result: ActionResult,
+}
+
+static ENTRIES: OnceLock<Mutex<VecDeque<AuditEntry>>> = OnceLock::new();
+
+impl std::fmt::Display for AuditEntry {
+    /// Such as `12s ago: Send /avatar/parameters/Wave: ok`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ago = self.time.elapsed().unwrap_or_default().as_secs();
+
+        match &self.result {
+            ActionResult::Success => write!(f, "{ago}s ago: {}: ok", self.action),
+            ActionResult::Failure(message) => {
+                write!(f, "{ago}s ago: {}: {message}", self.action)
+            }
+        }
+    }
+}
+
+/// Records an executed action. Only the latest entries are kept.
+pub fn record(action: String, result: ActionResult) {
+    match &result {
+        ActionResult::Success => log::info!("Action succeeded: {action}"),
+        ActionResult::Failure(message) => log::warn!("Action failed: {action}: {message}"),
+    }
+
+    let mut entries = ENTRIES
+        .get_or_init(|| Mutex::new(VecDeque::with_capacity(CAPACITY)))
+        .lock()
+        .unwrap();
+
+    if entries.len() == CAPACITY {
+        entries.pop_front();
+    }
+
+    entries.push_back(AuditEntry {
+        time: SystemTime::now(),
+        action,
+        result,
+    });
+}
+
+/// Returns the recorded entries, oldest first.
+pub fn entries() -> Vec<AuditEntry> {
+    ENTRIES.get().map_or_else(Vec::new, |entries| {
+        entries.lock().unwrap().iter().cloned().collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        record("test_record".to_string(), ActionResult::Success);
+
+        assert!(entries()
+            .iter()
+            .any(|entry| entry.action == "test_record" && entry.result == ActionResult::Success));
+    }
+
+    #[test]
+    fn test_display() {
+        let entry = AuditEntry {
+            time: SystemTime::now(),
+            action: "Press F5".to_string(),
+            result: ActionResult::Failure("no window".to_string()),
+        };
+
+        assert_eq!(entry.to_string(), "0s ago: Press F5: no window");
+    }
+}
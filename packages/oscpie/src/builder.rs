@@ -0,0 +1,67 @@
+use crate::{
+    action_behaviours::custom::BehaviourRegistry, menu::MenuActionBehaviour, prelude::*,
+    resource::BEHAVIOURS,
+};
+
+/// Runs oscpie with additions which are compiled in.
+///
+/// ```no_run
+/// # #[derive(Debug)]
+/// # struct Say;
+/// # impl oscpie::MenuActionBehaviour<bool> for Say {
+/// #     fn value(&self) -> bool { false }
+/// #     fn on_change(&mut self, _: bool, _: &mut oscpie::ActionContext) -> oscpie::ActionResult {
+/// #         oscpie::ActionResult::Success
+/// #     }
+/// # }
+/// oscpie::CoreBuilder::new()
+///     .register_behaviour("Say", |_params| Ok(Say))
+///     .run();
+/// ```
+pub struct CoreBuilder {
+    behaviours: BehaviourRegistry,
+}
+
+impl Default for CoreBuilder {
+    fn default() -> Self {
+        Self {
+            behaviours: BehaviourRegistry::with_builtins(),
+        }
+    }
+}
+
+impl CoreBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the action `type` `kind` in the config to behaviours created by `factory`.
+    ///
+    /// The factory gets the other fields of the action as a JSON object, e.g. to be read with
+    /// `serde_json::from_value`. Types of the config format itself take precedence over registered
+    /// ones, while behaviours of optional features such as `OscSend` can be replaced.
+    #[must_use]
+    pub fn register_behaviour<B, F>(mut self, kind: impl Into<String>, factory: F) -> Self
+    where
+        B: MenuActionBehaviour<bool> + 'static,
+        F: Fn(serde_json::Value) -> Result<B> + Send + Sync + 'static,
+    {
+        self.behaviours.register(kind, factory);
+        self
+    }
+
+    /// Runs the command given on the command line, the overlay by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called more than once, or after menus have been created.
+    pub fn run(self) {
+        assert!(
+            BEHAVIOURS.set(self.behaviours).is_ok(),
+            "CoreBuilder::run called more than once, or after menus have been created"
+        );
+
+        crate::run();
+    }
+}
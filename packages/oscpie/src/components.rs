@@ -1,3 +1,127 @@
+pub mod breadcrumb;
+pub mod cell_item;
+pub mod grid;
+pub mod item_interaction;
+pub mod menu_transition;
 pub mod pie_menu;
 pub mod pie_menu_item;
-pub mod sprite;
+pub mod pie_menu_slider_item;
+pub mod quick_bar;
+pub mod tutorial;
+
+use oscpie_ui::components::label;
+use tiny_skia::Pixmap;
+
+use crate::{prelude::*, theme::Theme};
+
+/// Pie menus with more items than this are shown as a grid, as the slices get too thin to hit.
+pub const MAX_PIE_ITEMS: usize = 16;
+
+/// Texture size in pixels which label sizes in the config are given for. Labels scale with the
+/// menu on larger textures.
+pub const REFERENCE_SIZE: f32 = 512.0;
+
+/// Radius of a pie menu filling a texture of `size` pixels.
+pub fn pie_radius(size: f32) -> f32 {
+    size * 0.5 * 0.9
+}
+
+/// Component for a menu, in the layout the menu asks for.
+pub enum MenuComponent {
+    Pie(pie_menu::PieMenuComponent),
+    QuickBar(quick_bar::QuickBarComponent),
+    Grid(grid::GridComponent),
+}
+
+impl MenuComponent {
+    /// Menu filling a square texture of `size` pixels.
+    pub fn new(menu: &Menu, action_context: &ActionContext, theme: Theme, size: f32) -> Self {
+        let center_x = size * 0.5;
+        let center_y = size * 0.5;
+
+        let layout = if menu.layout == MenuLayout::Pie
+            && menu.page_size.is_none()
+            && menu.items.len() > MAX_PIE_ITEMS
+        {
+            log::info!(
+                "Menu has {} items, more than fit a pie, showing it as a grid",
+                menu.items.len()
+            );
+            MenuLayout::Grid
+        } else {
+            menu.layout
+        };
+
+        match layout {
+            MenuLayout::Pie | MenuLayout::HalfCircle(_) => {
+                MenuComponent::Pie(pie_menu::PieMenuComponent::new(
+                    center_x,
+                    center_y,
+                    pie_radius(size),
+                    menu,
+                    action_context,
+                    theme,
+                ))
+            }
+            MenuLayout::QuickBar => MenuComponent::QuickBar(quick_bar::QuickBarComponent::new(
+                center_x,
+                center_y,
+                size * 0.9,
+                menu,
+                action_context,
+                theme,
+            )),
+            MenuLayout::Grid => MenuComponent::Grid(grid::GridComponent::new(
+                center_x,
+                center_y,
+                size * 0.9,
+                menu,
+                action_context,
+                theme,
+            )),
+        }
+    }
+
+    /// Angle which points at the item at `index`, in radians.
+    pub fn item_angle(&self, index: usize) -> Option<f32> {
+        match self {
+            MenuComponent::Pie(pie_menu) => pie_menu.item_angle(index),
+            MenuComponent::QuickBar(quick_bar) => quick_bar.item_angle(index),
+            MenuComponent::Grid(grid) => grid.item_angle(index),
+        }
+    }
+
+    /// Middle of the selected item in pixels.
+    pub fn selected_point(&self) -> Option<Vec2> {
+        match self {
+            MenuComponent::Pie(pie_menu) => pie_menu.selected_point(),
+            MenuComponent::QuickBar(quick_bar) => quick_bar.selected_point(),
+            MenuComponent::Grid(grid) => grid.selected_point(),
+        }
+    }
+
+    /// Where the breadcrumb of the menu stack goes so that it does not cover any item.
+    pub fn breadcrumb_props(&self) -> label::Props {
+        match self {
+            MenuComponent::Pie(pie_menu) => pie_menu.breadcrumb_props(),
+            MenuComponent::QuickBar(quick_bar) => quick_bar.breadcrumb_props(),
+            MenuComponent::Grid(grid) => grid.breadcrumb_props(),
+        }
+    }
+
+    pub fn update(&mut self, props: &pie_menu::Props) {
+        match self {
+            MenuComponent::Pie(pie_menu) => pie_menu.update(props),
+            MenuComponent::QuickBar(quick_bar) => quick_bar.update(props),
+            MenuComponent::Grid(grid) => grid.update(props),
+        }
+    }
+
+    pub fn render(&self, pixmap: &mut Pixmap) {
+        match self {
+            MenuComponent::Pie(pie_menu) => pie_menu.render(pixmap),
+            MenuComponent::QuickBar(quick_bar) => quick_bar.render(pixmap),
+            MenuComponent::Grid(grid) => grid.render(pixmap),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Names of the menus on the stack, for context in deep menu trees.
+
+use oscpie_ui::components::label::{self, LabelComponent};
+use tiny_skia::Pixmap;
+
+use crate::{resource::get_labels, theme::Theme};
+
+use super::REFERENCE_SIZE;
+
+const SEPARATOR: &str = " › ";
+
+pub struct BreadcrumbComponent {
+    /// Unset for the root menu, and while labels are hidden.
+    label: Option<LabelComponent>,
+}
+
+impl BreadcrumbComponent {
+    /// Breadcrumb for a menu filling a texture of `size` pixels.
+    pub fn new(names: &[&str], theme: Theme, size: f32) -> Self {
+        let label = get_labels().filter(|_| names.len() > 1).map(|labels| {
+            LabelComponent::new(
+                &labels.font,
+                &breadcrumb_text(names),
+                labels.size * 0.6 * size / REFERENCE_SIZE,
+                theme.label,
+            )
+        });
+
+        Self { label }
+    }
+
+    pub fn update(&mut self, props: &label::Props) {
+        if let Some(label) = &mut self.label {
+            label.update(props);
+        }
+    }
+
+    pub fn render(&self, pixmap: &mut Pixmap) {
+        if let Some(label) = &self.label {
+            label.render(pixmap);
+        }
+    }
+}
+
+fn breadcrumb_text(names: &[&str]) -> String {
+    names.join(SEPARATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breadcrumb_text() {
+        assert_eq!(breadcrumb_text(&["root"]), "root");
+        assert_eq!(
+            breadcrumb_text(&["root", "media", "volume"]),
+            "root › media › volume"
+        );
+    }
+
+    #[test]
+    fn test_root_menu_has_no_breadcrumb() {
+        let breadcrumb = BreadcrumbComponent::new(&["root"], Theme::default(), REFERENCE_SIZE);
+
+        assert!(breadcrumb.label.is_none());
+    }
+}
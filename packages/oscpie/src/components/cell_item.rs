@@ -0,0 +1,112 @@
+//! Item drawn in a rectangular cell, shared by the quick bar and the grid.
+
+use oscpie_ui::{
+    animation::Animated,
+    components::sprite::{self, SpriteComponent},
+    shapes, Component,
+};
+use tiny_skia::{Color, Pixmap};
+
+use crate::{
+    prelude::*,
+    resource::{get_animations, get_sprite_sheet},
+};
+
+use super::item_interaction::ItemInteraction;
+
+pub struct CellItem {
+    interaction: ItemInteraction,
+    icon_component: Option<SpriteComponent>,
+    icon_size: Animated,
+    spin_icon: SpriteComponent,
+    spin_icon_size: Animated,
+    x: f32,
+    y: f32,
+    extent: f32,
+}
+
+impl CellItem {
+    pub fn new(item: &MenuItem, action_context: &ActionContext) -> Self {
+        Self {
+            interaction: ItemInteraction::new(
+                item.action().clone(),
+                action_context.clone(),
+                item.keep_open(),
+            ),
+            icon_component: item.icon().and_then(|icon_sprite_id| {
+                let icon = get_sprite_sheet().unwrap().cutout(icon_sprite_id);
+
+                if icon.is_none() {
+                    log::warn!("Sprite '{icon_sprite_id}' not found");
+                }
+
+                icon.map(SpriteComponent::new)
+            }),
+            icon_size: Animated::new(0.0, get_animations().icon_grow),
+            spin_icon: SpriteComponent::new(
+                get_sprite_sheet()
+                    .map_or(Pixmap::new(1, 1).unwrap(), |ss| ss.cutout("spin").unwrap()),
+            ),
+            spin_icon_size: Animated::new(0.0, get_animations().spin_icon),
+            x: 0.0,
+            y: 0.0,
+            extent: 0.0,
+        }
+    }
+
+    /// Updates the item centered on `(x, y)` in a cell whose shorter side is `extent`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn update(&mut self, x: f32, y: f32, extent: f32, clicking: bool, selected: bool, dt: f32) {
+        self.x = x;
+        self.y = y;
+        self.extent = extent;
+
+        self.interaction.update(clicking, selected);
+
+        let icon_scale = self.icon_size.update(self.interaction.icon_scale(), dt);
+        let spin_icon_scale = self
+            .spin_icon_size
+            .update(self.interaction.indicator_scale(), dt);
+
+        if let Some(icon_component) = &mut self.icon_component {
+            icon_component.update(&sprite::Props {
+                x,
+                y,
+                width: extent * 0.6 * icon_scale,
+                height: extent * 0.6 * icon_scale,
+                rotate: 0.0,
+                layout_mode: sprite::LayoutMode::Center,
+            });
+        }
+
+        self.spin_icon.update(&sprite::Props {
+            x,
+            y,
+            width: extent * 0.9 * spin_icon_scale,
+            height: extent * 0.9 * spin_icon_scale,
+            rotate: ((get_time_since_start_secs_f64() as f32) % 360.0) * 360.0,
+            layout_mode: sprite::LayoutMode::Center,
+        });
+    }
+
+    pub fn render(&self, pixmap: &mut Pixmap) {
+        if let Some(icon_component) = &self.icon_component {
+            icon_component.render(pixmap);
+        }
+
+        if self.spin_icon_size.get_current() > 0.01 {
+            self.spin_icon.render(pixmap);
+        }
+
+        // Failure badge
+        if self.interaction.has_failed() {
+            shapes::fill_circle(
+                pixmap,
+                self.x + self.extent * 0.35,
+                self.y - self.extent * 0.35,
+                self.extent * 0.08,
+                Color::from_rgba8(230, 60, 60, 255),
+            );
+        }
+    }
+}
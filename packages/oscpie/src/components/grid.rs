@@ -0,0 +1,306 @@
+//! Menu drawn as a square grid of items, for menus with more items than a pie can fit. Items are
+//! selected by where the stick points and clicked like pie menu items.
+
+use std::f32::consts::PI;
+
+use oscpie_ui::{components::label, shapes};
+use tiny_skia::{Color, Pixmap};
+
+use crate::{hit_test, prelude::*, theme::Theme};
+
+use super::{cell_item::CellItem, pie_menu::Props};
+
+pub struct GridComponent {
+    center_x: f32,
+    center_y: f32,
+    size: f32,
+    columns: usize,
+    rows: usize,
+    items: Vec<CellItem>,
+    selected: Option<usize>,
+    /// Stick position, from `-1.0` to `1.0` on both axes.
+    input: Vec2,
+    contrast: f32,
+    time_delta: TimeDelta,
+    theme: Theme,
+}
+
+impl GridComponent {
+    pub fn new(
+        center_x: f32,
+        center_y: f32,
+        size: f32,
+        menu: &Menu,
+        action_context: &ActionContext,
+        theme: Theme,
+    ) -> Self {
+        let items: Vec<_> = menu
+            .items
+            .iter()
+            .map(|item| CellItem::new(item, action_context))
+            .collect();
+
+        let (columns, rows) = hit_test::grid_size(items.len());
+
+        Self {
+            center_x,
+            center_y,
+            size,
+            columns,
+            rows,
+            items,
+            selected: None,
+            input: Vec2::ZERO,
+            contrast: 0.0,
+            time_delta: TimeDelta::new(),
+            theme,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn cell_size(&self) -> Vec2 {
+        Vec2::new(
+            self.size / self.columns.max(1) as f32,
+            self.size / self.rows.max(1) as f32,
+        )
+    }
+
+    /// Center of the cell at `index`, from `-1.0` to `1.0` on both axes.
+    #[allow(clippy::cast_precision_loss)]
+    fn cell_center(&self, index: usize) -> Vec2 {
+        let column = (index % self.columns.max(1)) as f32;
+        let row = (index / self.columns.max(1)) as f32;
+
+        Vec2::new(
+            (column + 0.5) / self.columns.max(1) as f32 * 2.0 - 1.0,
+            (row + 0.5) / self.rows.max(1) as f32 * 2.0 - 1.0,
+        )
+    }
+
+    /// Angle which selects the item at `index` at full deflection. Cells surrounded by others are
+    /// only reached with a partial deflection, so there is no such angle for them.
+    pub fn item_angle(&self, index: usize) -> Option<f32> {
+        if index >= self.items.len() {
+            return None;
+        }
+
+        let center = self.cell_center(index);
+        let angle = center.y.atan2(center.x).rem_euclid(PI * 2.0);
+
+        (hit_test::polar_to_grid_cell(angle, 1.0, self.items.len()) == Some(index)).then_some(angle)
+    }
+
+    /// Middle of the selected item in pixels.
+    pub fn selected_point(&self) -> Option<Vec2> {
+        self.selected.map(|selected| {
+            Vec2::new(self.center_x, self.center_y) + self.cell_center(selected) * self.size * 0.5
+        })
+    }
+
+    /// Where the breadcrumb goes, above the grid.
+    pub fn breadcrumb_props(&self) -> label::Props {
+        label::Props {
+            x: self.center_x,
+            y: self.center_y - self.size * 0.5 - 12.0,
+            scale: 1.0,
+            max_width: self.size,
+        }
+    }
+
+    pub fn update(&mut self, props: &Props) {
+        let input = &props.pie_menu_input;
+
+        self.selected =
+            hit_test::polar_to_grid_cell(input.angle, input.magnitude, self.items.len());
+        self.input = Vec2::new(input.angle.cos(), input.angle.sin())
+            * input.magnitude.min(hit_test::OUTER_RADIUS);
+        self.contrast = props.contrast;
+
+        let clicking = input.click > 0.5 && self.selected.is_some();
+        let dt = self.time_delta.update_and_get_secs();
+        let cell_size = self.cell_size();
+
+        for index in 0..self.items.len() {
+            let center = self.cell_center(index) * self.size * 0.5;
+
+            self.items[index].update(
+                self.center_x + center.x,
+                self.center_y + center.y,
+                cell_size.x.min(cell_size.y),
+                clicking,
+                self.selected == Some(index),
+                dt,
+            );
+        }
+    }
+
+    pub fn render(&self, pixmap: &mut Pixmap) {
+        let left = self.center_x - self.size * 0.5;
+        let top = self.center_y - self.size * 0.5;
+        let cell_size = self.cell_size();
+
+        // Background
+        shapes::fill_rect(
+            pixmap,
+            left,
+            top,
+            self.size,
+            self.size,
+            self.theme.background_with_contrast(self.contrast),
+        );
+
+        // Selection
+        if let Some(selected) = self.selected {
+            let mut highlight = self.theme.highlight;
+            highlight.set_alpha(highlight.alpha() * 0.2);
+
+            let center = self.cell_center(selected) * self.size * 0.5;
+
+            shapes::fill_rect(
+                pixmap,
+                self.center_x + center.x - cell_size.x * 0.5,
+                self.center_y + center.y - cell_size.y * 0.5,
+                cell_size.x,
+                cell_size.y,
+                highlight,
+            );
+        }
+
+        // Separate lines
+        #[allow(clippy::cast_precision_loss)]
+        for column in 1..self.columns {
+            shapes::fill_rect(
+                pixmap,
+                left + cell_size.x * column as f32 - self.theme.separator_width * 0.5,
+                top,
+                self.theme.separator_width,
+                self.size,
+                self.theme.separator,
+            );
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        for row in 1..self.rows {
+            shapes::fill_rect(
+                pixmap,
+                left,
+                top + cell_size.y * row as f32 - self.theme.separator_width * 0.5,
+                self.size,
+                self.theme.separator_width,
+                self.theme.separator,
+            );
+        }
+
+        for item in &self.items {
+            item.render(pixmap);
+        }
+
+        // Stick
+        shapes::fill_circle(
+            pixmap,
+            self.center_x + self.input.x * self.size * 0.5,
+            self.center_y + self.input.y * self.size * 0.5,
+            12.0,
+            Color::from_rgba(0.5, 0.5, 0.55, 1.0).unwrap(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::menu::AppEventMenuActionBehaviour;
+
+    fn grid(count: usize, event_sender: std::sync::mpsc::Sender<AppEvent>) -> GridComponent {
+        let menu = Menu::new(
+            (0..count)
+                .map(|_| {
+                    MenuItem::new(
+                        MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+                            AppEventMenuActionBehaviour::new(AppEvent::StartTutorial),
+                        ))),
+                        None,
+                    )
+                })
+                .collect(),
+        );
+
+        GridComponent::new(
+            256.0,
+            256.0,
+            460.0,
+            &menu,
+            &ActionContext::new(event_sender),
+            Theme::default(),
+        )
+    }
+
+    #[test]
+    fn test_item_angle_selects_the_item() {
+        let grid = grid(9, std::sync::mpsc::channel().0);
+
+        for index in [0, 1, 2, 3, 5, 6, 7, 8] {
+            let angle = grid.item_angle(index).unwrap();
+
+            assert_eq!(
+                hit_test::polar_to_grid_cell(angle, 1.0, 9),
+                Some(index),
+                "item {index}"
+            );
+        }
+
+        // The middle cell is only reached with a partial deflection
+        assert_eq!(grid.item_angle(4), None);
+        assert_eq!(grid.item_angle(9), None);
+    }
+
+    #[test]
+    fn test_click_runs_the_selected_item() {
+        let (event_sender, event_receiver) = std::sync::mpsc::channel();
+        let mut grid = grid(9, event_sender);
+
+        for click in [0.0, 1.0, 0.0] {
+            grid.update(&Props::new(PieMenuInput::new(0.0, 0.2, click)));
+        }
+
+        assert_eq!(grid.selected, Some(4));
+        assert_eq!(
+            event_receiver
+                .try_iter()
+                .filter(|event| matches!(event, AppEvent::StartTutorial))
+                .count(),
+            1
+        );
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use oscpie_ui::story::story;
+
+    #[test]
+    fn story_grid_hover() {
+        story("grid_hover", |pixmap| {
+            let menu = Menu::new(
+                (0..20)
+                    .map(|_| MenuItem::new(MenuItemAction::Noop, None))
+                    .collect(),
+            );
+            let mut grid = GridComponent::new(
+                256.0,
+                256.0,
+                460.0,
+                &menu,
+                &ActionContext::new(std::sync::mpsc::channel().0),
+                Theme::default(),
+            );
+
+            let angle = grid.item_angle(3).unwrap();
+            grid.update(&Props::new(PieMenuInput::new(angle, 1.0, 0.0)));
+            grid.render(pixmap);
+        });
+    }
+}
@@ -0,0 +1,280 @@
+//! Hovering, clicking and running the action of a menu item, shared by every menu layout.
+
+use std::time::Instant;
+
+use crate::{audit, prelude::*};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateMachine {
+    Neutral, // To: Hovering, Pressing, PressingStartedInOutOfBounds
+    Hovering,
+    Pressing,
+    /// Pressed for long enough to run a hold action. Releasing it is not a click.
+    Held,
+    PressingStartedButOutOfBounds,
+    PressingStartedInOutOfBounds,
+    Clicked,
+}
+
+impl StateMachine {
+    pub fn update(&mut self, is_down: bool, is_hovering_self: bool) {
+        *self = match self {
+            StateMachine::Neutral => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedInOutOfBounds,
+                (true, true) => StateMachine::Pressing,
+            },
+            StateMachine::Hovering => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedInOutOfBounds,
+                (true, true) => StateMachine::Pressing,
+            },
+            StateMachine::Pressing => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Clicked,
+                (true, false) => StateMachine::PressingStartedButOutOfBounds,
+                (true, true) => StateMachine::Pressing,
+            },
+            StateMachine::Held => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedButOutOfBounds,
+                (true, true) => StateMachine::Held,
+            },
+            StateMachine::Clicked => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedInOutOfBounds,
+                (true, true) => StateMachine::Pressing,
+            },
+            StateMachine::PressingStartedButOutOfBounds => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedButOutOfBounds,
+                (true, true) => StateMachine::Pressing,
+            },
+            StateMachine::PressingStartedInOutOfBounds => match (is_down, is_hovering_self) {
+                (false, false) => StateMachine::Neutral,
+                (false, true) => StateMachine::Hovering,
+                (true, false) => StateMachine::PressingStartedInOutOfBounds,
+                (true, true) => StateMachine::PressingStartedInOutOfBounds,
+            },
+        };
+    }
+
+    /// Turns a press which has lasted long enough into a hold.
+    pub fn hold(&mut self) {
+        if *self == StateMachine::Pressing {
+            *self = StateMachine::Held;
+        }
+    }
+}
+
+/// Runs an item's action as it is hovered and clicked, and reports the result.
+pub struct ItemInteraction {
+    action: MenuItemAction,
+    action_context: ActionContext,
+    keep_open: Option<bool>,
+    last_result: Option<ActionResult>,
+    is_button_down: bool,
+    state_machine: StateMachine,
+    pressed_at: Option<Instant>,
+}
+
+impl ItemInteraction {
+    pub fn new(
+        action: MenuItemAction,
+        action_context: ActionContext,
+        keep_open: Option<bool>,
+    ) -> Self {
+        Self {
+            action,
+            action_context,
+            keep_open,
+            last_result: None,
+            is_button_down: false,
+            state_machine: StateMachine::Neutral,
+            pressed_at: None,
+        }
+    }
+
+    pub fn action(&self) -> &MenuItemAction {
+        &self.action
+    }
+
+    pub fn state(&self) -> &StateMachine {
+        &self.state_machine
+    }
+
+    pub fn has_failed(&self) -> bool {
+        matches!(self.last_result, Some(ActionResult::Failure(_)))
+    }
+
+    /// Scale the icon grows or shrinks to in the current state.
+    pub fn icon_scale(&self) -> f32 {
+        match self.state_machine {
+            StateMachine::Hovering | StateMachine::Clicked => 1.2,
+            StateMachine::Pressing | StateMachine::Held => 0.8,
+            _ => 1.0,
+        }
+    }
+
+    /// Scale of the indicator showing that the action is on, such as an enabled toggle.
+    pub fn indicator_scale(&self) -> f32 {
+        match &self.action {
+            MenuItemAction::Noop => 0.1,
+            MenuItemAction::Slider(behaviour) => behaviour.borrow().value(),
+            MenuItemAction::OneShotButton(behaviour)
+            | MenuItemAction::Button(behaviour)
+            | MenuItemAction::HoldButton(behaviour, _) => {
+                if behaviour.borrow().value() {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// How far a hold action is from running while it is pressed, from `0.0` to `1.0`.
+    pub fn hold_progress(&self) -> f32 {
+        match (&self.action, self.pressed_at) {
+            (MenuItemAction::HoldButton(_, hold), Some(pressed_at)) if !hold.is_zero() => {
+                (pressed_at.elapsed().as_secs_f32() / hold.as_secs_f32()).min(1.0)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Advances the state machine, and runs the action when the item is clicked, once it has been
+    /// held for hold buttons, or while it is pressed for buttons.
+    pub fn update(&mut self, is_down: bool, is_hovering: bool) {
+        self.state_machine.update(is_down, is_hovering);
+
+        if self.state_machine == StateMachine::Pressing {
+            self.pressed_at.get_or_insert_with(Instant::now);
+        } else {
+            self.pressed_at = None;
+        }
+
+        match self.action.clone() {
+            MenuItemAction::Noop | MenuItemAction::Slider(_) => {
+                // no op, sliders are adjusted by the component holding them
+            }
+            MenuItemAction::OneShotButton(behaviour) => {
+                if self.state_machine == StateMachine::Clicked {
+                    let result = behaviour
+                        .borrow_mut()
+                        .on_change(true, &mut self.action_context);
+
+                    self.report(&*behaviour.borrow(), result);
+                }
+            }
+            MenuItemAction::HoldButton(behaviour, hold) => {
+                let is_held_long_enough = self
+                    .pressed_at
+                    .is_some_and(|pressed_at| pressed_at.elapsed() >= hold);
+
+                if is_held_long_enough {
+                    self.state_machine.hold();
+                    self.pressed_at = None;
+
+                    let result = behaviour
+                        .borrow_mut()
+                        .on_change(true, &mut self.action_context);
+
+                    self.report(&*behaviour.borrow(), result);
+                } else if self.state_machine == StateMachine::Clicked {
+                    self.action_context
+                        .toast(Severity::Info, "Keep holding to run this item");
+                }
+            }
+            MenuItemAction::Button(behaviour) => {
+                let is_down = self.state_machine == StateMachine::Pressing;
+
+                let result = behaviour
+                    .borrow_mut()
+                    .on_change(is_down, &mut self.action_context);
+
+                // Runs every frame, so a failure is reported once until it changes
+                let is_new_failure =
+                    result != ActionResult::Success && self.last_result.as_ref() != Some(&result);
+
+                if is_down != self.is_button_down || is_new_failure {
+                    self.is_button_down = is_down;
+                    self.report(&*behaviour.borrow(), result);
+                }
+            }
+        }
+    }
+
+    fn report(&mut self, behaviour: &dyn MenuActionBehaviour<bool>, result: ActionResult) {
+        match &result {
+            ActionResult::Success => self.action_context.haptics(),
+            ActionResult::Failure(message) => {
+                self.action_context.toast(Severity::Error, message.clone());
+            }
+        }
+
+        let description = behaviour.describe();
+
+        if result == ActionResult::Success && !behaviour.is_navigation() {
+            self.action_context.emit(AppEvent::ActionFired {
+                action: description.clone(),
+                keep_open: self.keep_open,
+            });
+        }
+
+        audit::record(description, result.clone());
+
+        self.last_result = Some(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, sync::mpsc::channel};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Failing;
+
+    impl MenuActionBehaviour<bool> for Failing {
+        fn value(&self) -> bool {
+            false
+        }
+
+        fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+            ActionResult::Failure("Not connected".to_string())
+        }
+
+        fn describe(&self) -> String {
+            "Failing".to_string()
+        }
+    }
+
+    #[test]
+    fn test_failing_button_reports_once() {
+        let (sender, receiver) = channel();
+        let mut interaction = ItemInteraction::new(
+            MenuItemAction::Button(Rc::new(RefCell::new(Failing))),
+            ActionContext::new(sender),
+            None,
+        );
+
+        for _ in 0..10 {
+            interaction.update(false, true);
+        }
+
+        let toasts = receiver
+            .try_iter()
+            .filter(|event| matches!(event, AppEvent::Toast { .. }))
+            .count();
+
+        assert_eq!(toasts, 1);
+        assert!(interaction.has_failed());
+    }
+}
@@ -0,0 +1,138 @@
+//! Zoom between a menu and the next one, so that moving through sub menus keeps a sense of place.
+//! The outgoing menu shrinks into the item which was selected while the incoming menu grows out of
+//! it.
+
+use oscpie_ui::animation::Animated;
+use tiny_skia::{FilterQuality, Pixmap, PixmapPaint, Transform};
+
+use crate::{prelude::*, resource::get_animations};
+
+use super::MenuComponent;
+
+pub struct MenuTransitionComponent {
+    outgoing: MenuComponent,
+    /// Point the menus zoom around, the center of the target when `None`.
+    origin: Option<Vec2>,
+    progress: Animated,
+    time_delta: TimeDelta,
+}
+
+impl MenuTransitionComponent {
+    pub fn new(outgoing: MenuComponent, origin: Option<Vec2>) -> Self {
+        Self {
+            outgoing,
+            origin,
+            progress: Animated::new(0.0, get_animations().menu_transition),
+            time_delta: TimeDelta::new(),
+        }
+    }
+
+    pub fn update(&mut self) {
+        let dt = self.time_delta.update_and_get_secs();
+        self.progress.update(1.0, dt);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.progress.get_current() >= 0.99
+    }
+
+    /// Renders the outgoing menu and `incoming` at the current progress.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn render(&self, incoming: &MenuComponent, pixmap: &mut Pixmap) {
+        let origin = self.origin.unwrap_or(Vec2::new(
+            pixmap.width() as f32 * 0.5,
+            pixmap.height() as f32 * 0.5,
+        ));
+        let progress = self.progress.get_current().clamp(0.0, 1.0);
+
+        for (menu, scale) in [(&self.outgoing, 1.0 - progress), (incoming, progress)] {
+            if scale <= 0.01 {
+                continue;
+            }
+
+            let Some(mut layer) = Pixmap::new(pixmap.width(), pixmap.height()) else {
+                return;
+            };
+
+            menu.render(&mut layer);
+
+            let paint = PixmapPaint {
+                opacity: scale,
+                quality: FilterQuality::Bilinear,
+                ..PixmapPaint::default()
+            };
+
+            pixmap.draw_pixmap(
+                0,
+                0,
+                layer.as_ref(),
+                &paint,
+                Transform::from_scale(scale, scale)
+                    .post_translate(origin.x * (1.0 - scale), origin.y * (1.0 - scale)),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::REFERENCE_SIZE, theme::Theme};
+
+    fn menu_component() -> MenuComponent {
+        MenuComponent::new(
+            &Menu::new(vec![MenuItem::new(MenuItemAction::Noop, None)]),
+            &ActionContext::new(std::sync::mpsc::channel().0),
+            Theme::default(),
+            REFERENCE_SIZE,
+        )
+    }
+
+    #[test]
+    fn test_finishes_after_the_transition() {
+        let mut transition = MenuTransitionComponent::new(menu_component(), None);
+        assert!(!transition.is_done());
+
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        transition.update();
+
+        assert!(transition.is_done());
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use crate::{
+        components::{pie_menu, REFERENCE_SIZE},
+        theme::Theme,
+    };
+    use oscpie_ui::story::story;
+
+    #[test]
+    fn story_menu_transition() {
+        story("menu_transition", |pixmap| {
+            let action_context = ActionContext::new(std::sync::mpsc::channel().0);
+            let menu = |count| {
+                Menu::new(
+                    (0..count)
+                        .map(|_| MenuItem::new(MenuItemAction::Noop, None))
+                        .collect(),
+                )
+            };
+
+            let mut outgoing =
+                MenuComponent::new(&menu(4), &action_context, Theme::default(), REFERENCE_SIZE);
+            outgoing.update(&pie_menu::Props::new(PieMenuInput::new(0.5, 1.0, 0.0)));
+
+            let incoming =
+                MenuComponent::new(&menu(6), &action_context, Theme::default(), REFERENCE_SIZE);
+
+            let mut transition =
+                MenuTransitionComponent::new(outgoing, Some(Vec2::new(380.0, 320.0)));
+            transition.progress = Animated::new(0.5, get_animations().menu_transition);
+            transition.render(&incoming, pixmap);
+        });
+    }
+}
@@ -1,16 +1,103 @@
-use tiny_skia::{Pixmap, Transform};
+use std::{
+    cell::{Cell, RefCell},
+    f32::consts::{FRAC_PI_2, PI},
+    rc::Rc,
+};
 
-use crate::{component::Component, prelude::*, resource::get_sprite_sheet};
+use tiny_skia::Pixmap;
 
-use super::pie_menu_item;
+use oscpie_ui::{
+    components::label::{self, LabelComponent},
+    shapes, Component,
+};
+
+use crate::{
+    hit_test::Hysteresis,
+    layout,
+    prelude::*,
+    resource::{get_labels, get_sprite_sheet},
+    theme::Theme,
+};
+
+use super::{pie_menu_item, pie_menu_slider_item, pie_radius, REFERENCE_SIZE};
 
 pub struct Props {
-    pie_menu_input: PieMenuInput,
+    pub(super) pie_menu_input: PieMenuInput,
+    pub(super) contrast: f32,
+    pub(super) hysteresis: Hysteresis,
 }
 
 impl Props {
     pub fn new(pie_menu_input: PieMenuInput) -> Self {
-        Props { pie_menu_input }
+        Props {
+            pie_menu_input,
+            contrast: 0.0,
+            hysteresis: Hysteresis::default(),
+        }
+    }
+
+    pub fn hysteresis(mut self, hysteresis: Hysteresis) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Contrast from `0.0` (default look) to `1.0` (opaque and darker background).
+    pub fn contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast;
+        self
+    }
+}
+
+/// A slice of the pie, which is a slider for slider actions and a button otherwise.
+enum Item {
+    Button(pie_menu_item::PieMenuItemComponent),
+    Slider(pie_menu_slider_item::PieMenuSliderItemComponent),
+}
+
+impl Item {
+    fn middle_angle(&self) -> f32 {
+        match self {
+            Item::Button(item) => item.middle_angle(),
+            Item::Slider(item) => item.middle_angle(),
+        }
+    }
+
+    fn is_hovered(&self) -> bool {
+        match self {
+            Item::Button(item) => item.is_hovered(),
+            Item::Slider(item) => item.is_hovered(),
+        }
+    }
+}
+
+/// Action of the items which turn the page of a paginated menu.
+#[derive(Debug)]
+struct PageTurnBehaviour {
+    page_turn: Rc<Cell<isize>>,
+    delta: isize,
+}
+
+impl MenuActionBehaviour<bool> for PageTurnBehaviour {
+    fn value(&self) -> bool {
+        false
+    }
+
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        self.page_turn.set(self.delta);
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        if self.delta < 0 {
+            "Previous page".to_string()
+        } else {
+            "Next page".to_string()
+        }
+    }
+
+    fn is_navigation(&self) -> bool {
+        true
     }
 }
 
@@ -18,130 +105,402 @@ pub struct PieMenuComponent {
     center_x: f32,
     center_y: f32,
     radius: f32,
-    items: Vec<pie_menu_item::PieMenuItemComponent>,
+    menu: Menu,
+    action_context: ActionContext,
+    /// Page shown out of [`Self::page_count`].
+    page: usize,
+    /// Pages to turn by, set by the page turn items and applied on the next update.
+    page_turn: Rc<Cell<isize>>,
+    items: Vec<Item>,
+    /// Part of the circle the menu covers, or `None` for the full circle.
+    span: Option<(f32, f32)>,
+    /// Label of each item shown in the center while it is hovered.
+    center_labels: Vec<Option<LabelComponent>>,
+    hovered: Option<usize>,
     input_angle: f32,
     input_magnitude: f32,
+    contrast: f32,
+    theme: Theme,
 }
 
 impl PieMenuComponent {
-    pub fn new(center_x: f32, center_y: f32, radius: f32, menu: &Menu) -> Self {
-        let item_count = menu.items.len();
+    pub fn new(
+        center_x: f32,
+        center_y: f32,
+        radius: f32,
+        menu: &Menu,
+        action_context: &ActionContext,
+        theme: Theme,
+    ) -> Self {
+        let mut pie_menu = Self {
+            center_x,
+            center_y,
+            radius,
+            menu: menu.clone(),
+            action_context: action_context.clone(),
+            page: 0,
+            page_turn: Rc::new(Cell::new(0)),
+            items: vec![],
+            span: None,
+            center_labels: vec![],
+            hovered: None,
+            input_angle: 0.0,
+            input_magnitude: 0.0,
+            contrast: 0.0,
+            theme,
+        };
+
+        pie_menu.show_page(0);
+        pie_menu
+    }
+
+    /// Number of pages the menu is split into, which is `1` unless it has more items than its page
+    /// size.
+    pub fn page_count(&self) -> usize {
+        match self.menu.page_size {
+            Some(page_size) if self.menu.items.len() > page_size => {
+                self.menu.items.len().div_ceil(page_size.max(1))
+            }
+            _ => 1,
+        }
+    }
+
+    /// Items of `page`, with items to turn to the neighbouring pages around them.
+    fn page_items(&self, page: usize) -> Vec<MenuItem> {
+        let page_count = self.page_count();
 
-        let items = menu
+        if page_count == 1 {
+            return self.menu.items.clone();
+        }
+
+        let page_size = self.menu.page_size.unwrap_or(1).max(1);
+        let mut items: Vec<MenuItem> = self
+            .menu
             .items
             .iter()
-            .enumerate()
-            .map(|(i, item)| {
-                let start_angle = (i as f32 / item_count as f32) * 2.0 * std::f32::consts::PI;
-                let end_angle = ((i + 1) as f32 / item_count as f32) * 2.0 * std::f32::consts::PI;
-
-                pie_menu_item::PieMenuItemComponent::new(
-                    center_x,
-                    center_y,
-                    radius,
-                    start_angle,
-                    end_angle,
-                    item.action().clone(),
-                    item.icon().map(|icon_sprite_id| {
-                        get_sprite_sheet().unwrap().cutout(icon_sprite_id).unwrap()
-                    }), // FIXME: Not testable
+            .skip(page * page_size)
+            .take(page_size)
+            .cloned()
+            .collect();
+
+        let page_turn_item = |delta: isize, label: &str| {
+            MenuItem::new(
+                MenuItemAction::OneShotButton(Rc::new(RefCell::new(PageTurnBehaviour {
+                    page_turn: self.page_turn.clone(),
+                    delta,
+                }))),
+                None,
+            )
+            .with_label(label)
+        };
+
+        if page > 0 {
+            items.insert(0, page_turn_item(-1, "Previous page"));
+        }
+
+        if page + 1 < page_count {
+            items.push(page_turn_item(1, "Next page"));
+        }
+
+        items
+    }
+
+    /// Replaces the items with those of `page`.
+    fn show_page(&mut self, page: usize) {
+        let page = page.min(self.page_count() - 1);
+        let items = self.page_items(page);
+
+        let (center_x, center_y, radius, theme) =
+            (self.center_x, self.center_y, self.radius, self.theme);
+        let action_context = &self.action_context;
+
+        let (slices, span) = match self.menu.layout {
+            MenuLayout::HalfCircle(towards) => {
+                let start = towards.angle() - FRAC_PI_2;
+
+                (
+                    layout::half_circle_slice_angles(towards, items.len()),
+                    Some((start, start + PI)),
                 )
+            }
+            _ => {
+                let anchors: Vec<_> = items.iter().map(MenuItem::anchor).collect();
+
+                (layout::slice_angles(self.menu.rotation, &anchors), None)
+            }
+        };
+
+        let label = |item: &MenuItem, scale: f32| {
+            let labels = get_labels()?;
+
+            Some(LabelComponent::new(
+                &labels.font,
+                item.label()?,
+                labels.size * scale * radius / pie_radius(REFERENCE_SIZE),
+                theme.label,
+            ))
+        };
+
+        self.items = items
+            .iter()
+            .zip(slices)
+            .map(|(item, (start_angle, end_angle))| {
+                let icon = item.icon().and_then(|icon_sprite_id| {
+                    let icon = get_sprite_sheet().unwrap().cutout(icon_sprite_id);
+
+                    if icon.is_none() {
+                        log::warn!("Sprite '{icon_sprite_id}' not found");
+                    }
+
+                    icon
+                }); // FIXME: Not testable
+
+                match item.action() {
+                    MenuItemAction::Slider(behaviour) => {
+                        let slider = pie_menu_slider_item::PieMenuSliderItemComponent::new(
+                            center_x,
+                            center_y,
+                            radius,
+                            (start_angle, end_angle),
+                            behaviour.clone(),
+                            action_context.clone(),
+                            icon,
+                            theme,
+                        );
+
+                        Item::Slider(match label(item, 1.0) {
+                            Some(label) => slider.with_label(label),
+                            None => slider,
+                        })
+                    }
+                    action => {
+                        let button = pie_menu_item::PieMenuItemComponent::new(
+                            center_x,
+                            center_y,
+                            radius,
+                            start_angle,
+                            end_angle,
+                            action.clone(),
+                            action_context.clone(),
+                            item.keep_open(),
+                            icon,
+                            theme,
+                        );
+
+                        Item::Button(match label(item, 1.0) {
+                            Some(label) => button.with_label(label),
+                            None => button,
+                        })
+                    }
+                }
             })
             .collect();
 
-        Self {
-            center_x,
-            center_y,
-            radius,
-            items,
-            input_angle: 0.0,
-            input_magnitude: 0.0,
+        self.center_labels = items.iter().map(|item| label(item, 1.5)).collect();
+        self.span = span;
+        self.page = page;
+        self.hovered = None;
+    }
+
+    /// Angle in the middle of the item at `index`, in radians.
+    pub fn item_angle(&self, index: usize) -> Option<f32> {
+        self.items.get(index).map(Item::middle_angle)
+    }
+
+    /// Middle of the hovered item in pixels.
+    pub fn selected_point(&self) -> Option<Vec2> {
+        let angle = self.item_angle(self.hovered?)?;
+        let distance = self.radius * (1.0 + self.theme.center_radius) * 0.5;
+
+        Some(Vec2::new(self.center_x, self.center_y) + Vec2::from_angle(angle) * distance)
+    }
+
+    /// Where the breadcrumb goes, under the label of the hovered item in the center.
+    pub fn breadcrumb_props(&self) -> label::Props {
+        let center_radius = self.radius * self.theme.center_radius;
+
+        label::Props {
+            x: self.center_x,
+            y: self.center_y + center_radius * 0.6,
+            scale: 1.0,
+            max_width: center_radius * 1.6,
         }
     }
 
     pub fn update(&mut self, props: &Props) {
         self.input_angle = props.pie_menu_input.angle;
         self.input_magnitude = props.pie_menu_input.magnitude;
+        self.contrast = props.contrast;
+
+        let item_props =
+            pie_menu_item::Props::new(&props.pie_menu_input).hysteresis(props.hysteresis);
 
         for item in &mut self.items {
-            item.update(&pie_menu_item::Props::new(&props.pie_menu_input));
+            match item {
+                Item::Button(item) => item.update(&item_props),
+                Item::Slider(item) => item.update(&item_props),
+            }
+        }
+
+        self.hovered = self.items.iter().position(Item::is_hovered);
+
+        if let Some(Some(center_label)) = self
+            .hovered
+            .and_then(|hovered| self.center_labels.get_mut(hovered))
+        {
+            center_label.update(&label::Props {
+                x: self.center_x,
+                y: self.center_y,
+                scale: 1.0,
+                max_width: self.radius * self.theme.center_radius * 1.8,
+            });
+        }
+
+        let page_turn = self.page_turn.replace(0);
+
+        if page_turn != 0 {
+            self.show_page(self.page.saturating_add_signed(page_turn));
         }
     }
 
     pub fn render(&self, pixmap: &mut Pixmap) {
         // Background
-        {
-            let mut paint = default_paint();
-            paint.set_color(tiny_skia::Color::from_rgba(0.1, 0.1, 0.2, 0.8).unwrap());
-
-            let path =
-                tiny_skia::PathBuilder::from_circle(self.center_x, self.center_y, self.radius)
-                    .unwrap();
-
-            pixmap.fill_path(
-                &path,
-                &paint,
-                tiny_skia::FillRule::Winding,
-                Transform::from_translate(0.0, 0.0),
-                None,
-            );
-        }
+        self.fill_disc(
+            pixmap,
+            self.radius,
+            self.theme.background_with_contrast(self.contrast),
+        );
 
         // Items
         for item in &self.items {
-            item.render(pixmap);
+            match item {
+                Item::Button(item) => item.render(pixmap),
+                Item::Slider(item) => item.render(pixmap),
+            }
         }
 
         // Center
-        {
-            let mut paint = default_paint();
-            paint.set_color(tiny_skia::Color::from_rgba(0.1, 0.1, 0.2, 1.0).unwrap());
+        self.fill_disc(
+            pixmap,
+            self.radius * self.theme.center_radius,
+            self.theme.center,
+        );
 
-            let path = tiny_skia::PathBuilder::from_circle(
-                self.center_x,
-                self.center_y,
-                self.radius * 0.3,
-            )
-            .unwrap();
+        // Stick
+        {
+            let x = self.input_angle.cos() * self.input_magnitude * self.radius * 0.25;
+            let y = self.input_angle.sin() * self.input_magnitude * self.radius * 0.25;
 
-            pixmap.fill_path(
-                &path,
-                &paint,
-                tiny_skia::FillRule::Winding,
-                Transform::from_translate(0.0, 0.0),
-                None,
+            shapes::fill_circle(
+                pixmap,
+                self.center_x + x,
+                self.center_y + y,
+                50.0,
+                tiny_skia::Color::from_rgba(0.5, 0.5, 0.55, 1.0).unwrap(),
             );
         }
 
-        // Stick
+        // Label of the hovered item, over the stick to stay readable
+        if let Some(Some(center_label)) = self
+            .hovered
+            .and_then(|hovered| self.center_labels.get(hovered))
         {
-            let mut paint = default_paint();
-            paint.set_color(tiny_skia::Color::from_rgba(0.5, 0.5, 0.55, 1.0).unwrap());
+            center_label.render(pixmap);
+        }
+    }
 
-            let x = self.input_angle.cos() * self.input_magnitude * self.radius * 0.25;
-            let y = self.input_angle.sin() * self.input_magnitude * self.radius * 0.25;
+    /// Fills the part of the disc of `radius` which the menu covers.
+    fn fill_disc(&self, pixmap: &mut Pixmap, radius: f32, color: tiny_skia::Color) {
+        match self.span {
+            Some((start_angle, end_angle)) => shapes::fill_arc(
+                pixmap,
+                self.center_x,
+                self.center_y,
+                0.0,
+                radius,
+                start_angle,
+                end_angle,
+                color,
+            ),
+            None => shapes::fill_circle(pixmap, self.center_x, self.center_y, radius, color),
+        }
+    }
+}
 
-            let path =
-                tiny_skia::PathBuilder::from_circle(self.center_x + x, self.center_y + y, 50.0);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            pixmap.fill_path(
-                &path.unwrap(),
-                &paint,
-                tiny_skia::FillRule::Winding,
-                Transform::from_translate(0.0, 0.0),
-                None,
-            );
+    fn paginated_pie_menu(count: usize, page_size: usize) -> PieMenuComponent {
+        let mut menu = Menu::new(
+            (0..count)
+                .map(|_| MenuItem::new(MenuItemAction::Noop, None))
+                .collect(),
+        );
+        menu.page_size = Some(page_size);
+
+        PieMenuComponent::new(
+            256.0,
+            256.0,
+            256.0 * 0.9,
+            &menu,
+            &ActionContext::new(std::sync::mpsc::channel().0),
+            Theme::default(),
+        )
+    }
+
+    fn click(pie_menu: &mut PieMenuComponent, index: usize) {
+        let angle = pie_menu.item_angle(index).unwrap();
+
+        for click in [0.0, 1.0, 0.0] {
+            pie_menu.update(&Props::new(PieMenuInput::new(angle, 1.0, click)));
         }
     }
+
+    #[test]
+    fn test_small_menu_is_not_paginated() {
+        let pie_menu = paginated_pie_menu(4, 4);
+
+        assert_eq!(pie_menu.page_count(), 1);
+        assert_eq!(pie_menu.items.len(), 4);
+    }
+
+    #[test]
+    fn test_pages_have_page_turn_items() {
+        let mut pie_menu = paginated_pie_menu(10, 4);
+        assert_eq!(pie_menu.page_count(), 3);
+
+        // First page: 4 items and next
+        assert_eq!(pie_menu.items.len(), 5);
+
+        click(&mut pie_menu, 4);
+        assert_eq!(pie_menu.page, 1);
+
+        // Middle page: previous, 4 items and next
+        assert_eq!(pie_menu.items.len(), 6);
+
+        click(&mut pie_menu, 5);
+        assert_eq!(pie_menu.page, 2);
+
+        // Last page: previous and the remaining 2 items
+        assert_eq!(pie_menu.items.len(), 3);
+
+        click(&mut pie_menu, 0);
+        assert_eq!(pie_menu.page, 1);
+    }
 }
 
 #[cfg(test)]
 mod stories {
     pub use super::*;
     pub use crate::prelude::*;
-    use crate::story::story;
+    use oscpie_ui::story::story;
+
+    fn pie_menu(theme: Theme) -> PieMenuComponent {
+        pie_menu_with_layout(MenuLayout::Pie, theme)
+    }
 
-    fn pie_menu() -> PieMenuComponent {
+    fn pie_menu_with_layout(layout: MenuLayout, theme: Theme) -> PieMenuComponent {
         let center_x = 256.0;
         let center_y = 256.0;
         let radius = 256.0 * 0.9;
@@ -149,22 +508,28 @@ mod stories {
         let mut icon = Pixmap::new(128, 128).unwrap();
         icon.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 255));
 
-        let menu = Menu {
-            items: vec![
-                MenuItem::new(MenuItemAction::Noop, None),
-                MenuItem::new(MenuItemAction::Noop, None),
-                MenuItem::new(MenuItemAction::Noop, None),
-                MenuItem::new(MenuItemAction::Noop, None),
-            ],
-        };
+        let mut menu = Menu::new(vec![
+            MenuItem::new(MenuItemAction::Noop, None),
+            MenuItem::new(MenuItemAction::Noop, None),
+            MenuItem::new(MenuItemAction::Noop, None),
+            MenuItem::new(MenuItemAction::Noop, None),
+        ]);
+        menu.layout = layout;
 
-        PieMenuComponent::new(center_x, center_y, radius, &menu)
+        PieMenuComponent::new(
+            center_x,
+            center_y,
+            radius,
+            &menu,
+            &ActionContext::new(std::sync::mpsc::channel().0),
+            theme,
+        )
     }
 
     #[test]
     fn story_pie_menu() {
         story("pie_menu", |pixmap| {
-            let mut pie_menu = pie_menu();
+            let mut pie_menu = pie_menu(Theme::default());
             pie_menu.update(&Props::new(PieMenuInput::new(0.1, 1.0, 0.0)));
             pie_menu.render(pixmap);
         });
@@ -173,16 +538,52 @@ mod stories {
     #[test]
     fn story_pie_menu_hover() {
         story("pie_menu_hover", |pixmap| {
-            let mut pie_menu = pie_menu();
+            let mut pie_menu = pie_menu(Theme::default());
             pie_menu.update(&Props::new(PieMenuInput::new(0.1, 1.0, 0.0)));
             pie_menu.render(pixmap);
         });
     }
 
+    #[test]
+    fn story_pie_menu_high_contrast() {
+        story("pie_menu_high_contrast", |pixmap| {
+            let mut pie_menu = pie_menu(Theme::default());
+            pie_menu.update(&Props::new(PieMenuInput::new(0.1, 1.0, 0.0)).contrast(1.0));
+            pie_menu.render(pixmap);
+        });
+    }
+
+    #[test]
+    fn story_pie_menu_themes() {
+        for (name, theme) in [
+            ("pie_menu_light", Theme::light()),
+            ("pie_menu_theme_high_contrast", Theme::high_contrast()),
+        ] {
+            story(name, |pixmap| {
+                let mut pie_menu = pie_menu(theme);
+                pie_menu.update(&Props::new(PieMenuInput::new(0.1, 1.0, 0.0)));
+                pie_menu.render(pixmap);
+            });
+        }
+    }
+
+    #[test]
+    fn story_pie_menu_half_circle() {
+        story("pie_menu_half_circle", |pixmap| {
+            let mut pie_menu = pie_menu_with_layout(
+                MenuLayout::HalfCircle(layout::Cardinal::North),
+                Theme::default(),
+            );
+            let angle = pie_menu.item_angle(1).unwrap();
+            pie_menu.update(&Props::new(PieMenuInput::new(angle, 1.0, 0.0)));
+            pie_menu.render(pixmap);
+        });
+    }
+
     #[test]
     fn story_pie_menu_click() {
         story("pie_menu_click", |pixmap| {
-            let mut pie_menu = pie_menu();
+            let mut pie_menu = pie_menu(Theme::default());
             pie_menu.update(&Props::new(PieMenuInput::new(0.1, 1.0, 1.0)));
             pie_menu.render(pixmap);
         });
@@ -1,70 +1,38 @@
 use crate::prelude::*;
-use crate::resource::get_sprite_sheet;
-use crate::{component::Component, debug::rt_debug};
-use tiny_skia::{Pixmap, Transform};
-
-use super::sprite::{self, SpriteComponent};
+use crate::resource::{get_animations, get_sprite_sheet};
+use crate::theme::Theme;
+use crate::{debug::rt_debug, hit_test::Hysteresis};
+use oscpie_ui::{
+    animation::Animated,
+    components::{
+        label::{self, LabelComponent},
+        sprite::{self, SpriteComponent},
+    },
+    shapes, Component,
+};
+use tiny_skia::Pixmap;
+
+use super::item_interaction::{ItemInteraction, StateMachine};
+
+/// Alpha of the hover highlight relative to the theme's highlight color.
+const HIGHLIGHT_ALPHA: f32 = 0.2;
 
 pub struct Props<'a> {
     pub pie_menu_input: &'a PieMenuInput,
+    pub hysteresis: Hysteresis,
 }
 
 impl<'a> Props<'a> {
     pub fn new(pie_menu_input: &'a PieMenuInput) -> Self {
-        Props { pie_menu_input }
+        Props {
+            pie_menu_input,
+            hysteresis: Hysteresis::default(),
+        }
     }
-}
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum StateMachine {
-    Neutral, // To: Hovering, Pressing, PressingStartedInOutOfBounds
-    Hovering,
-    Pressing,
-    PressingStartedButOutOfBounds,
-    PressingStartedInOutOfBounds,
-    Clicked,
-}
-
-impl StateMachine {
-    pub fn update(&mut self, is_down: bool, is_hovering_self: bool) {
-        *self = match self {
-            StateMachine::Neutral => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Hovering,
-                (true, false) => StateMachine::PressingStartedInOutOfBounds,
-                (true, true) => StateMachine::Pressing,
-            },
-            StateMachine::Hovering => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Hovering,
-                (true, false) => StateMachine::PressingStartedInOutOfBounds,
-                (true, true) => StateMachine::Pressing,
-            },
-            StateMachine::Pressing => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Clicked,
-                (true, false) => StateMachine::PressingStartedButOutOfBounds,
-                (true, true) => StateMachine::Pressing,
-            },
-            StateMachine::Clicked => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Hovering,
-                (true, false) => StateMachine::PressingStartedInOutOfBounds,
-                (true, true) => StateMachine::Pressing,
-            },
-            StateMachine::PressingStartedButOutOfBounds => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Hovering,
-                (true, false) => StateMachine::PressingStartedButOutOfBounds,
-                (true, true) => StateMachine::Pressing,
-            },
-            StateMachine::PressingStartedInOutOfBounds => match (is_down, is_hovering_self) {
-                (false, false) => StateMachine::Neutral,
-                (false, true) => StateMachine::Hovering,
-                (true, false) => StateMachine::PressingStartedInOutOfBounds,
-                (true, true) => StateMachine::PressingStartedInOutOfBounds,
-            },
-        };
+    pub fn hysteresis(mut self, hysteresis: Hysteresis) -> Self {
+        self.hysteresis = hysteresis;
+        self
     }
 }
 
@@ -74,16 +42,21 @@ pub struct PieMenuItemComponent {
     radius: f32,
     start_angle: f32,
     end_angle: f32,
-    action: MenuItemAction,
-    state_machine: StateMachine,
+    interaction: ItemInteraction,
+    hovered: bool,
     icon_component: Option<SpriteComponent>,
-    icon_size: ExponentialSmoothing<f32>,
+    icon_size: Animated,
+    label: Option<LabelComponent>,
     time_delta: TimeDelta,
     spin_icon: SpriteComponent,
-    spin_icon_size: ExponentialSmoothing<f32>,
+    spin_icon_size: Animated,
+    /// Strength of the hover highlight, fading in and out.
+    highlight: ExponentialSmoothing<f32>,
+    theme: Theme,
 }
 
 impl PieMenuItemComponent {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         center_x: f32,
         center_y: f32,
@@ -91,7 +64,10 @@ impl PieMenuItemComponent {
         start_angle: f32,
         end_angle: f32,
         action: MenuItemAction,
+        action_context: ActionContext,
+        keep_open: Option<bool>,
         icon: Option<Pixmap>,
+        theme: Theme,
     ) -> Self {
         Self {
             center_x,
@@ -99,19 +75,35 @@ impl PieMenuItemComponent {
             radius,
             start_angle,
             end_angle,
-            action,
-            // callback,
-            state_machine: StateMachine::Neutral,
+            interaction: ItemInteraction::new(action, action_context, keep_open),
+            hovered: false,
             icon_component: icon.map(SpriteComponent::new),
-            icon_size: ExponentialSmoothing::new(0.0, 20.0),
+            icon_size: Animated::new(0.0, get_animations().icon_grow),
+            label: None,
             time_delta: TimeDelta::new(),
             spin_icon: SpriteComponent::new(
                 get_sprite_sheet()
                     .map_or(Pixmap::new(1, 1).unwrap(), |ss| ss.cutout("spin").unwrap()),
             ),
-            spin_icon_size: ExponentialSmoothing::new(0.0, 10.0),
+            spin_icon_size: Animated::new(0.0, get_animations().spin_icon),
+            highlight: ExponentialSmoothing::new(0.0, 15.0),
+            theme,
         }
     }
+
+    /// Shows `label` under the icon.
+    pub fn with_label(mut self, label: LabelComponent) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn middle_angle(&self) -> f32 {
+        f32::midpoint(self.start_angle, self.end_angle)
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
 }
 
 impl Component for PieMenuItemComponent {
@@ -120,78 +112,52 @@ impl Component for PieMenuItemComponent {
     #[allow(clippy::cast_possible_truncation)]
     fn update(&mut self, props: &Props) {
         let input = &props.pie_menu_input;
-        let in_angle = self.start_angle <= input.angle && input.angle <= self.end_angle;
-        let hover_self = in_angle && input.magnitude > 0.5;
-        let clicking = input.click > 0.5 && input.magnitude > 0.5;
+        let is_over_slices = props
+            .hysteresis
+            .is_over_slices(input.magnitude, self.hovered);
+        self.hovered = props.hysteresis.is_hovered(
+            input.angle,
+            input.magnitude,
+            (self.start_angle, self.end_angle),
+            self.hovered,
+        );
+        let clicking = input.click > 0.5 && is_over_slices;
 
         self.time_delta.update_and_get_secs();
 
-        self.state_machine.update(clicking, hover_self);
+        self.interaction.update(clicking, self.hovered);
 
-        /*if self.state_machine == StateMachine::Clicked {
-            // (self.callback)(CallbackProps::Action(self.action.clone()));
-        }
-
-        match &self.action {
-            MenuItemAction::Noop => {}
-            MenuItemAction::Button(ref button_action) => {}
-        }*/
-
-        match &self.action {
-            MenuItemAction::Noop => {
-                // no op
-            }
-            MenuItemAction::OneShotButton(behaviour) => {
-                if self.state_machine == StateMachine::Clicked {
-                    behaviour.borrow_mut().on_change(true);
-                }
-            }
-            MenuItemAction::Button(behaviour) => {
-                behaviour
-                    .borrow_mut()
-                    .on_change(self.state_machine == StateMachine::Pressing);
-            }
-        }
+        let highlight = match self.interaction.state() {
+            StateMachine::Hovering => 1.0,
+            StateMachine::Pressing | StateMachine::Held => 1.5,
+            _ => 0.0,
+        };
+        self.highlight
+            .update(highlight, self.time_delta.get_without_update_secs());
 
         let spin_icon_scale = self.spin_icon_size.update(
-            match &self.action {
-                MenuItemAction::Noop => 0.1,
-                MenuItemAction::OneShotButton(behaviour) | MenuItemAction::Button(behaviour) => {
-                    if behaviour.borrow().value() {
-                        1.0
-                    } else {
-                        0.0
-                    }
-                }
-            },
+            self.interaction.indicator_scale(),
             self.time_delta.get_without_update_secs(),
         );
 
-        // rt_debug("50_PieMenuItem State", || format!("{:?}", self.state_machine));
-
         rt_debug(|| {
             (
-                format!("50_PieMenuItem '{:?}' State", self.action),
-                format!("{:?}", self.state_machine),
+                format!("50_PieMenuItem '{:?}' State", self.interaction.action()),
+                format!("{:?}", self.interaction.state()),
             )
         });
 
-        let icon_size_target = match self.state_machine {
-            StateMachine::Hovering => 1.2,
-            StateMachine::Pressing => 0.8,
-            StateMachine::Clicked => 1.2,
-            _ => 1.0,
-        };
-
-        self.icon_size
-            .update(icon_size_target, self.time_delta.get_without_update_secs());
+        self.icon_size.update(
+            self.interaction.icon_scale(),
+            self.time_delta.get_without_update_secs(),
+        );
 
         let middle_angle = f32::midpoint(self.start_angle, self.end_angle);
 
         if let Some(icon_component) = &mut self.icon_component {
             icon_component.update(&sprite::Props {
-                x: self.center_x + self.radius * 0.7 * middle_angle.cos(),
-                y: self.center_y + self.radius * 0.7 * middle_angle.sin(),
+                x: self.center_x + self.radius * self.theme.item_radius * middle_angle.cos(),
+                y: self.center_y + self.radius * self.theme.item_radius * middle_angle.sin(),
                 width: self.radius * 0.25 * self.icon_size.get_current(),
                 height: self.radius * 0.25 * self.icon_size.get_current(),
                 rotate: 0.0,
@@ -199,9 +165,19 @@ impl Component for PieMenuItemComponent {
             });
         }
 
+        if let Some(label) = &mut self.label {
+            label.update(&label::Props {
+                x: self.center_x + self.radius * self.theme.item_radius * middle_angle.cos(),
+                y: self.center_y
+                    + self.radius * (self.theme.item_radius * middle_angle.sin() + 0.17),
+                scale: 1.0,
+                max_width: self.radius * 0.4,
+            });
+        }
+
         self.spin_icon.update(&sprite::Props {
-            x: self.center_x + self.radius * 0.7 * middle_angle.cos(),
-            y: self.center_y + self.radius * 0.7 * middle_angle.sin(),
+            x: self.center_x + self.radius * self.theme.item_radius * middle_angle.cos(),
+            y: self.center_y + self.radius * self.theme.item_radius * middle_angle.sin(),
             width: self.radius * 0.4 * spin_icon_scale,
             height: self.radius * 0.4 * spin_icon_scale,
             rotate: ((get_time_since_start_secs_f64() as f32) % 360.0) * (360.0 / 1.0),
@@ -209,33 +185,34 @@ impl Component for PieMenuItemComponent {
         });
     }
     fn render(&self, pixmap: &mut Pixmap) {
-        let transform = Transform::from_translate(self.center_x, self.center_y);
+        // Highlight
+        if self.highlight.get_current() > 0.01 {
+            let mut highlight = self.theme.highlight;
+            highlight.set_alpha(highlight.alpha() * HIGHLIGHT_ALPHA * self.highlight.get_current());
+
+            shapes::fill_arc(
+                pixmap,
+                self.center_x,
+                self.center_y,
+                self.radius * self.theme.center_radius,
+                self.radius,
+                self.start_angle,
+                self.end_angle,
+                highlight,
+            );
+        }
 
         // Separate line
-        {
-            let path = {
-                let mut pb = tiny_skia::PathBuilder::new();
-
-                pb.move_to(
-                    self.start_angle.cos() * self.radius * 0.4,
-                    self.start_angle.sin() * self.radius * 0.4,
-                );
-
-                pb.line_to(
-                    self.start_angle.cos() * self.radius * 0.9,
-                    self.start_angle.sin() * self.radius * 0.9,
-                );
-
-                pb.finish().unwrap()
-            };
-
-            let mut paint = default_paint();
-            let mut stroke = tiny_skia::Stroke::default();
-            stroke.width = 4.0;
-
-            paint.set_color_rgba8(255, 255, 255, 255);
-            pixmap.stroke_path(&path, &paint, &stroke, transform, None);
-        }
+        shapes::stroke_radial_line(
+            pixmap,
+            self.center_x,
+            self.center_y,
+            self.start_angle,
+            self.radius * (self.theme.center_radius + 0.1),
+            self.radius * 0.9,
+            self.theme.separator_width,
+            self.theme.separator,
+        );
 
         // Icon
         {
@@ -244,12 +221,48 @@ impl Component for PieMenuItemComponent {
             }
         }
 
+        // Label
+        if let Some(label) = &self.label {
+            label.render(pixmap);
+        }
+
         // Spin icon
         {
             if self.spin_icon_size.get_current() > 0.01 {
                 self.spin_icon.render(pixmap);
             }
         }
+
+        // Hold progress
+        {
+            let hold_progress = self.interaction.hold_progress();
+
+            if hold_progress > 0.0 {
+                shapes::fill_arc(
+                    pixmap,
+                    self.center_x,
+                    self.center_y,
+                    self.radius * 0.86,
+                    self.radius * 0.9,
+                    self.start_angle,
+                    self.start_angle + (self.end_angle - self.start_angle) * hold_progress,
+                    self.theme.highlight,
+                );
+            }
+        }
+
+        // Failure badge
+        if self.interaction.has_failed() {
+            let middle_angle = f32::midpoint(self.start_angle, self.end_angle);
+
+            shapes::fill_circle(
+                pixmap,
+                self.center_x + self.radius * (self.theme.item_radius + 0.12) * middle_angle.cos(),
+                self.center_y + self.radius * (self.theme.item_radius + 0.12) * middle_angle.sin(),
+                self.radius * 0.05,
+                tiny_skia::Color::from_rgba8(230, 60, 60, 255),
+            );
+        }
     }
 }
 
@@ -275,8 +288,10 @@ mod tests {
             false
         }
 
-        fn on_change(&mut self, _value: bool) {
+        fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
             *self.count.borrow_mut() += 1;
+
+            ActionResult::Success
         }
     }
 
@@ -287,7 +302,18 @@ mod tests {
             callback_variable,
         ))));
 
-        PieMenuItemComponent::new(0.0, 0.0, 0.0, start_angle, end_angle, action, None)
+        PieMenuItemComponent::new(
+            0.0,
+            0.0,
+            0.0,
+            start_angle,
+            end_angle,
+            action,
+            ActionContext::new(std::sync::mpsc::channel().0),
+            None,
+            None,
+            Theme::default(),
+        )
     }
 
     #[test]
@@ -356,20 +382,143 @@ mod tests {
         pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
         assert_eq!(*is_action_executed.borrow(), 2);
     }
+
+    fn hold_menu_item(
+        callback_variable: Rc<RefCell<u32>>,
+        hold: std::time::Duration,
+        event_sender: std::sync::mpsc::Sender<AppEvent>,
+    ) -> PieMenuItemComponent {
+        let action = MenuItemAction::HoldButton(
+            Rc::new(RefCell::new(CountAction::new(callback_variable))),
+            hold,
+        );
+
+        PieMenuItemComponent::new(
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            PI * 2.0 * 0.25,
+            action,
+            ActionContext::new(event_sender),
+            None,
+            None,
+            Theme::default(),
+        )
+    }
+
+    #[test]
+    fn test_hover_holds_near_the_edges() {
+        let mut pie_menu_item = pie_menu_item(Rc::new(RefCell::new(0)));
+
+        // Not hovered yet at the magnitude which keeps it hovered
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 0.5, 0.0)));
+        assert!(!pie_menu_item.is_hovered());
+
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 0.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 0.5, 0.0)));
+        assert!(pie_menu_item.is_hovered());
+
+        // Just past the edge
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(PI * 0.51, 1.0, 0.0)));
+        assert!(pie_menu_item.is_hovered());
+
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(PI * 0.75, 1.0, 0.0)));
+        assert!(!pie_menu_item.is_hovered());
+    }
+
+    #[test]
+    fn test_hold_button_runs_once_held() {
+        let count = Rc::new(RefCell::new(0));
+        let mut pie_menu_item = hold_menu_item(
+            count.clone(),
+            std::time::Duration::ZERO,
+            std::sync::mpsc::channel().0,
+        );
+
+        let hover_angle = PI * 2.0 * 0.125;
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 1.0)));
+        assert_eq!(*count.borrow(), 1);
+
+        // Still held, and released: neither runs it again
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 1.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+        assert_eq!(*count.borrow(), 1);
+    }
+
+    #[test]
+    fn test_hold_button_ignores_click() {
+        let count = Rc::new(RefCell::new(0));
+        let (event_sender, event_receiver) = std::sync::mpsc::channel();
+        let mut pie_menu_item = hold_menu_item(
+            count.clone(),
+            std::time::Duration::from_secs(3600),
+            event_sender,
+        );
+
+        let hover_angle = PI * 2.0 * 0.125;
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 1.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+
+        assert_eq!(*count.borrow(), 0);
+        assert!(event_receiver.try_iter().any(|event| matches!(
+            event,
+            AppEvent::Toast {
+                severity: Severity::Info,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_action_fired_carries_keep_open() {
+        let (event_sender, event_receiver) = std::sync::mpsc::channel();
+        let action = MenuItemAction::OneShotButton(Rc::new(RefCell::new(CountAction::new(
+            Rc::new(RefCell::new(0)),
+        ))));
+
+        let mut pie_menu_item = PieMenuItemComponent::new(
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            PI * 2.0 * 0.25,
+            action,
+            ActionContext::new(event_sender),
+            Some(false),
+            None,
+            Theme::default(),
+        );
+
+        let hover_angle = PI * 2.0 * 0.125;
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 1.0)));
+        pie_menu_item.update(&Props::new(&PieMenuInput::new(hover_angle, 1.0, 0.0)));
+
+        assert!(event_receiver.try_iter().any(|event| matches!(
+            event,
+            AppEvent::ActionFired {
+                keep_open: Some(false),
+                ..
+            }
+        )));
+    }
 }
 
 #[cfg(test)]
 mod stories {
     // NOTE: Allow unused_imports to import Component trait
     #![allow(unused_imports)]
-    pub use crate::component::Component;
     use crate::{
-        menu::{MenuActionBehaviour, PieMenuInput},
+        menu::{ActionContext, ActionResult, MenuActionBehaviour, PieMenuInput},
         resource::SPRITE_SHEET,
-        story::story,
     };
+    use oscpie_ui::story::story;
+    pub use oscpie_ui::Component;
 
-    use super::{MenuId, MenuItemAction, PieMenuItemComponent, Pixmap, Props};
+    use super::{MenuId, MenuItemAction, PieMenuItemComponent, Pixmap, Props, Theme};
     use std::{cell::RefCell, f32::consts::PI, path::PathBuf, rc::Rc};
 
     static NEUTRAL_ANGLE: f32 = 0.0;
@@ -394,8 +543,10 @@ mod stories {
             false
         }
 
-        fn on_change(&mut self, _value: bool) {
+        fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
             *self.count.borrow_mut() += 1;
+
+            ActionResult::Success
         }
     }
 
@@ -414,7 +565,10 @@ mod stories {
             START_ANGLE,
             END_ANGLE,
             action,
+            ActionContext::new(std::sync::mpsc::channel().0),
+            None,
             Some(icon),
+            Theme::default(),
         )
     }
 
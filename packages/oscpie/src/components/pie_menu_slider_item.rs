@@ -0,0 +1,405 @@
+use std::{
+    cell::RefCell,
+    f32::consts::{PI, TAU},
+    rc::Rc,
+};
+
+use oscpie_ui::{
+    animation::Animated,
+    components::{
+        label::{self, LabelComponent},
+        sprite::{self, SpriteComponent},
+    },
+    shapes, Component,
+};
+use tiny_skia::{Color, Pixmap};
+
+use crate::{audit, prelude::*, resource::get_animations, theme::Theme};
+
+use super::pie_menu_item::Props;
+
+/// Change of the value for a full turn of the stick.
+const VALUE_PER_TURN: f32 = 1.0;
+
+/// Smallest change which is sent, so that a resting stick does not flood the behaviour.
+const MIN_STEP: f32 = 0.005;
+
+/// Pie menu item which is grabbed by pressing on it, and adjusted by turning the stick until it is
+/// released. The value is drawn as an arc along the outer edge of the slice.
+pub struct PieMenuSliderItemComponent {
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    behaviour: Rc<RefCell<dyn MenuActionBehaviour<f32>>>,
+    action_context: ActionContext,
+    value: f32,
+    /// Stick angle of the last update while the item is held.
+    grabbed_at: Option<f32>,
+    was_down: bool,
+    hovered: bool,
+    last_result: Option<ActionResult>,
+    icon_component: Option<SpriteComponent>,
+    icon_size: Animated,
+    label: Option<LabelComponent>,
+    time_delta: TimeDelta,
+    theme: Theme,
+}
+
+impl PieMenuSliderItemComponent {
+    pub fn new(
+        center_x: f32,
+        center_y: f32,
+        radius: f32,
+        (start_angle, end_angle): (f32, f32),
+        behaviour: Rc<RefCell<dyn MenuActionBehaviour<f32>>>,
+        action_context: ActionContext,
+        icon: Option<Pixmap>,
+        theme: Theme,
+    ) -> Self {
+        let value = behaviour.borrow().value();
+
+        Self {
+            center_x,
+            center_y,
+            radius,
+            start_angle,
+            end_angle,
+            behaviour,
+            action_context,
+            value,
+            grabbed_at: None,
+            was_down: false,
+            hovered: false,
+            last_result: None,
+            icon_component: icon.map(SpriteComponent::new),
+            icon_size: Animated::new(0.0, get_animations().icon_grow),
+            label: None,
+            time_delta: TimeDelta::new(),
+            theme,
+        }
+    }
+
+    /// Shows `label` under the icon.
+    pub fn with_label(mut self, label: LabelComponent) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn middle_angle(&self) -> f32 {
+        f32::midpoint(self.start_angle, self.end_angle)
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    pub fn is_grabbed(&self) -> bool {
+        self.grabbed_at.is_some()
+    }
+
+    fn set_value(&mut self, value: f32) {
+        let result = self
+            .behaviour
+            .borrow_mut()
+            .on_change(value, &mut self.action_context);
+
+        // Report a failure once rather than on every step of the same drag
+        if let ActionResult::Failure(message) = &result {
+            if !matches!(self.last_result, Some(ActionResult::Failure(_))) {
+                self.action_context.toast(Severity::Error, message.clone());
+            }
+        }
+
+        self.value = value;
+        self.last_result = Some(result);
+    }
+
+    fn release(&mut self) {
+        self.grabbed_at = None;
+
+        if let Some(result) = self.last_result.clone() {
+            audit::record(
+                format!(
+                    "{} to {:.0}%",
+                    self.behaviour.borrow().describe(),
+                    self.value * 100.0
+                ),
+                result,
+            );
+        }
+    }
+}
+
+impl Component for PieMenuSliderItemComponent {
+    type Props<'a> = Props<'a>;
+
+    fn update(&mut self, props: &Props) {
+        let input = &props.pie_menu_input;
+        let is_over_slices = props
+            .hysteresis
+            .is_over_slices(input.magnitude, self.hovered || self.is_grabbed());
+        self.hovered = props.hysteresis.is_hovered(
+            input.angle,
+            input.magnitude,
+            (self.start_angle, self.end_angle),
+            self.hovered,
+        );
+        let hover_self = self.hovered;
+        let is_down = input.click > 0.5;
+
+        match self.grabbed_at {
+            Some(_) if !is_down => self.release(),
+            Some(last_angle) => {
+                // The angle is meaningless while the stick rests in the hub
+                if is_over_slices {
+                    let turned = (input.angle - last_angle + PI).rem_euclid(TAU) - PI;
+                    let value = (self.value + turned / TAU * VALUE_PER_TURN).clamp(0.0, 1.0);
+
+                    if (value - self.value).abs() >= MIN_STEP {
+                        self.set_value(value);
+                    }
+
+                    self.grabbed_at = Some(input.angle);
+                }
+            }
+            None if is_down && !self.was_down && hover_self => {
+                self.grabbed_at = Some(input.angle);
+                self.last_result = None;
+                self.value = self.behaviour.borrow().value();
+                self.action_context.haptics();
+            }
+            None => {
+                self.value = self.behaviour.borrow().value();
+            }
+        }
+
+        self.was_down = is_down;
+
+        let icon_scale = match (self.is_grabbed(), hover_self) {
+            (true, _) => 0.8,
+            (false, true) => 1.2,
+            (false, false) => 1.0,
+        };
+
+        let dt = self.time_delta.update_and_get_secs();
+        let icon_scale = self.icon_size.update(icon_scale, dt);
+        let middle_angle = self.middle_angle();
+
+        if let Some(icon_component) = &mut self.icon_component {
+            icon_component.update(&sprite::Props {
+                x: self.center_x
+                    + self.radius * (self.theme.item_radius - 0.05) * middle_angle.cos(),
+                y: self.center_y
+                    + self.radius * (self.theme.item_radius - 0.05) * middle_angle.sin(),
+                width: self.radius * 0.25 * icon_scale,
+                height: self.radius * 0.25 * icon_scale,
+                rotate: 0.0,
+                layout_mode: sprite::LayoutMode::Center,
+            });
+        }
+
+        if let Some(label) = &mut self.label {
+            label.update(&label::Props {
+                x: self.center_x
+                    + self.radius * (self.theme.item_radius - 0.05) * middle_angle.cos(),
+                y: self.center_y
+                    + self.radius * ((self.theme.item_radius - 0.05) * middle_angle.sin() + 0.17),
+                scale: 1.0,
+                max_width: self.radius * 0.4,
+            });
+        }
+    }
+
+    fn render(&self, pixmap: &mut Pixmap) {
+        // Separate line
+        shapes::stroke_radial_line(
+            pixmap,
+            self.center_x,
+            self.center_y,
+            self.start_angle,
+            self.radius * (self.theme.center_radius + 0.1),
+            self.radius * 0.9,
+            self.theme.separator_width,
+            self.theme.separator,
+        );
+
+        // Track and fill, leaving a gap to the separate lines
+        {
+            let margin = (self.end_angle - self.start_angle) * 0.08;
+            let start = self.start_angle + margin;
+            let end = self.end_angle - margin;
+            let mut track = self.theme.separator;
+            track.set_alpha(track.alpha() * 0.2);
+
+            let mut fill = self.theme.highlight;
+            if !self.is_grabbed() {
+                fill.set_alpha(fill.alpha() * 0.7);
+            }
+
+            shapes::fill_arc(
+                pixmap,
+                self.center_x,
+                self.center_y,
+                self.radius * 0.8,
+                self.radius * 0.86,
+                start,
+                end,
+                track,
+            );
+            shapes::fill_arc(
+                pixmap,
+                self.center_x,
+                self.center_y,
+                self.radius * 0.8,
+                self.radius * 0.86,
+                start,
+                start + (end - start) * self.value,
+                fill,
+            );
+        }
+
+        // Icon
+        if let Some(icon_component) = &self.icon_component {
+            icon_component.render(pixmap);
+        }
+
+        // Label
+        if let Some(label) = &self.label {
+            label.render(pixmap);
+        }
+
+        // Failure badge
+        if matches!(self.last_result, Some(ActionResult::Failure(_))) {
+            let middle_angle = self.middle_angle();
+
+            shapes::fill_circle(
+                pixmap,
+                self.center_x + self.radius * (self.theme.item_radius + 0.07) * middle_angle.cos(),
+                self.center_y + self.radius * (self.theme.item_radius + 0.07) * middle_angle.sin(),
+                self.radius * 0.05,
+                Color::from_rgba8(230, 60, 60, 255),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct ValueAction {
+        value: f32,
+        changes: u32,
+    }
+
+    impl MenuActionBehaviour<f32> for ValueAction {
+        fn value(&self) -> f32 {
+            self.value
+        }
+
+        fn on_change(&mut self, value: f32, _ctx: &mut ActionContext) -> ActionResult {
+            self.value = value;
+            self.changes += 1;
+
+            ActionResult::Success
+        }
+    }
+
+    fn slider_item(action: Rc<RefCell<ValueAction>>) -> PieMenuSliderItemComponent {
+        PieMenuSliderItemComponent::new(
+            0.0,
+            0.0,
+            0.0,
+            (0.0, PI * 0.5),
+            action,
+            ActionContext::new(std::sync::mpsc::channel().0),
+            None,
+            Theme::default(),
+        )
+    }
+
+    #[test]
+    fn test_turning_while_held_adjusts_value() {
+        let action = Rc::new(RefCell::new(ValueAction::default()));
+        let mut slider_item = slider_item(action.clone());
+
+        // Grab, then turn a quarter clockwise and past the end of the slice
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 1.0)));
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.5, 1.0, 1.0)));
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.75, 1.0, 1.0)));
+
+        assert!((action.borrow().value - 0.25).abs() < 0.001);
+
+        // Resting in the hub keeps the value
+        slider_item.update(&Props::new(&PieMenuInput::new(0.0, 0.0, 1.0)));
+
+        assert!((action.borrow().value - 0.25).abs() < 0.001);
+
+        // Turning back counter-clockwise, across zero, clamps at the bottom
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 1.0)));
+        slider_item.update(&Props::new(&PieMenuInput::new(-PI * 0.25, 1.0, 1.0)));
+
+        assert!(action.borrow().value.abs() < 0.001);
+
+        // Released, turning does nothing
+        slider_item.update(&Props::new(&PieMenuInput::new(-PI * 0.25, 1.0, 0.0)));
+        let changes = action.borrow().changes;
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 0.0)));
+
+        assert_eq!(action.borrow().changes, changes);
+        assert!(!slider_item.is_grabbed());
+    }
+
+    #[test]
+    fn test_press_started_elsewhere_does_not_grab() {
+        let action = Rc::new(RefCell::new(ValueAction::default()));
+        let mut slider_item = slider_item(action.clone());
+
+        slider_item.update(&Props::new(&PieMenuInput::new(PI, 1.0, 1.0)));
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 1.0)));
+        slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.5, 1.0, 1.0)));
+
+        assert!(!slider_item.is_grabbed());
+        assert_eq!(action.borrow().changes, 0);
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use oscpie_ui::story::story;
+
+    #[derive(Debug)]
+    struct FixedAction(f32);
+
+    impl MenuActionBehaviour<f32> for FixedAction {
+        fn value(&self) -> f32 {
+            self.0
+        }
+
+        fn on_change(&mut self, _value: f32, _ctx: &mut ActionContext) -> ActionResult {
+            ActionResult::Success
+        }
+    }
+
+    #[test]
+    fn story_pie_menu_slider_item() {
+        story("slider", |pixmap| {
+            let mut slider_item = PieMenuSliderItemComponent::new(
+                256.0,
+                256.0,
+                256.0,
+                (0.0, PI * 0.5),
+                Rc::new(RefCell::new(FixedAction(0.6))),
+                ActionContext::new(std::sync::mpsc::channel().0),
+                None,
+                Theme::default(),
+            );
+            slider_item.update(&Props::new(&PieMenuInput::new(PI * 0.25, 1.0, 0.0)));
+            slider_item.render(pixmap);
+        });
+    }
+}
@@ -0,0 +1,278 @@
+//! Menu drawn as a row of items, for those who prefer a bar over a pie. Items are selected by the
+//! horizontal position of the stick and clicked like pie menu items.
+
+use std::f32::consts::PI;
+
+use oscpie_ui::{components::label, shapes};
+use tiny_skia::{Color, Pixmap};
+
+use crate::{hit_test, prelude::*, theme::Theme};
+
+use super::{cell_item::CellItem, pie_menu::Props};
+
+pub struct QuickBarComponent {
+    center_x: f32,
+    center_y: f32,
+    width: f32,
+    height: f32,
+    items: Vec<CellItem>,
+    selected: Option<usize>,
+    /// Horizontal stick position from `-1.0` to `1.0`.
+    input_x: f32,
+    contrast: f32,
+    time_delta: TimeDelta,
+    theme: Theme,
+}
+
+impl QuickBarComponent {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(
+        center_x: f32,
+        center_y: f32,
+        width: f32,
+        menu: &Menu,
+        action_context: &ActionContext,
+        theme: Theme,
+    ) -> Self {
+        let items: Vec<_> = menu
+            .items
+            .iter()
+            .map(|item| CellItem::new(item, action_context))
+            .collect();
+
+        // Square items, but never taller than a quarter of the bar
+        let height = (width / items.len().max(1) as f32).min(width * 0.25);
+
+        Self {
+            center_x,
+            center_y,
+            width,
+            height,
+            items,
+            selected: None,
+            input_x: 0.0,
+            contrast: 0.0,
+            time_delta: TimeDelta::new(),
+            theme,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn item_width(&self) -> f32 {
+        self.width / self.items.len().max(1) as f32
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn item_center_x(&self, index: usize) -> f32 {
+        self.center_x - self.width * 0.5 + self.item_width() * (index as f32 + 0.5)
+    }
+
+    /// Angle which selects the item at `index` at full deflection, pointing up so that the stick
+    /// passes over no other item on the way.
+    pub fn item_angle(&self, index: usize) -> Option<f32> {
+        if index >= self.items.len() {
+            return None;
+        }
+
+        let x = (self.item_center_x(index) - self.center_x) / (self.width * 0.5);
+
+        Some(PI * 2.0 - x.acos())
+    }
+
+    /// Middle of the selected item in pixels.
+    pub fn selected_point(&self) -> Option<Vec2> {
+        self.selected
+            .map(|selected| Vec2::new(self.item_center_x(selected), self.center_y))
+    }
+
+    /// Where the breadcrumb goes, above the bar.
+    pub fn breadcrumb_props(&self) -> label::Props {
+        label::Props {
+            x: self.center_x,
+            y: self.center_y - self.height * 0.5 - 20.0,
+            scale: 1.0,
+            max_width: self.width,
+        }
+    }
+
+    pub fn update(&mut self, props: &Props) {
+        let input = &props.pie_menu_input;
+
+        self.selected = hit_test::polar_to_bar_item(input.angle, input.magnitude, self.items.len());
+        self.input_x = input.angle.cos() * input.magnitude.min(hit_test::OUTER_RADIUS);
+        self.contrast = props.contrast;
+
+        let clicking = input.click > 0.5 && self.selected.is_some();
+        let dt = self.time_delta.update_and_get_secs();
+        let icon_extent = self.item_width().min(self.height);
+
+        for index in 0..self.items.len() {
+            let x = self.item_center_x(index);
+
+            self.items[index].update(
+                x,
+                self.center_y,
+                icon_extent,
+                clicking,
+                self.selected == Some(index),
+                dt,
+            );
+        }
+    }
+
+    pub fn render(&self, pixmap: &mut Pixmap) {
+        let left = self.center_x - self.width * 0.5;
+        let top = self.center_y - self.height * 0.5;
+        let item_width = self.item_width();
+
+        // Background
+        shapes::fill_rect(
+            pixmap,
+            left,
+            top,
+            self.width,
+            self.height,
+            self.theme.background_with_contrast(self.contrast),
+        );
+
+        // Selection
+        if let Some(selected) = self.selected {
+            let mut highlight = self.theme.highlight;
+            highlight.set_alpha(highlight.alpha() * 0.2);
+
+            shapes::fill_rect(
+                pixmap,
+                self.item_center_x(selected) - item_width * 0.5,
+                top,
+                item_width,
+                self.height,
+                highlight,
+            );
+        }
+
+        for (index, item) in self.items.iter().enumerate() {
+            let x = self.item_center_x(index);
+
+            // Separate line
+            if index > 0 {
+                shapes::fill_rect(
+                    pixmap,
+                    x - item_width * 0.5 - self.theme.separator_width * 0.5,
+                    top + self.height * 0.15,
+                    self.theme.separator_width,
+                    self.height * 0.7,
+                    self.theme.separator,
+                );
+            }
+
+            item.render(pixmap);
+        }
+
+        // Stick
+        shapes::fill_circle(
+            pixmap,
+            self.center_x + self.input_x * self.width * 0.5,
+            top + self.height + 20.0,
+            12.0,
+            Color::from_rgba(0.5, 0.5, 0.55, 1.0).unwrap(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::menu::AppEventMenuActionBehaviour;
+
+    fn quick_bar(event_sender: std::sync::mpsc::Sender<AppEvent>) -> QuickBarComponent {
+        let menu = Menu::new(
+            (0..4)
+                .map(|_| {
+                    MenuItem::new(
+                        MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+                            AppEventMenuActionBehaviour::new(AppEvent::StartTutorial),
+                        ))),
+                        None,
+                    )
+                })
+                .collect(),
+        );
+
+        QuickBarComponent::new(
+            256.0,
+            256.0,
+            460.0,
+            &menu,
+            &ActionContext::new(event_sender),
+            Theme::default(),
+        )
+    }
+
+    #[test]
+    fn test_item_angle_selects_the_item() {
+        let quick_bar = quick_bar(std::sync::mpsc::channel().0);
+
+        for index in 0..4 {
+            let angle = quick_bar.item_angle(index).unwrap();
+
+            assert_eq!(
+                hit_test::polar_to_bar_item(angle, 1.0, 4),
+                Some(index),
+                "item {index}"
+            );
+        }
+
+        assert_eq!(quick_bar.item_angle(4), None);
+    }
+
+    #[test]
+    fn test_click_runs_the_selected_item() {
+        let (event_sender, event_receiver) = std::sync::mpsc::channel();
+        let mut quick_bar = quick_bar(event_sender);
+        let angle = quick_bar.item_angle(2).unwrap();
+
+        for click in [0.0, 1.0, 0.0] {
+            quick_bar.update(&Props::new(PieMenuInput::new(angle, 1.0, click)));
+        }
+
+        assert_eq!(quick_bar.selected, Some(2));
+        assert_eq!(
+            event_receiver
+                .try_iter()
+                .filter(|event| matches!(event, AppEvent::StartTutorial))
+                .count(),
+            1
+        );
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use oscpie_ui::story::story;
+
+    #[test]
+    fn story_quick_bar_hover() {
+        story("quick_bar_hover", |pixmap| {
+            let menu = Menu::new(
+                (0..5)
+                    .map(|_| MenuItem::new(MenuItemAction::Noop, None))
+                    .collect(),
+            );
+            let mut quick_bar = QuickBarComponent::new(
+                256.0,
+                256.0,
+                460.0,
+                &menu,
+                &ActionContext::new(std::sync::mpsc::channel().0),
+                Theme::default(),
+            );
+
+            let angle = quick_bar.item_angle(1).unwrap();
+            quick_bar.update(&Props::new(PieMenuInput::new(angle, 1.0, 0.0)));
+            quick_bar.render(pixmap);
+        });
+    }
+}
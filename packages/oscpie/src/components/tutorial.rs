@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use tiny_skia::{Pixmap, Transform};
+
+use oscpie_ui::{
+    components::label::{self, LabelComponent},
+    shapes, Component,
+};
+
+use crate::{prelude::*, resource::get_labels, tutorial::TutorialStep};
+
+use super::{pie_radius, REFERENCE_SIZE};
+
+const COLOR: (u8, u8, u8) = (255, 200, 40);
+
+pub struct Props {
+    step: TutorialStep,
+    /// Angle of the item the user should aim at, e.g. the back item.
+    target_angle: Option<f32>,
+}
+
+impl Props {
+    pub fn new(step: TutorialStep, target_angle: Option<f32>) -> Self {
+        Self { step, target_angle }
+    }
+}
+
+/// Arrows and captions drawn on top of the pie menu while the tutorial runs.
+pub struct TutorialComponent {
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    step: TutorialStep,
+    arrow_angle: f32,
+    pulse: f32,
+    caption: Option<(TutorialStep, LabelComponent)>,
+}
+
+impl TutorialComponent {
+    pub fn new(center_x: f32, center_y: f32, radius: f32) -> Self {
+        Self {
+            center_x,
+            center_y,
+            radius,
+            step: TutorialStep::Done,
+            arrow_angle: 0.0,
+            pulse: 0.0,
+            caption: None,
+        }
+    }
+
+    fn render_arrow(&self, pixmap: &mut Pixmap) {
+        let path = {
+            let mut pb = tiny_skia::PathBuilder::new();
+
+            pb.move_to(self.radius * 0.35, 0.0);
+            pb.line_to(self.radius * 0.6, 0.0);
+            pb.move_to(self.radius * 0.5, -self.radius * 0.08);
+            pb.line_to(self.radius * 0.6, 0.0);
+            pb.line_to(self.radius * 0.5, self.radius * 0.08);
+
+            pb.finish().unwrap()
+        };
+
+        let mut paint = default_paint();
+        paint.set_color_rgba8(COLOR.0, COLOR.1, COLOR.2, 255);
+
+        let stroke = tiny_skia::Stroke {
+            width: 10.0,
+            line_cap: tiny_skia::LineCap::Round,
+            line_join: tiny_skia::LineJoin::Round,
+            ..tiny_skia::Stroke::default()
+        };
+
+        pixmap.stroke_path(
+            &path,
+            &paint,
+            &stroke,
+            Transform::from_rotate(self.arrow_angle.to_degrees())
+                .post_translate(self.center_x, self.center_y),
+            None,
+        );
+    }
+
+    fn render_pulse(&self, pixmap: &mut Pixmap) {
+        shapes::fill_circle(
+            pixmap,
+            self.center_x,
+            self.center_y,
+            self.radius * (0.1 + 0.1 * self.pulse),
+            tiny_skia::Color::from_rgba(1.0, 0.8, 0.15, 1.0 - self.pulse * 0.8).unwrap(),
+        );
+    }
+}
+
+impl Component for TutorialComponent {
+    type Props<'a> = Props;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn update(&mut self, props: &Props) {
+        let time = get_time_since_start_secs_f64() as f32;
+
+        self.step = props.step;
+        self.pulse = (time * 2.0).fract();
+        self.arrow_angle = props
+            .target_angle
+            .unwrap_or_else(|| (time * PI * 0.5).rem_euclid(PI * 2.0));
+
+        if self.caption.as_ref().map(|(step, _)| *step) != Some(props.step) {
+            self.caption = props
+                .step
+                .caption()
+                .zip(get_labels())
+                .map(|(text, labels)| {
+                    let label = LabelComponent::new(
+                        &labels.font,
+                        text,
+                        labels.size * self.radius / pie_radius(REFERENCE_SIZE),
+                        tiny_skia::Color::from_rgba8(COLOR.0, COLOR.1, COLOR.2, 255),
+                    );
+
+                    (props.step, label)
+                });
+        }
+
+        if let Some((_, caption)) = &mut self.caption {
+            caption.update(&label::Props {
+                x: self.center_x,
+                y: self.center_y + self.radius * 0.75,
+                scale: 1.0,
+                max_width: self.radius * 1.6,
+            });
+        }
+    }
+
+    fn render(&self, pixmap: &mut Pixmap) {
+        match self.step {
+            TutorialStep::PushStick | TutorialStep::GoBack => self.render_arrow(pixmap),
+            TutorialStep::Click => self.render_pulse(pixmap),
+            TutorialStep::OpenSubMenu => {
+                self.render_arrow(pixmap);
+                self.render_pulse(pixmap);
+            }
+            TutorialStep::Done => return,
+        }
+
+        if let Some((_, caption)) = &self.caption {
+            caption.render(pixmap);
+        }
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use super::*;
+    use oscpie_ui::story::story;
+
+    fn render_step(name: &str, step: TutorialStep, target_angle: Option<f32>) {
+        story(name, |pixmap| {
+            let mut tutorial = TutorialComponent::new(256.0, 256.0, 256.0 * 0.9);
+            tutorial.update(&Props::new(step, target_angle));
+            tutorial.render(pixmap);
+        });
+    }
+
+    #[test]
+    fn story_tutorial_push_stick() {
+        render_step("tutorial_push_stick", TutorialStep::PushStick, Some(0.0));
+    }
+
+    #[test]
+    fn story_tutorial_click() {
+        render_step("tutorial_click", TutorialStep::Click, None);
+    }
+
+    #[test]
+    fn story_tutorial_go_back() {
+        render_step("tutorial_go_back", TutorialStep::GoBack, Some(PI * 1.25));
+    }
+}
@@ -1,51 +1,92 @@
 use crate::prelude::*;
 use anyhow::anyhow;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
-use crate::versioned::{CompositMigrator, Versioned};
+use crate::{
+    storage::Storage,
+    versioned::{CompositMigrator, Versioned},
+};
 
+mod include;
+mod library;
+mod snippet;
 mod v1;
+mod v2;
+mod validation;
+mod watcher;
+
+pub use include::resolve as resolve_includes;
+pub use library::resolve as resolve_library;
+pub use snippet::{append_menus, merge_snippet, MenuSnippet};
+pub use validation::{validate, Problem};
+pub use watcher::ConfigWatcher;
 
 pub mod types {
-    pub use super::v1::*;
+    pub use super::v2::*;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "config_version", content = "config")]
 pub enum ConfigFile {
     V1(v1::Config),
+    V2(v2::Config),
 }
 
 impl Versioned<u32> for ConfigFile {
     fn version(&self) -> u32 {
         match self {
             ConfigFile::V1(_) => 1,
+            ConfigFile::V2(_) => 2,
         }
     }
 }
 
-pub type Config = v1::Config;
+pub type Config = v2::Config;
+
+const CURRENT_VERSION: u32 = 2;
+
+const BACKUP_COUNT: usize = 5;
 
 fn migrator() -> CompositMigrator<ConfigFile, u32> {
-    CompositMigrator::new()
+    let mut migrator = CompositMigrator::new();
+
+    migrator.add_migrator(1, 2, |config_file| match config_file {
+        ConfigFile::V1(config) => Ok(ConfigFile::V2(config.into())),
+        other => Err(format!("Expected version 1, got {}", other.version())),
+    });
+
+    migrator
 }
 
 pub fn read(config_file: ConfigFile) -> Result<Config> {
     let migrator = migrator();
 
-    let migrated = migrator.migrate(config_file, 1);
+    let migrated = migrator
+        .migrate(config_file, CURRENT_VERSION)
+        .map_err(|e| anyhow!("Failed to migrate config: {e}"))?;
 
-    let Ok(ConfigFile::V1(config)) = migrated else {
+    let ConfigFile::V2(config) = migrated else {
         return Err(anyhow!("Failed to migrate config"));
     };
 
     Ok(config)
 }
 
+/// Loads the config with the menus of included files merged in.
 pub fn load(path: &str) -> Result<Config> {
-    let file = std::fs::File::open(path).map_err(|e| anyhow!(e.to_string()))?;
-    let config_file: ConfigFile =
-        serde_json::from_reader(file).map_err(|e| anyhow!(e.to_string()))?;
+    let mut config = load_unresolved(path)?;
+
+    include::resolve(&mut config, Path::new(path))?;
+    library::resolve(&mut config);
+
+    Ok(config)
+}
+
+/// Loads the config as it is in the file, without the menus of included files.
+fn load_unresolved(path: &str) -> Result<Config> {
+    let config_file = storage(path).read()?;
 
     let config = read(config_file)?;
 
@@ -54,6 +95,101 @@ pub fn load(path: &str) -> Result<Config> {
     Ok(config)
 }
 
+/// Loads the config again while the overlay runs.
+///
+/// Unlike [`load`], a file which fails to parse is an error instead of being restored from a
+/// backup, so that a config which is being edited is never replaced.
+pub fn reload(path: &str) -> Result<Config> {
+    let mut config = read(storage(path).read_current()?)?;
+
+    include::resolve(&mut config, Path::new(path))?;
+    library::resolve(&mut config);
+
+    Ok(config)
+}
+
+/// Changes the config file with `change`. Included files are left as they are, and their menus
+/// are not written into the config file.
+pub fn update(path: &str, change: impl FnOnce(&mut Config)) -> Result<()> {
+    let mut config = load_unresolved(path)?;
+
+    change(&mut config);
+
+    storage(path).write(&ConfigFile::V2(config))
+}
+
+/// JSON Schema of the config file, for editors to complete and check `config.json` with.
+pub fn schema() -> Result<String> {
+    Ok(serde_json::to_string_pretty(&schemars::schema_for!(
+        ConfigFile
+    ))?)
+}
+
+/// Sprite sheet written next to a starter config, relative to it.
+const DEFAULT_SPRITE_SHEET: &str = "spritesheet.json";
+const DEFAULT_SPRITE_SHEET_IMAGE: &str = "spritesheet_image.png";
+
+/// Writes a starter config to `path`, with a root menu of a few example items. A blank
+/// placeholder sprite sheet is written next to it, unless there already is one.
+pub fn write_default(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    if !directory.as_os_str().is_empty() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let config: Config = serde_json::from_value(serde_json::json!({
+        "root": "root",
+        "sprite_sheet": DEFAULT_SPRITE_SHEET,
+        "menus": {
+            "root": {
+                "items": [
+                    {
+                        "action": {
+                            "type": "KeyStroke",
+                            "key_stroke": [{ "type": "Down", "key": 0x39 }, { "type": "Up", "key": 0x39 }]
+                        },
+                        "label": "Space"
+                    },
+                    {
+                        "action": { "type": "Exec", "program_path": "notepad.exe", "args": [] },
+                        "label": "Notepad"
+                    },
+                    { "action": { "type": "Notifications" }, "label": "Notifications" },
+                    { "action": { "type": "StartTutorial" }, "label": "Tutorial" }
+                ]
+            }
+        }
+    }))?;
+
+    let sprite_sheet_path = directory.join(DEFAULT_SPRITE_SHEET);
+
+    if !sprite_sheet_path.exists() {
+        let sprite_sheet = serde_json::json!({
+            "image": DEFAULT_SPRITE_SHEET_IMAGE,
+            "sprites": {
+                "back": { "name": "back", "width": 64, "height": 64, "x_start": 0, "y_start": 0 },
+                "spin": { "name": "spin", "width": 64, "height": 64, "x_start": 64, "y_start": 0 }
+            }
+        });
+
+        tiny_skia::Pixmap::new(128, 64)
+            .ok_or_else(|| anyhow!("Failed to create the sprite sheet image"))?
+            .save_png(directory.join(DEFAULT_SPRITE_SHEET_IMAGE))?;
+        std::fs::write(
+            &sprite_sheet_path,
+            serde_json::to_string_pretty(&sprite_sheet)?,
+        )?;
+    }
+
+    Storage::new(path, BACKUP_COUNT).write(&ConfigFile::V2(config))
+}
+
+fn storage(path: &str) -> Storage<ConfigFile> {
+    Storage::new(path, BACKUP_COUNT)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -63,4 +199,179 @@ mod test {
         let config = load("test_files/config/config.json");
         assert!(config.is_ok());
     }
+
+    #[test]
+    fn test_write_default() {
+        let directory = std::env::temp_dir().join("oscpie_test_write_default");
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join("config/config.json");
+        let path = path.to_str().unwrap();
+
+        write_default(path).unwrap();
+
+        let config = load(path).unwrap();
+        let sprite_sheet = oscpie_ui::sprite::SpriteSheet::load(
+            directory.join("config").join(&config.sprite_sheet),
+        )
+        .unwrap();
+
+        assert!(validate(&config, &sprite_sheet).is_empty());
+        // Pie menu items draw their progress indicator with it
+        assert!(sprite_sheet.cutout("spin").is_some());
+    }
+
+    #[test]
+    fn test_write_default_renders() {
+        use crate::{
+            components::{pie_menu::Props, MenuComponent, REFERENCE_SIZE},
+            resource::SPRITE_SHEET,
+            theme::Theme,
+        };
+
+        let directory = std::env::temp_dir().join("oscpie_test_write_default_renders");
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join("config/config.json");
+        let path = path.to_str().unwrap();
+
+        write_default(path).unwrap();
+
+        let config = load(path).unwrap();
+        // Other tests may have loaded theirs already, the cutouts are asserted above regardless
+        SPRITE_SHEET.get_or_init(|| {
+            oscpie_ui::sprite::SpriteSheet::load(
+                directory.join("config").join(&config.sprite_sheet),
+            )
+            .unwrap()
+        });
+
+        let mut pie_menu = MenuComponent::new(
+            &Menu::from_config(&config.menus[&config.root]),
+            &ActionContext::new(std::sync::mpsc::channel().0),
+            Theme::from_config(&config.theme),
+            REFERENCE_SIZE,
+        );
+        pie_menu.update(&Props::new(PieMenuInput::new(0.0, 0.0, 0.0)));
+
+        let mut pixmap = tiny_skia::Pixmap::new(512, 512).unwrap();
+        pie_menu.render(&mut pixmap);
+
+        assert!(pixmap.pixels().iter().any(|pixel| pixel.alpha() > 0));
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema: serde_json::Value = serde_json::from_str(&schema().unwrap()).unwrap();
+        let schema = schema.to_string();
+
+        assert!(schema.contains("config_version"));
+        assert!(schema.contains("V2"));
+        assert!(schema.contains("label"));
+    }
+
+    #[test]
+    fn test_migrate_v1_to_v2() {
+        let config_file: ConfigFile = serde_json::from_str(
+            r#"{
+                "config_version": "V1",
+                "config": {
+                    "root": "root",
+                    "sprite_sheet": "",
+                    "menus": {
+                        "root": {
+                            "items": [{ "action": { "type": "StartTutorial" }, "icon": "tutorial" }]
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config_file.version(), 1);
+
+        let config = read(config_file).unwrap();
+        let item = &config.menus[&config.root].items[0];
+
+        assert_eq!(item.icon.as_deref(), Some("tutorial"));
+        assert!(item.label.is_none());
+    }
+
+    #[test]
+    fn test_migrate_v1_actions_and_gesture() {
+        let config_file: ConfigFile = serde_json::from_str(
+            r#"{
+                "config_version": "V1",
+                "config": {
+                    "root": "root",
+                    "sprite_sheet": "",
+                    "open_gesture": {
+                        "type": "All",
+                        "of": [
+                            { "type": "Action", "name": "OpenLeft" },
+                            { "type": "Not", "of": { "type": "Action", "name": "GripLeft" } }
+                        ]
+                    },
+                    "menus": {
+                        "root": {
+                            "items": [
+                                { "action": { "type": "SubMenu", "to": "root" } },
+                                {
+                                    "action": {
+                                        "type": "OscSend",
+                                        "parameter": "Wave",
+                                        "value": { "type": "Bool", "value": true }
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let config = read(config_file).unwrap();
+        let items = &config.menus[&config.root].items;
+
+        assert!(matches!(
+            &items[0].action,
+            types::MenuItemAction::SubMenu { to } if to.inner() == "root"
+        ));
+        assert!(matches!(
+            &items[1].action,
+            types::MenuItemAction::Custom(action) if action.kind == "OscSend"
+        ));
+        assert_eq!(
+            config.open_gesture,
+            types::InputExpression::All {
+                of: vec![
+                    types::InputExpression::Action {
+                        name: "OpenLeft".to_string()
+                    },
+                    types::InputExpression::Not {
+                        of: Box::new(types::InputExpression::Action {
+                            name: "GripLeft".to_string()
+                        })
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_v2_round_trip() {
+        let config = load("test_files/config/config.json").unwrap();
+        let mut config_file = ConfigFile::V2(config);
+
+        if let ConfigFile::V2(config) = &mut config_file {
+            let item = &mut config.menus.get_mut(&config.root).unwrap().items[0];
+            item.label = Some("Label".to_string());
+            item.color = Some("#ff8800".to_string());
+        }
+
+        let json = serde_json::to_string(&config_file).unwrap();
+        let config = read(serde_json::from_str(&json).unwrap()).unwrap();
+        let item = &config.menus[&config.root].items[0];
+
+        assert_eq!(item.label.as_deref(), Some("Label"));
+        assert_eq!(item.color.as_deref(), Some("#ff8800"));
+    }
 }
@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+use super::{
+    snippet::append_menus,
+    types::{Menu, MenuId},
+    Config,
+};
+
+/// A file listed in `include`, holding menus and possibly including further files.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MenuInclude {
+    /// Paths relative to this file.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub menus: HashMap<MenuId, Menu>,
+}
+
+/// Merges the menus of the files listed in `config.include` into `config.menus`, like snippets.
+pub fn resolve(config: &mut Config, config_path: &Path) -> Result<()> {
+    let mut resolver = Resolver {
+        menus: std::mem::take(&mut config.menus),
+        stack: vec![canonicalize(config_path)?],
+        visited: vec![],
+    };
+
+    for include in &config.include {
+        resolver.merge_file(&relative_to(config_path, include))?;
+    }
+
+    config.menus = resolver.menus;
+
+    Ok(())
+}
+
+struct Resolver {
+    menus: HashMap<MenuId, Menu>,
+    /// Files currently being included, to detect cycles.
+    stack: Vec<PathBuf>,
+    /// Files already merged, so that a file included twice is merged once.
+    visited: Vec<PathBuf>,
+}
+
+impl Resolver {
+    fn merge_file(&mut self, path: &Path) -> Result<()> {
+        let canonical = canonicalize(path)?;
+
+        if self.stack.contains(&canonical) {
+            let chain: Vec<String> = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|path| path.display().to_string())
+                .collect();
+
+            return Err(anyhow!("Include cycle: {}", chain.join(" -> ")));
+        }
+
+        if self.visited.contains(&canonical) {
+            log::debug!("{} is already included", path.display());
+            return Ok(());
+        }
+
+        let file = std::fs::File::open(path).map_err(|e| anyhow!("{}: {e}", path.display()))?;
+        let include: MenuInclude = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| anyhow!("{}: {e}", path.display()))?;
+
+        append_menus(&mut self.menus, include.menus);
+
+        self.stack.push(canonical.clone());
+
+        for nested in &include.include {
+            self.merge_file(&relative_to(path, nested))?;
+        }
+
+        self.stack.pop();
+        self.visited.push(canonical);
+
+        Ok(())
+    }
+}
+
+fn relative_to(including: &Path, included: &str) -> PathBuf {
+    including.parent().unwrap_or(Path::new("")).join(included)
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf> {
+    path.canonicalize()
+        .map_err(|e| anyhow!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, json: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, json).unwrap();
+        path
+    }
+
+    fn config(include: &[&str]) -> Config {
+        let mut config: Config = serde_json::from_str(
+            r#"{
+                "root": "root",
+                "sprite_sheet": "",
+                "menus": {
+                    "root": { "items": [{ "action": { "type": "SubMenu", "to": "media" } }] }
+                }
+            }"#,
+        )
+        .unwrap();
+        config.include = include.iter().map(ToString::to_string).collect();
+        config
+    }
+
+    #[test]
+    fn test_resolve() {
+        let dir = std::env::temp_dir().join("oscpie_test_include_resolve");
+        let config_path = write(&dir, "config.json", "{}");
+        write(
+            &dir,
+            "menus/media.json",
+            r#"{
+                "include": ["more.json", "more.json"],
+                "menus": { "media": { "items": [{ "action": { "type": "StartTutorial" } }] } }
+            }"#,
+        );
+        write(
+            &dir,
+            "menus/more.json",
+            r#"{ "menus": { "root": { "items": [{ "action": { "type": "Notifications" } }] } } }"#,
+        );
+
+        let mut config = config(&["menus/media.json"]);
+        resolve(&mut config, &config_path).unwrap();
+
+        assert_eq!(config.menus[&config.root].items.len(), 2);
+        assert!(config.menus.keys().any(|id| id.inner() == "media"));
+    }
+
+    #[test]
+    fn test_resolve_cycle() {
+        let dir = std::env::temp_dir().join("oscpie_test_include_cycle");
+        let config_path = write(&dir, "config.json", "{}");
+        write(&dir, "a.json", r#"{ "include": ["b.json"] }"#);
+        write(&dir, "b.json", r#"{ "include": ["a.json"] }"#);
+
+        let error = resolve(&mut config(&["a.json"]), &config_path).unwrap_err();
+
+        assert!(error.to_string().starts_with("Include cycle"), "{error}");
+    }
+}
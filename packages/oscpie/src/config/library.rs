@@ -0,0 +1,73 @@
+use super::{
+    types::{MenuItem, MenuItemAction},
+    Config,
+};
+
+/// Replaces items referring to `config.item_library` with the library item. Fields set on the
+/// referring item, such as an anchor, take precedence over the library item's.
+///
+/// References to items which do not exist are left as they are, for validation to report.
+pub fn resolve(config: &mut Config) {
+    for menu in config.menus.values_mut() {
+        for item in &mut menu.items {
+            let MenuItemAction::Library { item: id } = &item.action else {
+                continue;
+            };
+
+            let Some(library_item) = config.item_library.get(id) else {
+                continue;
+            };
+
+            *item = with_overrides(library_item.clone(), item.clone());
+        }
+    }
+}
+
+fn with_overrides(item: MenuItem, overrides: MenuItem) -> MenuItem {
+    MenuItem {
+        action: item.action,
+        icon: overrides.icon.or(item.icon),
+        anchor: overrides.anchor.or(item.anchor),
+        keep_open: overrides.keep_open.or(item.keep_open),
+        label: overrides.label.or(item.label),
+        color: overrides.color.or(item.color),
+        description: overrides.description.or(item.description),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let mut config: Config = serde_json::from_str(
+            r#"{
+                "root": "root",
+                "sprite_sheet": "",
+                "item_library": {
+                    "tutorial": { "action": { "type": "StartTutorial" }, "icon": "2", "label": "Tutorial" }
+                },
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "Library", "item": "tutorial" } },
+                            { "action": { "type": "Library", "item": "tutorial" }, "icon": "3" },
+                            { "action": { "type": "Library", "item": "missing" } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        resolve(&mut config);
+
+        let items = &config.menus[&config.root].items;
+        assert!(matches!(items[0].action, MenuItemAction::StartTutorial));
+        assert_eq!(items[0].icon.as_deref(), Some("2"));
+        assert_eq!(items[1].icon.as_deref(), Some("3"));
+        assert_eq!(items[1].label.as_deref(), Some("Tutorial"));
+        assert!(matches!(items[2].action, MenuItemAction::Library { .. }));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+use super::types::{Menu, MenuId, MenuItemAction};
+use super::Config;
+
+/// A fragment of configuration that is merged into an existing config.
+///
+/// Items of menus which already exist are appended to them, and unknown menus are added as new.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuSnippet {
+    pub menus: HashMap<MenuId, Menu>,
+}
+
+/// Appends the items of `other` to the menus with the same ID, adding menus which are new.
+pub fn append_menus(menus: &mut HashMap<MenuId, Menu>, other: HashMap<MenuId, Menu>) {
+    for (id, menu) in other {
+        menus.entry(id).or_default().items.extend(menu.items);
+    }
+}
+
+pub fn merge_snippet(config: &Config, snippet: MenuSnippet) -> Result<Config> {
+    if snippet.menus.values().all(|menu| menu.items.is_empty()) {
+        return Err(anyhow!("Snippet does not contain any menu items"));
+    }
+
+    let mut merged = config.clone();
+    append_menus(&mut merged.menus, snippet.menus);
+
+    for (id, menu) in &merged.menus {
+        for item in &menu.items {
+            if let MenuItemAction::SubMenu { to } = &item.action {
+                if !merged.menus.contains_key(to) {
+                    return Err(anyhow!(
+                        "Menu '{}' refers to unknown menu '{}'",
+                        id.inner(),
+                        to.inner()
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_str(
+            r#"{
+                "root": "root",
+                "sprite_sheet": "",
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "root" } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merge_snippet_adds_menu() {
+        let snippet: MenuSnippet = serde_json::from_str(
+            r#"{
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "obs" } }
+                        ]
+                    },
+                    "obs": {
+                        "items": [
+                            { "action": { "type": "Exec", "program_path": "obs64.exe", "args": [] } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let merged = merge_snippet(&config(), snippet).unwrap();
+
+        assert_eq!(merged.menus.len(), 2);
+        assert_eq!(
+            merged.menus[&serde_json::from_str::<MenuId>("\"root\"").unwrap()]
+                .items
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_merge_snippet_rejects_unknown_sub_menu() {
+        let snippet: MenuSnippet = serde_json::from_str(
+            r#"{
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "missing" } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(merge_snippet(&config(), snippet).is_err());
+    }
+
+    #[test]
+    fn test_merge_snippet_rejects_empty() {
+        let snippet = MenuSnippet {
+            menus: HashMap::new(),
+        };
+
+        assert!(merge_snippet(&config(), snippet).is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Hash)]
 #[serde(transparent)]
 pub struct MenuId(String);
 
@@ -12,7 +13,7 @@ impl MenuId {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "key")]
 pub enum KeyAction {
     Down(u16), // ScanCode
@@ -21,7 +22,15 @@ pub enum KeyAction {
 
 pub type KeyStroke = Vec<KeyAction>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "value")]
+pub enum OscValue {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum MenuItemAction {
     SubMenu {
@@ -34,22 +43,218 @@ pub enum MenuItemAction {
         program_path: String,
         args: Vec<String>,
     },
+    StartTutorial,
+    /// Opens the built-in menu listing recent notifications.
+    Notifications,
+    /// Any other `type`, provided by a behaviour registered with `CoreBuilder::register_behaviour`.
+    #[serde(untagged)]
+    Custom(CustomAction),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CustomAction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The remaining fields of the action, handed to the behaviour's factory.
+    #[serde(flatten)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Cardinal {
+    North,
+    East,
+    South,
+    West,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MenuItem {
     pub action: MenuItemAction,
     pub icon: Option<String>,
+    pub anchor: Option<Cardinal>,
+    /// Overrides [`Config::after_action`] for this item: `true` stays open, `false` closes.
+    pub keep_open: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Menu {
     pub items: Vec<MenuItem>,
+    /// Rotation of the whole menu in degrees, clockwise. Ignored when any item has an anchor.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+/// Boolean expression over digital action names such as `OpenLeft` or `GripLeft`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum InputExpression {
+    Action { name: String },
+    All { of: Vec<InputExpression> },
+    Any { of: Vec<InputExpression> },
+    Not { of: Box<InputExpression> },
+}
+
+impl Default for InputExpression {
+    fn default() -> Self {
+        InputExpression::Action {
+            name: "OpenLeft".to_string(),
+        }
+    }
+}
+
+/// What the menu does after an item's action has run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub enum AfterAction {
+    #[default]
+    StayOpen,
+    Close,
+    ReturnToRoot,
+}
+
+/// Source of the scene brightness used by the automatic contrast adjustment.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum BrightnessSource {
+    TimeOfDay {
+        #[serde(default)]
+        utc_offset_hours: f32,
+    },
+    /// Brightness is sent as `SetSceneBrightness` control messages.
+    External,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct OscConfig {
+    pub host: String,
+    pub send_port: u16,
+    pub receive_port: u16,
+    /// Find the `VRChat` endpoint with `OSCQuery` and advertise the receiver, instead of relying on
+    /// `host` and the ports alone.
+    pub discover: bool,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            send_port: 9000,
+            receive_port: 9001,
+            discover: true,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// What a quick sideways swipe of the controller does while the menu is open.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub enum SwipeAction {
+    Back,
+    Close,
+    ReturnToRoot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SwipeConfig {
+    /// Sideways controller speed in meters per second from which a swipe is detected.
+    pub min_speed: f32,
+    /// Further swipes are ignored for this long after one fired.
+    pub cooldown_ms: u64,
+    pub left: Option<SwipeAction>,
+    pub right: Option<SwipeAction>,
+}
+
+impl Default for SwipeConfig {
+    fn default() -> Self {
+        Self {
+            min_speed: 1.5,
+            cooldown_ms: 400,
+            left: Some(SwipeAction::Back),
+            right: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    CubicBezier { x1: f32, y1: f32, x2: f32, y2: f32 },
+    Spring { stiffness: f32, damping: f32 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Transition {
+    Smooth { speed: f32 },
+    Tween { duration_ms: u32, easing: Easing },
+}
+
+/// Overrides of the built-in transitions. Unset ones keep the default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Animations {
+    /// Size of an item's icon while it is hovered or pressed.
+    pub icon_grow: Option<Transition>,
+    /// Size of the spinner shown while an item's action is active.
+    pub spin_icon: Option<Transition>,
+}
+
+/// What happens when the controller driving the open menu loses tracking.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TrackingLossConfig {
+    /// Selection stops following the input after tracking is lost for this long.
+    pub freeze_ms: u64,
+    /// The menu closes after tracking is lost for this long.
+    pub close_ms: u64,
+}
+
+impl Default for TrackingLossConfig {
+    fn default() -> Self {
+        Self {
+            freeze_ms: 100,
+            close_ms: 1500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     pub menus: HashMap<MenuId, Menu>,
     pub root: MenuId,
     pub sprite_sheet: String,
+    /// Gesture which toggles the menu. Defaults to the `OpenLeft` action alone.
+    #[serde(default)]
+    pub open_gesture: InputExpression,
+    #[serde(default)]
+    pub after_action: AfterAction,
+    /// Set once the first launch tutorial has been finished or dismissed.
+    #[serde(default)]
+    pub tutorial_completed: bool,
+    /// Adjusts the menu contrast to the scene brightness when set.
+    pub auto_contrast: Option<BrightnessSource>,
+    #[serde(default)]
+    pub osc: OscConfig,
+    /// Number of notifications kept for the notifications menu.
+    #[serde(default = "default_notification_history")]
+    pub notification_history: usize,
+    /// Navigates with controller swipes when set.
+    pub swipe: Option<SwipeConfig>,
+    /// Keeps local usage statistics next to the config. Nothing is uploaded.
+    #[serde(default)]
+    pub analytics: bool,
+    #[serde(default)]
+    pub animations: Animations,
+    #[serde(default)]
+    pub tracking_loss: TrackingLossConfig,
+}
+
+pub(super) fn default_notification_history() -> usize {
+    20
 }
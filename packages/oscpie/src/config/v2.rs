@@ -0,0 +1,577 @@
+//! Version 2 adds a label, a color and a description to menu items.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::v1;
+pub use super::v1::{
+    AfterAction, BrightnessSource, Cardinal, CustomAction, Easing, KeyAction, KeyStroke, MenuId,
+    OscConfig, OscValue, SwipeAction, SwipeConfig, TrackingLossConfig, Transition,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum MenuItemAction {
+    SubMenu {
+        to: MenuId,
+    },
+    KeyStroke {
+        key_stroke: KeyStroke,
+    },
+    Exec {
+        program_path: String,
+        args: Vec<String>,
+    },
+    StartTutorial,
+    /// Opens the built-in menu listing recent notifications.
+    Notifications,
+    /// Runs `on` and `off` in turn, and shows whether it is on. With `persist`, the state is kept
+    /// under this name between runs.
+    Toggle {
+        on: Box<MenuItemAction>,
+        off: Box<MenuItemAction>,
+        #[serde(default)]
+        persist: Option<String>,
+    },
+    /// Stands for the item with this ID in the config's `item_library`.
+    Library {
+        item: String,
+    },
+    /// Switches to another of the config's `profiles`.
+    SwitchProfile {
+        profile: String,
+    },
+    /// Runs `action` only once the item has been held for `hold_ms`, for actions which should not
+    /// happen by accident such as shutting down. Defaults to 800 ms.
+    Hold {
+        action: Box<MenuItemAction>,
+        #[serde(default)]
+        hold_ms: Option<u64>,
+    },
+    /// Adjusts a value from 0.0 to 1.0 while the item is held and the stick is turned.
+    Slider {
+        target: SliderTarget,
+    },
+    /// Any other `type`, provided by a behaviour registered with `CoreBuilder::register_behaviour`.
+    #[serde(untagged)]
+    Custom(CustomAction),
+}
+
+impl From<v1::MenuItemAction> for MenuItemAction {
+    fn from(action: v1::MenuItemAction) -> Self {
+        match action {
+            v1::MenuItemAction::SubMenu { to } => Self::SubMenu { to },
+            v1::MenuItemAction::KeyStroke { key_stroke } => Self::KeyStroke { key_stroke },
+            v1::MenuItemAction::Exec { program_path, args } => Self::Exec { program_path, args },
+            v1::MenuItemAction::StartTutorial => Self::StartTutorial,
+            v1::MenuItemAction::Notifications => Self::Notifications,
+            v1::MenuItemAction::Custom(action) => Self::Custom(action),
+        }
+    }
+}
+
+/// Fields of the `OscSend` action, which sets an avatar parameter over OSC or flips it on and off
+/// with `toggle`. Available with the `osc` feature.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OscSendAction {
+    pub parameter: String,
+    pub value: OscValue,
+    #[serde(default)]
+    pub toggle: bool,
+}
+
+/// What a `Slider` item adjusts.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum SliderTarget {
+    /// A float avatar parameter over OSC. Available with the `osc` feature.
+    OscFloat { parameter: String },
+    /// Master volume of the default playback device.
+    SystemVolume,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MenuItem {
+    pub action: MenuItemAction,
+    pub icon: Option<String>,
+    pub anchor: Option<Cardinal>,
+    /// Overrides [`Config::after_action`] for this item: `true` stays open, `false` closes.
+    pub keep_open: Option<bool>,
+    /// Short name of the item, shown in place of the action's own description.
+    pub label: Option<String>,
+    /// Accent color of the item as a hex string such as `#ff8800`.
+    pub color: Option<String>,
+    /// Longer explanation of what the item does.
+    pub description: Option<String>,
+}
+
+impl From<v1::MenuItem> for MenuItem {
+    fn from(item: v1::MenuItem) -> Self {
+        Self {
+            action: item.action.into(),
+            icon: item.icon,
+            anchor: item.anchor,
+            keep_open: item.keep_open,
+            label: None,
+            color: None,
+            description: None,
+        }
+    }
+}
+
+/// How a menu is drawn and pointed at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum MenuLayout {
+    #[default]
+    Pie,
+    /// Items in a row, selected by the horizontal position of the stick.
+    QuickBar,
+    /// Items on the half of the pie facing `towards`, leaving the other half free.
+    HalfCircle { towards: Cardinal },
+    /// Items in a square grid, selected by where the stick points. Pie menus with too many items
+    /// to hit reliably fall back to this.
+    Grid,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+    /// Rotation of the whole menu in degrees, clockwise. Ignored when any item has an anchor.
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default)]
+    pub layout: MenuLayout,
+    /// Splits pie menus with more items into pages of this many, with items to turn the page
+    /// around them. Without it, pie menus with too many items are shown as a grid.
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
+impl From<v1::Menu> for Menu {
+    fn from(menu: v1::Menu) -> Self {
+        Self {
+            items: menu.items.into_iter().map(MenuItem::from).collect(),
+            rotation: menu.rotation,
+            layout: MenuLayout::Pie,
+            page_size: None,
+        }
+    }
+}
+
+/// Boolean expression over digital action names such as `OpenLeft` or `GripLeft`, or hand poses
+/// from finger tracking such as `FistLeft` or `FingerGunLeft`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum InputExpression {
+    Action { name: String },
+    All { of: Vec<InputExpression> },
+    Any { of: Vec<InputExpression> },
+    Not { of: Box<InputExpression> },
+}
+
+impl Default for InputExpression {
+    fn default() -> Self {
+        InputExpression::Action {
+            name: "OpenLeft".to_string(),
+        }
+    }
+}
+
+impl From<v1::InputExpression> for InputExpression {
+    fn from(expression: v1::InputExpression) -> Self {
+        match expression {
+            v1::InputExpression::Action { name } => Self::Action { name },
+            v1::InputExpression::All { of } => Self::All {
+                of: of.into_iter().map(Self::from).collect(),
+            },
+            v1::InputExpression::Any { of } => Self::Any {
+                of: of.into_iter().map(Self::from).collect(),
+            },
+            v1::InputExpression::Not { of } => Self::Not {
+                of: Box::new((*of).into()),
+            },
+        }
+    }
+}
+
+/// Overrides of the built-in transitions. Unset ones keep the default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Animations {
+    /// Size of an item's icon while it is hovered or pressed.
+    pub icon_grow: Option<Transition>,
+    /// Size of the spinner shown while an item's action is active.
+    pub spin_icon: Option<Transition>,
+    /// Progress of the zoom between a menu and its sub menu.
+    pub menu_transition: Option<Transition>,
+}
+
+impl From<v1::Animations> for Animations {
+    fn from(animations: v1::Animations) -> Self {
+        Self {
+            icon_grow: animations.icon_grow,
+            spin_icon: animations.spin_icon,
+            menu_transition: None,
+        }
+    }
+}
+
+/// Font of the item labels.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FontConfig {
+    /// Font file relative to this config. Defaults to Segoe UI from the Windows fonts.
+    pub path: Option<String>,
+    /// Size of the labels under the icons in pixels. The hovered item's label in the center is
+    /// drawn larger.
+    #[serde(default = "default_font_size")]
+    pub size: f32,
+}
+
+impl Default for FontConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            size: default_font_size(),
+        }
+    }
+}
+
+fn default_font_size() -> f32 {
+    20.0
+}
+
+/// Look of the menus: a preset, with any of its values overridden. Colors are hex strings such
+/// as `#1a1a33`, or `#1a1a33cc` with alpha.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    pub background: Option<String>,
+    /// Fill of the center circle.
+    pub center: Option<String>,
+    /// Hovered items, slider values and hold progress.
+    pub highlight: Option<String>,
+    pub separator: Option<String>,
+    /// Width of the lines between items in pixels.
+    pub separator_width: Option<f32>,
+    /// Radius of the center circle, relative to the menu radius.
+    pub center_radius: Option<f32>,
+    /// Distance of the icons from the center, relative to the menu radius.
+    pub item_radius: Option<f32>,
+    /// Color of the item labels.
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Hand {
+    #[default]
+    Left,
+    Right,
+    /// Whichever hand did the open gesture last.
+    Both,
+}
+
+/// Size and look of the menu overlay in VR.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct OverlayConfig {
+    /// Width of the menu in meters.
+    pub width_m: f32,
+    /// Opacity of the whole menu, from `0.0` to `1.0`.
+    pub alpha: f32,
+    /// Bends the menu around the viewer, from `0.0` for flat to `1.0` for a full circle. Head
+    /// gaze selection assumes a flat menu.
+    pub curvature: f32,
+    /// Color multiplied into the menu as a hex string such as `#ffcc88`.
+    pub tint: Option<String>,
+    /// Overrides the menu's sort order, to draw it over other applications' overlays.
+    pub sort_order: Option<u32>,
+    /// Width and height of the menu texture in pixels. Labels scale along, so that the menu looks
+    /// the same but sharper on higher resolutions.
+    pub resolution: u32,
+    /// Renders the menu at this multiple of `resolution` and lets the runtime filter it down, which
+    /// smooths the edges of the slices. `1` turns it off.
+    pub supersampling: u32,
+    /// How the menu is handed to SteamVR.
+    pub texture: OverlayTexture,
+    /// Vulkan device to upload the menu with, on systems with several GPUs. By default the one
+    /// the headset is connected to. Read at startup, and overridden by `oscpie --gpu`.
+    pub gpu: Option<GpuSelector>,
+}
+
+impl OverlayConfig {
+    /// Size in pixels of the texture the menu is rendered into.
+    pub fn texture_size(&self) -> u32 {
+        self.resolution.max(1) * self.supersampling.max(1)
+    }
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            width_m: 1.0,
+            alpha: 1.0,
+            curvature: 0.0,
+            tint: None,
+            sort_order: None,
+            resolution: 512,
+            supersampling: 1,
+            texture: OverlayTexture::default(),
+            gpu: None,
+        }
+    }
+}
+
+/// Picks one of the GPUs, which are logged with their index at startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum GpuSelector {
+    Index(usize),
+    /// Part of the name, such as `RTX 4070`, ignoring case.
+    Name(String),
+}
+
+impl GpuSelector {
+    /// Reads an index, or else a name.
+    pub fn parse(selector: &str) -> Self {
+        selector
+            .parse()
+            .map_or_else(|_| Self::Name(selector.to_string()), Self::Index)
+    }
+
+    pub fn matches(&self, index: usize, name: &str) -> bool {
+        match self {
+            Self::Index(selected) => *selected == index,
+            Self::Name(selected) => name.to_lowercase().contains(&selected.to_lowercase()),
+        }
+    }
+}
+
+/// Kind of texture the menu is uploaded into for SteamVR. OpenXR runtimes always use Vulkan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum OverlayTexture {
+    #[default]
+    Vulkan,
+    /// A Direct3D 11 texture SteamVR opens through its shared handle, for systems where the
+    /// Vulkan interop shows a black or garbled menu.
+    DirectX,
+}
+
+/// Tracked device the menu is attached to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum AttachmentTarget {
+    /// The hand which drives the menu, see [`Config::hand`].
+    #[default]
+    ActiveHand,
+    LeftHand,
+    RightHand,
+    Hmd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AttachmentConfig {
+    pub target: AttachmentTarget,
+    /// Placement of the menu relative to the target, as rows of a 3x4 matrix with the
+    /// translation in meters in the last column, like OpenVR's `HmdMatrix34_t`.
+    pub offset: [[f32; 4]; 3],
+}
+
+impl Default for AttachmentConfig {
+    fn default() -> Self {
+        Self {
+            target: AttachmentTarget::default(),
+            offset: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+            ],
+        }
+    }
+}
+
+/// VR runtime oscpie runs on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Runtime {
+    /// SteamVR, through OpenVR overlays.
+    #[default]
+    OpenVr,
+    /// Any OpenXR runtime supporting the `XR_EXTX_overlay` extension. Needs oscpie to be built
+    /// with the `openxr` feature. Notifications, profile switching by app and battery warnings
+    /// are SteamVR only.
+    OpenXr,
+}
+
+/// What points at the items. Items are clicked with the controller either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SelectionMode {
+    #[default]
+    Stick,
+    /// The item the headset looks at is hovered, for when a thumbstick is hard to use.
+    HeadGaze,
+    /// SteamVR's laser pointer points at the items and its trigger clicks, like on a dashboard
+    /// panel.
+    Laser,
+}
+
+/// Thresholds which keep the hovered item from flickering when the stick rests near an edge.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SelectionConfig {
+    pub mode: SelectionMode,
+    /// Stick deflection, from `0.0` to `1.0`, past which an item becomes hovered.
+    pub enter_magnitude: f32,
+    /// Stick deflection under which the hovered item stops being hovered.
+    pub exit_magnitude: f32,
+    /// Degrees the hovered item stays hovered past its edges, and that the stick has to be inside
+    /// any other item to move on to it.
+    pub snap_margin_deg: f32,
+}
+
+impl Default for SelectionConfig {
+    fn default() -> Self {
+        Self {
+            mode: SelectionMode::default(),
+            enter_magnitude: 0.55,
+            exit_magnitude: 0.45,
+            snap_margin_deg: 3.0,
+        }
+    }
+}
+
+/// A named set of menus, such as `Desktop` or `Streaming`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Profile {
+    /// Menu shown at the bottom of the stack while the profile is active.
+    pub root: MenuId,
+    /// Application keys of VR apps, such as `steam.app.438100`, which switch to this profile
+    /// while they render the scene.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Config {
+    /// Files with more menus, relative to this one. Their items are appended to menus with the
+    /// same ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    pub menus: HashMap<MenuId, Menu>,
+    pub root: MenuId,
+    /// Menu sets to switch between with `SwitchProfile` actions. [`Config::root`] is used until
+    /// one is switched to.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, Profile>,
+    /// Items shared between menus, used with `Library` actions. Changing one changes it in every
+    /// menu.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub item_library: HashMap<String, MenuItem>,
+    pub sprite_sheet: String,
+    /// Gesture which toggles the menu. Defaults to the `OpenLeft` action alone.
+    #[serde(default)]
+    pub open_gesture: InputExpression,
+    /// Hand which opens and drives the menu. The open gesture is written for the left hand and
+    /// mirrored for the right one.
+    #[serde(default)]
+    pub hand: Hand,
+    #[serde(default)]
+    pub after_action: AfterAction,
+    /// Set once the first launch tutorial has been finished or dismissed.
+    #[serde(default)]
+    pub tutorial_completed: bool,
+    /// Adjusts the menu contrast to the scene brightness when set.
+    pub auto_contrast: Option<BrightnessSource>,
+    #[serde(default)]
+    pub osc: OscConfig,
+    /// Number of notifications kept for the notifications menu.
+    #[serde(default = "v1::default_notification_history")]
+    pub notification_history: usize,
+    /// Navigates with controller swipes when set.
+    pub swipe: Option<SwipeConfig>,
+    /// Keeps local usage statistics next to the config. Nothing is uploaded.
+    #[serde(default)]
+    pub analytics: bool,
+    #[serde(default)]
+    pub animations: Animations,
+    #[serde(default)]
+    pub tracking_loss: TrackingLossConfig,
+    /// Places the menu where the controller will be this far ahead, so that it keeps up with fast
+    /// hand movement. SteamVR already predicts up to the next frame, a few milliseconds on top
+    /// are usually enough.
+    #[serde(default)]
+    pub pose_prediction_ms: u64,
+    #[serde(default)]
+    pub font: FontConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub selection: SelectionConfig,
+    /// Attaches the menu to a tracked device, which SteamVR then moves it along with. The menu
+    /// follows the pose of the hand driving it when unset.
+    pub attachment: Option<AttachmentConfig>,
+    #[serde(default)]
+    pub overlay: OverlayConfig,
+    #[serde(default)]
+    pub runtime: Runtime,
+}
+
+impl From<v1::Config> for Config {
+    fn from(config: v1::Config) -> Self {
+        Self {
+            include: vec![],
+            menus: config
+                .menus
+                .into_iter()
+                .map(|(id, menu)| (id, menu.into()))
+                .collect(),
+            root: config.root,
+            profiles: HashMap::new(),
+            item_library: HashMap::new(),
+            sprite_sheet: config.sprite_sheet,
+            open_gesture: config.open_gesture.into(),
+            hand: Hand::default(),
+            after_action: config.after_action,
+            tutorial_completed: config.tutorial_completed,
+            auto_contrast: config.auto_contrast,
+            osc: config.osc,
+            notification_history: config.notification_history,
+            swipe: config.swipe,
+            analytics: config.analytics,
+            animations: config.animations.into(),
+            tracking_loss: config.tracking_loss,
+            pose_prediction_ms: 0,
+            font: FontConfig::default(),
+            theme: ThemeConfig::default(),
+            selection: SelectionConfig::default(),
+            attachment: None,
+            overlay: OverlayConfig::default(),
+            runtime: Runtime::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_selector() {
+        assert_eq!(GpuSelector::parse("1"), GpuSelector::Index(1));
+        assert!(GpuSelector::parse("1").matches(1, "Intel(R) UHD Graphics"));
+
+        let selector = GpuSelector::parse("rtx 4070");
+        assert_eq!(selector, GpuSelector::Name("rtx 4070".to_string()));
+        assert!(selector.matches(0, "NVIDIA GeForce RTX 4070"));
+        assert!(!selector.matches(0, "Intel(R) UHD Graphics"));
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use oscpie_ui::sprite::SpriteSheet;
+
+use super::{
+    types::{MenuId, MenuItemAction, OverlayTexture, Runtime},
+    Config,
+};
+
+/// Largest menu texture, in pixels per side, which runtimes reliably accept.
+const MAX_TEXTURE_SIZE: u32 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// The config cannot be used.
+    Error,
+    /// The config works, but probably not as intended.
+    Warning,
+}
+
+/// Something wrong with a config, with where it is and how to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub level: Level,
+    /// Where the problem is, such as `menus.root[2]`.
+    pub path: String,
+    pub message: String,
+}
+
+impl Problem {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            level: Level::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level == Level::Error
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        };
+
+        write!(f, "{level}: {}: {}", self.path, self.message)
+    }
+}
+
+/// Checks what deserialization alone cannot, and returns every problem found.
+pub fn validate(config: &Config, sprite_sheet: &SpriteSheet) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    let mut menu_ids: Vec<&MenuId> = config.menus.keys().collect();
+    menu_ids.sort_by_key(|id| id.inner());
+
+    let known_menus = menu_ids
+        .iter()
+        .map(|id| id.inner())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if !config.menus.contains_key(&config.root) {
+        problems.push(Problem::error(
+            "root",
+            format!(
+                "menu '{}' does not exist, use one of: {known_menus}",
+                config.root.inner()
+            ),
+        ));
+    }
+
+    let mut profiles: Vec<_> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| *name);
+
+    let mut app_profiles: HashMap<&str, &str> = HashMap::new();
+
+    for (name, profile) in profiles {
+        if !config.menus.contains_key(&profile.root) {
+            problems.push(Problem::error(
+                format!("profiles.{name}.root"),
+                format!(
+                    "menu '{}' does not exist, use one of: {known_menus}",
+                    profile.root.inner()
+                ),
+            ));
+        }
+
+        for app in &profile.apps {
+            if let Some(first) = app_profiles.get(app.as_str()) {
+                problems.push(Problem::warning(
+                    format!("profiles.{name}.apps"),
+                    format!(
+                        "app '{app}' is also listed by profile '{first}', which is used for it"
+                    ),
+                ));
+            } else {
+                app_profiles.insert(app, name);
+            }
+        }
+    }
+
+    for menu_id in menu_ids {
+        for (index, item) in config.menus[menu_id].items.iter().enumerate() {
+            let path = format!("menus.{}[{index}]", menu_id.inner());
+
+            match &item.action {
+                MenuItemAction::SubMenu { to } if !config.menus.contains_key(to) => {
+                    problems.push(Problem::error(
+                        &path,
+                        format!(
+                            "opens menu '{}', which does not exist, use one of: {known_menus}",
+                            to.inner()
+                        ),
+                    ));
+                }
+                MenuItemAction::SwitchProfile { profile }
+                    if !config.profiles.contains_key(profile) =>
+                {
+                    let mut known_profiles: Vec<_> =
+                        config.profiles.keys().map(String::as_str).collect();
+                    known_profiles.sort_unstable();
+
+                    problems.push(Problem::error(
+                        &path,
+                        format!(
+                            "switches to profile '{profile}', which does not exist, use one of: {}",
+                            known_profiles.join(", ")
+                        ),
+                    ));
+                }
+                MenuItemAction::Library { item } => {
+                    let message = if config.item_library.contains_key(item) {
+                        format!("refers to library item '{item}', which refers to another one")
+                    } else {
+                        let mut known_items: Vec<_> =
+                            config.item_library.keys().map(String::as_str).collect();
+                        known_items.sort_unstable();
+
+                        format!(
+                            "refers to library item '{item}', which does not exist, use one of: {}",
+                            known_items.join(", ")
+                        )
+                    };
+
+                    problems.push(Problem::error(&path, message));
+                }
+                MenuItemAction::KeyStroke { key_stroke } if key_stroke.is_empty() => {
+                    problems.push(Problem::error(
+                        &path,
+                        "key stroke is empty, add at least one Down or Up key action",
+                    ));
+                }
+                _ => {}
+            }
+
+            if let Some(icon) = &item.icon {
+                if !sprite_sheet.contains(icon) {
+                    problems.push(Problem::warning(
+                        &path,
+                        format!(
+                            "icon '{icon}' is not in the sprite sheet, use one of: {}",
+                            sprite_sheet.names().join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    if config.overlay.texture_size() > MAX_TEXTURE_SIZE {
+        problems.push(Problem::error(
+            "overlay",
+            format!(
+                "resolution times supersampling is {}, at most {MAX_TEXTURE_SIZE} is supported",
+                config.overlay.texture_size()
+            ),
+        ));
+    }
+
+    if config.runtime == Runtime::OpenXr && config.overlay.texture != OverlayTexture::Vulkan {
+        problems.push(Problem::warning(
+            "overlay.texture",
+            "is ignored on OpenXR, which always uses Vulkan",
+        ));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let sprite_sheet =
+            SpriteSheet::load(std::path::PathBuf::from("config/spritesheet.json")).unwrap();
+
+        let config: Config = serde_json::from_str(
+            r#"{
+                "root": "main",
+                "sprite_sheet": "",
+                "runtime": "OpenXr",
+                "overlay": { "resolution": 2048, "supersampling": 4, "texture": "DirectX" },
+                "profiles": {
+                    "desktop": { "root": "root", "apps": ["steam.app.438100"] },
+                    "streaming": { "root": "obs", "apps": ["steam.app.438100"] }
+                },
+                "item_library": {
+                    "recenter": { "action": { "type": "StartTutorial" } }
+                },
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "tool" }, "icon": "2" },
+                            { "action": { "type": "KeyStroke", "key_stroke": [] }, "icon": "missing" },
+                            { "action": { "type": "SwitchProfile", "profile": "vrchat" } },
+                            { "action": { "type": "Library", "item": "mute" } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let problems: Vec<String> = validate(&config, &sprite_sheet)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            problems,
+            vec![
+                "error: root: menu 'main' does not exist, use one of: root",
+                "error: profiles.streaming.root: menu 'obs' does not exist, use one of: root",
+                "warning: profiles.streaming.apps: app 'steam.app.438100' is also listed by profile 'desktop', which is used for it",
+                "error: menus.root[0]: opens menu 'tool', which does not exist, use one of: root",
+                "error: menus.root[1]: key stroke is empty, add at least one Down or Up key action",
+                "warning: menus.root[1]: icon 'missing' is not in the sprite sheet, use one of: 2, 3, 4, 5, back, spin",
+                "error: menus.root[2]: switches to profile 'vrchat', which does not exist, use one of: desktop, streaming",
+                "error: menus.root[3]: refers to library item 'mute', which does not exist, use one of: recenter",
+                "error: overlay: resolution times supersampling is 8192, at most 4096 is supported",
+                "warning: overlay.texture: is ignored on OpenXR, which always uses Vulkan",
+            ]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use crate::utils::IntervalTimer;
+
+const POLL_INTERVAL_MS: f64 = 500.0;
+
+/// Notices changes to the config file by polling its modification time.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    interval_timer: IntervalTimer,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        Self {
+            modified: Self::modified_time(&path),
+            path,
+            interval_timer: IntervalTimer::new(POLL_INTERVAL_MS),
+        }
+    }
+
+    /// Returns whether the file has changed since the last call. Checks at most every
+    /// `POLL_INTERVAL_MS`.
+    pub fn poll(&mut self) -> bool {
+        if !self.interval_timer.update() {
+            return false;
+        }
+
+        self.check()
+    }
+
+    /// Takes the current state of the file as seen, e.g. after oscpie wrote it itself.
+    pub fn mark_seen(&mut self) {
+        self.modified = Self::modified_time(&self.path);
+    }
+
+    fn check(&mut self) -> bool {
+        let modified = Self::modified_time(&self.path);
+
+        // Editors may replace the file, skip the moment it does not exist
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+
+        true
+    }
+
+    fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn touch(path: &std::path::Path, offset: Duration) -> std::io::Result<()> {
+        std::fs::File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(SystemTime::now() + offset)
+    }
+
+    #[test]
+    fn test_config_watcher() {
+        let path = std::env::temp_dir().join("oscpie_test_config_watcher.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let mut watcher = ConfigWatcher::new(&path);
+        assert!(!watcher.check());
+
+        touch(&path, Duration::from_secs(10)).unwrap();
+        assert!(watcher.check());
+        assert!(!watcher.check());
+
+        touch(&path, Duration::from_secs(20)).unwrap();
+        watcher.mark_seen();
+        assert!(!watcher.check());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.check());
+    }
+}
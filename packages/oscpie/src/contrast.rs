@@ -0,0 +1,95 @@
+use std::f32::consts::PI;
+
+use crate::{config, prelude::*};
+
+/// Where the brightness of the scene behind the overlay comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrightnessSource {
+    /// Estimated from the local time of day, assuming daylight in passthrough.
+    TimeOfDay { utc_offset_hours: f32 },
+    /// Reported by another process through the control channel.
+    External,
+}
+
+impl From<config::types::BrightnessSource> for BrightnessSource {
+    fn from(source: config::types::BrightnessSource) -> Self {
+        match source {
+            config::types::BrightnessSource::TimeOfDay { utc_offset_hours } => {
+                BrightnessSource::TimeOfDay { utc_offset_hours }
+            }
+            config::types::BrightnessSource::External => BrightnessSource::External,
+        }
+    }
+}
+
+/// Derives a menu contrast in `0.0..=1.0` from the scene brightness, where `1.0` makes the menu
+/// fully opaque and darker so that it stays readable in front of a bright background.
+pub struct AutoContrast {
+    source: BrightnessSource,
+    external_brightness: f32,
+    contrast: ExponentialSmoothing<f32>,
+}
+
+impl AutoContrast {
+    pub fn new(source: BrightnessSource) -> Self {
+        Self {
+            source,
+            external_brightness: 0.0,
+            contrast: ExponentialSmoothing::new(0.0, 1.0),
+        }
+    }
+
+    pub fn set_brightness(&mut self, brightness: f32) {
+        self.external_brightness = brightness.clamp(0.0, 1.0);
+    }
+
+    pub fn update(&mut self, dt: f32) -> f32 {
+        let brightness = match self.source {
+            BrightnessSource::TimeOfDay { utc_offset_hours } => {
+                let secs = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f32();
+
+                daylight((secs / 3600.0 + utc_offset_hours).rem_euclid(24.0))
+            }
+            BrightnessSource::External => self.external_brightness,
+        };
+
+        self.contrast.update(contrast_for(brightness), dt)
+    }
+}
+
+/// Rough daylight curve peaking at noon, zero between 18:00 and 6:00.
+fn daylight(hour: f32) -> f32 {
+    (PI * (hour - 6.0) / 12.0).sin().max(0.0)
+}
+
+/// Dark scenes keep the default look, contrast only ramps up in bright ones.
+fn contrast_for(brightness: f32) -> f32 {
+    let t = ((brightness - 0.3) / 0.5).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_daylight() {
+        assert!(daylight(0.0) < 1e-5);
+        assert!((daylight(12.0) - 1.0).abs() < 1e-5);
+        assert!(daylight(20.0) < 1e-5);
+    }
+
+    #[test]
+    fn test_external_brightness_raises_contrast() {
+        let mut auto_contrast = AutoContrast::new(BrightnessSource::External);
+
+        assert!(auto_contrast.update(1.0) < 1e-5);
+
+        auto_contrast.set_brightness(1.0);
+
+        assert!(auto_contrast.update(10.0) > 0.99);
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{types::MenuId, MenuSnippet};
+
+/// Name of the local endpoint external tools connect to with
+/// `inter_process_channel::transport::connect`.
+pub const CONTROL_ENDPOINT: &str = "oscpie-control";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ControlMessage {
+    MergeMenuSnippet {
+        snippet: MenuSnippet,
+    },
+    /// Brightness of the scene behind the overlay, from `0.0` to `1.0`.
+    SetSceneBrightness {
+        brightness: f32,
+    },
+    /// Reads the config file again and rebuilds the menus.
+    ReloadConfig,
+    /// Opens the overlay on top of the given menu.
+    OpenMenu {
+        menu: MenuId,
+    },
+    CloseMenu,
+    SetVisibility {
+        visible: bool,
+    },
+    /// Writes the actions run recently and their results to the log.
+    LogRecentActions,
+    Shutdown,
+}
+
+/// Reads control messages from stdin and from clients of [`CONTROL_ENDPOINT`] on background threads.
+///
+/// The returned receiver is meant to be polled with `try_recv` from the main loop.
+pub fn listen() -> Receiver<ControlMessage> {
+    let (sender, receiver) = channel();
+
+    let stdin_sender = sender.clone();
+    std::thread::spawn(move || {
+        crate::power::register_current_thread("control");
+
+        forward(
+            inter_process_channel::receiver::<ControlMessage, _>(std::io::stdin()),
+            &stdin_sender,
+        );
+
+        log::debug!("Control channel on stdin closed");
+    });
+
+    std::thread::spawn(move || {
+        crate::power::register_current_thread("control_endpoint");
+
+        if let Err(e) = accept_clients(&sender) {
+            log::warn!("Control endpoint {CONTROL_ENDPOINT} is unavailable: {e}");
+        }
+    });
+
+    receiver
+}
+
+fn accept_clients(sender: &Sender<ControlMessage>) -> std::io::Result<()> {
+    let listener = inter_process_channel::transport::listen(CONTROL_ENDPOINT)?;
+
+    log::info!("Listening for control messages on {CONTROL_ENDPOINT}");
+
+    loop {
+        let (_, receiver) = listener.accept::<(), ControlMessage>()?;
+        let sender = sender.clone();
+
+        std::thread::spawn(move || {
+            forward(receiver, &sender);
+            log::debug!("Control client disconnected");
+        });
+    }
+}
+
+fn forward<R: std::io::Read>(
+    mut receiver: inter_process_channel::Receiver<ControlMessage, R>,
+    sender: &Sender<ControlMessage>,
+) {
+    for message in receiver.iter() {
+        match message {
+            Ok(message) => {
+                if sender.send(message).is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to receive control message: {e}");
+            }
+        }
+    }
+}
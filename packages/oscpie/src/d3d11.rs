@@ -0,0 +1,194 @@
+//! Direct3D 11 path for the overlay texture, for systems where SteamVR's Vulkan interop misbehaves.
+//! The texture is created as a shared resource, which SteamVR opens on its own device.
+
+use tiny_skia::Pixmap;
+use windows::{
+    core::Interface,
+    Win32::{
+        Foundation::HMODULE,
+        Graphics::{
+            Direct3D::{D3D_DRIVER_TYPE_HARDWARE, D3D_DRIVER_TYPE_UNKNOWN, D3D_FEATURE_LEVEL_11_0},
+            Direct3D11::{
+                D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11ShaderResourceView,
+                ID3D11Texture2D, D3D11_BIND_RENDER_TARGET, D3D11_BIND_SHADER_RESOURCE, D3D11_BOX,
+                D3D11_CREATE_DEVICE_FLAG, D3D11_RESOURCE_MISC_GENERATE_MIPS,
+                D3D11_RESOURCE_MISC_SHARED, D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC,
+                D3D11_USAGE_DEFAULT,
+            },
+            Dxgi::{
+                Common::{DXGI_FORMAT_R8G8B8A8_UNORM_SRGB, DXGI_SAMPLE_DESC},
+                CreateDXGIFactory1, IDXGIAdapter, IDXGIFactory1,
+            },
+        },
+    },
+};
+
+use crate::{
+    dirty_rows::DirtyRows,
+    openvr::{self, Overlay},
+    overlay_texture::OverlayTextureUploader,
+    prelude::*,
+};
+
+pub struct D3d11Uploader {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    texture: ID3D11Texture2D,
+    /// View to generate the mipmaps through, when the texture has them.
+    mipmap_view: Option<ID3D11ShaderResourceView>,
+}
+
+impl D3d11Uploader {
+    /// Creates the device on the adapter at `adapter_index`, which should be the one SteamVR
+    /// renders on, or on the default adapter. See [`OverlayTextureUploader::resize`] for the rest.
+    pub fn new(pixmap: &Pixmap, adapter_index: Option<u32>, mipmaps: bool) -> Result<Self> {
+        let adapter = adapter_index
+            .map(|adapter_index| -> Result<IDXGIAdapter> {
+                let factory: IDXGIFactory1 = unsafe { CreateDXGIFactory1()? };
+                let adapter = unsafe { factory.EnumAdapters1(adapter_index)? };
+
+                Ok(adapter.cast()?)
+            })
+            .transpose()?;
+
+        // The driver type has to be unknown when passing an adapter
+        let driver_type = if adapter.is_some() {
+            D3D_DRIVER_TYPE_UNKNOWN
+        } else {
+            D3D_DRIVER_TYPE_HARDWARE
+        };
+
+        let mut device = None;
+        let mut context = None;
+
+        unsafe {
+            D3D11CreateDevice(
+                adapter.as_ref(),
+                driver_type,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_FLAG(0),
+                Some(&[D3D_FEATURE_LEVEL_11_0]),
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )?;
+        }
+
+        let device = device.ok_or_else(|| anyhow!("Failed to create a Direct3D 11 device"))?;
+        let context = context.ok_or_else(|| anyhow!("Failed to create a Direct3D 11 context"))?;
+        let (texture, mipmap_view) = create_texture(&device, pixmap, mipmaps)?;
+
+        Ok(Self {
+            device,
+            context,
+            texture,
+            mipmap_view,
+        })
+    }
+}
+
+impl OverlayTextureUploader for D3d11Uploader {
+    fn upload(&mut self, overlay: &Overlay, pixmap: &Pixmap, rows: DirtyRows) -> Result<()> {
+        let bytes = rows.byte_range(pixmap);
+
+        unsafe {
+            self.context.UpdateSubresource(
+                &self.texture,
+                0,
+                Some(&D3D11_BOX {
+                    left: 0,
+                    top: rows.start,
+                    front: 0,
+                    right: pixmap.width(),
+                    bottom: rows.end,
+                    back: 1,
+                }),
+                pixmap.data()[bytes].as_ptr().cast(),
+                pixmap.width() * 4,
+                0,
+            );
+
+            if let Some(mipmap_view) = &self.mipmap_view {
+                self.context.GenerateMips(mipmap_view);
+            }
+
+            // SteamVR reads the texture on its own device, so it has to be written by now
+            self.context.Flush();
+        }
+
+        overlay.set_overlay_texture(&mut openvr::Texture {
+            handle: openvr::TextureHandle::DirectX(self.texture.as_raw()),
+            texture_type: openvr::TextureType::DirectX,
+            color_space: openvr::ColorSpace::Auto,
+        })
+    }
+
+    fn resize(&mut self, pixmap: &Pixmap, mipmaps: bool) -> Result<()> {
+        (self.texture, self.mipmap_view) = create_texture(&self.device, pixmap, mipmaps)?;
+
+        Ok(())
+    }
+}
+
+/// Creates a shared texture the size of `pixmap`, and a view to generate its mipmaps through when
+/// it has them.
+#[allow(clippy::cast_sign_loss)]
+fn create_texture(
+    device: &ID3D11Device,
+    pixmap: &Pixmap,
+    mipmaps: bool,
+) -> Result<(ID3D11Texture2D, Option<ID3D11ShaderResourceView>)> {
+    let (mip_levels, bind_flags, misc_flags) = if mipmaps {
+        (
+            // All of them
+            0,
+            D3D11_BIND_SHADER_RESOURCE.0 | D3D11_BIND_RENDER_TARGET.0,
+            D3D11_RESOURCE_MISC_SHARED.0 | D3D11_RESOURCE_MISC_GENERATE_MIPS.0,
+        )
+    } else {
+        (
+            1,
+            D3D11_BIND_SHADER_RESOURCE.0,
+            D3D11_RESOURCE_MISC_SHARED.0,
+        )
+    };
+
+    let mut texture = None;
+
+    unsafe {
+        device.CreateTexture2D(
+            &D3D11_TEXTURE2D_DESC {
+                Width: pixmap.width(),
+                Height: pixmap.height(),
+                MipLevels: mip_levels,
+                ArraySize: 1,
+                Format: DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
+                SampleDesc: DXGI_SAMPLE_DESC {
+                    Count: 1,
+                    Quality: 0,
+                },
+                Usage: D3D11_USAGE_DEFAULT,
+                BindFlags: bind_flags as u32,
+                CPUAccessFlags: 0,
+                MiscFlags: misc_flags as u32,
+            },
+            None,
+            Some(&mut texture),
+        )?;
+    }
+
+    let texture = texture.ok_or_else(|| anyhow!("Failed to create the overlay texture"))?;
+
+    let mipmap_view = if mipmaps {
+        let mut view = None;
+
+        unsafe { device.CreateShaderResourceView(&texture, None, Some(&mut view))? };
+
+        view
+    } else {
+        None
+    };
+
+    Ok((texture, mipmap_view))
+}
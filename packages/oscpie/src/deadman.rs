@@ -0,0 +1,71 @@
+use crate::config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadmanState {
+    Tracking,
+    /// Tracking has been lost for a moment. Input is stale and must not move the selection.
+    Frozen,
+    /// Tracking has been lost for too long. The menu should close.
+    Expired,
+}
+
+/// Watches the tracking of the controller driving the menu while it is open.
+pub struct Deadman {
+    freeze_after_secs: f32,
+    close_after_secs: f32,
+    lost_for_secs: f32,
+}
+
+impl Deadman {
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(config: &config::types::TrackingLossConfig) -> Self {
+        Self {
+            freeze_after_secs: config.freeze_ms as f32 / 1000.0,
+            close_after_secs: config.close_ms as f32 / 1000.0,
+            lost_for_secs: 0.0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.lost_for_secs = 0.0;
+    }
+
+    pub fn update(&mut self, is_tracked: bool, dt: f32) -> DeadmanState {
+        if is_tracked {
+            self.lost_for_secs = 0.0;
+            return DeadmanState::Tracking;
+        }
+
+        self.lost_for_secs += dt;
+
+        if self.lost_for_secs > self.close_after_secs {
+            DeadmanState::Expired
+        } else if self.lost_for_secs > self.freeze_after_secs {
+            DeadmanState::Frozen
+        } else {
+            DeadmanState::Tracking
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadman() {
+        let mut deadman = Deadman::new(&config::types::TrackingLossConfig {
+            freeze_ms: 100,
+            close_ms: 1000,
+        });
+
+        assert_eq!(deadman.update(false, 0.05), DeadmanState::Tracking);
+        assert_eq!(deadman.update(false, 0.1), DeadmanState::Frozen);
+        assert_eq!(deadman.update(true, 0.1), DeadmanState::Tracking);
+        assert_eq!(deadman.update(false, 0.5), DeadmanState::Frozen);
+        assert_eq!(deadman.update(false, 0.6), DeadmanState::Expired);
+
+        deadman.reset();
+        assert_eq!(deadman.update(false, 0.05), DeadmanState::Tracking);
+    }
+}
@@ -0,0 +1,132 @@
+//! Tracked devices and their batteries, for menus and widgets to show.
+
+use crate::openvr::{
+    Handle, SystemInterface, TrackedControllerRole, TrackedDeviceClass, MAX_TRACKED_DEVICES,
+};
+
+/// Charge under which a device's battery counts as low.
+const LOW_BATTERY_LEVEL: f32 = 0.15;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub index: u32,
+    pub class: TrackedDeviceClass,
+    /// Hand a controller is held in.
+    pub role: Option<TrackedControllerRole>,
+    /// Charge from `0.0` to `1.0`, for devices with a battery.
+    pub battery_level: Option<f32>,
+    pub is_charging: bool,
+}
+
+impl DeviceInfo {
+    /// Name and charge, such as `Left controller 80%`.
+    pub fn describe(&self) -> String {
+        let name = match (self.class, self.role) {
+            (TrackedDeviceClass::Hmd, _) => "Headset".to_string(),
+            (_, Some(TrackedControllerRole::LeftHand)) => "Left controller".to_string(),
+            (_, Some(TrackedControllerRole::RightHand)) => "Right controller".to_string(),
+            (TrackedDeviceClass::Controller, None) => format!("Controller {}", self.index),
+            (TrackedDeviceClass::GenericTracker, None) => format!("Tracker {}", self.index),
+            (TrackedDeviceClass::TrackingReference, None) => {
+                format!("Base station {}", self.index)
+            }
+            (TrackedDeviceClass::Other, None) => format!("Device {}", self.index),
+        };
+
+        match self.battery_level {
+            Some(level) => {
+                let charging = if self.is_charging { " (charging)" } else { "" };
+                format!("{name} {:.0}%{charging}", level * 100.0)
+            }
+            None => name,
+        }
+    }
+}
+
+/// Whether `device` ran low on battery since `previous` was read. Devices that were not there
+/// before, such as on startup, don't count.
+pub fn has_run_low(previous: &[DeviceInfo], device: &DeviceInfo) -> bool {
+    let is_low = |device: &DeviceInfo| {
+        !device.is_charging
+            && device
+                .battery_level
+                .is_some_and(|level| level < LOW_BATTERY_LEVEL)
+    };
+
+    is_low(device)
+        && previous
+            .iter()
+            .find(|other| other.index == device.index)
+            .is_some_and(|other| !is_low(other))
+}
+
+/// Reads every connected tracked device.
+pub fn enumerate(system: &Handle<SystemInterface>) -> Vec<DeviceInfo> {
+    (0..MAX_TRACKED_DEVICES)
+        .filter_map(|index| {
+            let class = system.device_class(index)?;
+
+            Some(DeviceInfo {
+                index,
+                class,
+                role: system.controller_role(index),
+                battery_level: system.battery_level(index),
+                is_charging: system.is_charging(index),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(role: Option<TrackedControllerRole>) -> DeviceInfo {
+        DeviceInfo {
+            index: 3,
+            class: TrackedDeviceClass::Controller,
+            role,
+            battery_level: Some(0.8),
+            is_charging: false,
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            controller(Some(TrackedControllerRole::LeftHand)).describe(),
+            "Left controller 80%"
+        );
+        assert_eq!(controller(None).describe(), "Controller 3 80%");
+
+        let base_station = DeviceInfo {
+            class: TrackedDeviceClass::TrackingReference,
+            battery_level: None,
+            ..controller(None)
+        };
+        assert_eq!(base_station.describe(), "Base station 3");
+    }
+
+    #[test]
+    fn test_has_run_low() {
+        let full = controller(Some(TrackedControllerRole::LeftHand));
+        let low = DeviceInfo {
+            battery_level: Some(0.1),
+            ..full.clone()
+        };
+
+        assert!(has_run_low(&[full], &low));
+        assert!(!has_run_low(std::slice::from_ref(&low), &low));
+        assert!(!has_run_low(&[], &low));
+    }
+
+    #[test]
+    fn test_describe_charging() {
+        let charging = DeviceInfo {
+            is_charging: true,
+            ..controller(Some(TrackedControllerRole::RightHand))
+        };
+
+        assert_eq!(charging.describe(), "Right controller 80% (charging)");
+    }
+}
@@ -0,0 +1,121 @@
+//! Rows of the menu which changed since the previous frame, so that only those are uploaded. The
+//! pie menu rarely changes all of it, e.g. hovering an item only redraws that item.
+
+use tiny_skia::Pixmap;
+
+/// Rows `start..end` of a pixmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyRows {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl DirtyRows {
+    pub fn full(pixmap: &Pixmap) -> Self {
+        Self {
+            start: 0,
+            end: pixmap.height(),
+        }
+    }
+
+    pub fn is_full(self, pixmap: &Pixmap) -> bool {
+        self == Self::full(pixmap)
+    }
+
+    pub fn height(self) -> u32 {
+        self.end - self.start
+    }
+
+    /// Byte range of the rows in the data of `pixmap`.
+    pub fn byte_range(self, pixmap: &Pixmap) -> std::ops::Range<usize> {
+        let row_bytes = pixmap.width() as usize * 4;
+
+        self.start as usize * row_bytes..self.end as usize * row_bytes
+    }
+}
+
+/// Remembers the previous frame to compare the next one with.
+#[derive(Default)]
+pub struct FrameDiff {
+    previous: Option<Pixmap>,
+}
+
+impl FrameDiff {
+    /// Rows of `current` which differ from the previous frame, all of them for the first frame.
+    /// `None` when nothing changed.
+    pub fn update(&mut self, current: &Pixmap) -> Option<DirtyRows> {
+        let Some(previous) = self
+            .previous
+            .as_mut()
+            .filter(|previous| previous.width() == current.width())
+            .filter(|previous| previous.height() == current.height())
+        else {
+            self.previous = Some(current.clone());
+            return Some(DirtyRows::full(current));
+        };
+
+        let row_bytes = current.width() as usize * 4;
+        let is_dirty = |row: &usize| {
+            let range = row * row_bytes..(row + 1) * row_bytes;
+            previous.data()[range.clone()] != current.data()[range]
+        };
+
+        let rows = 0..current.height() as usize;
+        let start = rows.clone().find(is_dirty)?;
+        let end = rows.rev().find(is_dirty)? + 1;
+
+        previous.data_mut().copy_from_slice(current.data());
+
+        Some(DirtyRows {
+            start: u32::try_from(start).unwrap(),
+            end: u32::try_from(end).unwrap(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn fill_rows(pixmap: &mut Pixmap, rows: std::ops::Range<u32>) {
+        let rect = tiny_skia::Rect::from_xywh(
+            0.0,
+            rows.start as f32,
+            pixmap.width() as f32,
+            (rows.end - rows.start) as f32,
+        )
+        .unwrap();
+
+        pixmap.fill_rect(
+            rect,
+            &tiny_skia::Paint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_first_frame_is_fully_dirty() {
+        let pixmap = Pixmap::new(8, 8).unwrap();
+
+        assert_eq!(
+            FrameDiff::default().update(&pixmap),
+            Some(DirtyRows { start: 0, end: 8 })
+        );
+    }
+
+    #[test]
+    fn test_changed_rows() {
+        let mut diff = FrameDiff::default();
+        let mut pixmap = Pixmap::new(8, 8).unwrap();
+        diff.update(&pixmap);
+
+        assert_eq!(diff.update(&pixmap), None);
+
+        fill_rows(&mut pixmap, 2..5);
+
+        assert_eq!(diff.update(&pixmap), Some(DirtyRows { start: 2, end: 5 }));
+        assert_eq!(diff.update(&pixmap), None);
+    }
+}
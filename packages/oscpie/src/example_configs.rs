@@ -0,0 +1,173 @@
+//! Drives the example configs in `examples/` end to end, so that the features they combine keep
+//! working together.
+
+use std::{collections::BTreeMap, path::Path, sync::mpsc::channel};
+
+use oscpie_ui::sprite::SpriteSheet;
+use tiny_skia::Pixmap;
+
+use crate::{
+    components::pie_menu::{PieMenuComponent, Props},
+    config, frame_capture,
+    prelude::*,
+    resource::SPRITE_SHEET,
+    side_effects,
+    theme::Theme,
+    validator,
+};
+
+const EXAMPLES: [&str; 4] = [
+    "examples/media_control.json",
+    "examples/obs.json",
+    "examples/vrchat.json",
+    "examples/desktop_control.json",
+];
+
+/// Hashes of the rendered root menus. Set `OSCPIE_UPDATE_SNAPSHOTS=1` to record them again
+/// after changing how menus look.
+const SNAPSHOTS_PATH: &str = "examples/snapshots.json";
+
+/// Frames simulated before capturing, long enough for every transition to reach its target.
+const SETTLE_FRAMES: u32 = 60;
+const FRAME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+
+fn load(path: &str) -> config::Config {
+    config::load(path).unwrap_or_else(|e| panic!("{path}: {e}"))
+}
+
+fn pie_menu(menu: &Menu, action_context: &ActionContext) -> PieMenuComponent {
+    PieMenuComponent::new(
+        256.0,
+        256.0,
+        256.0 * 0.9,
+        menu,
+        action_context,
+        Theme::default(),
+    )
+}
+
+/// Hovers, presses and releases the item at `index`.
+fn click(pie_menu: &mut PieMenuComponent, index: usize) {
+    let angle = pie_menu.item_angle(index).unwrap();
+
+    for click in [0.0, 1.0, 0.0] {
+        pie_menu.update(&Props::new(PieMenuInput::new(angle, 1.0, click)));
+    }
+}
+
+fn render_root(path: &str, config: &config::Config) -> String {
+    SPRITE_SHEET
+        .get_or_init(|| SpriteSheet::load(resolve_path(path, &config.sprite_sheet)).unwrap());
+
+    freeze_clock();
+
+    let menu = Menu::from_config(&config.menus[&config.root]);
+    let mut pie_menu = pie_menu(&menu, &ActionContext::new(channel().0));
+
+    for _ in 0..SETTLE_FRAMES {
+        advance_clock(FRAME_INTERVAL);
+        pie_menu.update(&Props::new(PieMenuInput::new(0.0, 0.0, 0.0)));
+    }
+
+    let mut pixmap = Pixmap::new(512, 512).unwrap();
+    pie_menu.render(&mut pixmap);
+
+    format!("{:016x}", frame_capture::fnv1a(pixmap.data()))
+}
+
+#[test]
+fn test_examples_validate() {
+    for path in EXAMPLES {
+        assert!(validator::validate_config(path, false).unwrap(), "{path}");
+
+        let config = load(path);
+
+        for (id, menu) in &config.menus {
+            for item in &menu.items {
+                if let config::types::MenuItemAction::SubMenu { to } = &item.action {
+                    assert!(
+                        config.menus.contains_key(to),
+                        "{path}: '{}' refers to unknown menu '{}'",
+                        id.inner(),
+                        to.inner()
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_examples_click_every_item() {
+    for path in EXAMPLES {
+        let config = load(path);
+
+        for (id, menu_config) in &config.menus {
+            let menu = Menu::from_config(menu_config);
+
+            for (index, item) in menu.items.iter().enumerate() {
+                let (sender, receiver) = channel();
+                let mut pie_menu = pie_menu(&menu, &ActionContext::new(sender));
+
+                let ((), effects) = side_effects::capture(|| click(&mut pie_menu, index));
+                let events: Vec<_> = receiver.try_iter().collect();
+                let at = format!("{path}: {}[{index}]", id.inner());
+
+                assert!(
+                    !events.iter().any(|event| matches!(
+                        event,
+                        AppEvent::Toast {
+                            severity: Severity::Error,
+                            ..
+                        }
+                    )),
+                    "{at} failed: {events:?}"
+                );
+
+                let MenuItemAction::OneShotButton(behaviour) = item.action() else {
+                    panic!("{at} is not a button");
+                };
+
+                if behaviour.borrow().is_navigation() {
+                    assert!(effects.is_empty(), "{at} has side effects: {effects:?}");
+                } else {
+                    assert_eq!(effects.len(), 1, "{at}: {effects:?}");
+                    assert!(
+                        events
+                            .iter()
+                            .any(|event| matches!(event, AppEvent::ActionFired { .. })),
+                        "{at} did not fire: {events:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_examples_snapshot_root_menu() {
+    let hashes: BTreeMap<String, String> = EXAMPLES
+        .iter()
+        .map(|path| (path.to_string(), render_root(path, &load(path))))
+        .collect();
+
+    if std::env::var_os("OSCPIE_UPDATE_SNAPSHOTS").is_some_and(|update| update == "1") {
+        std::fs::write(
+            Path::new(SNAPSHOTS_PATH),
+            serde_json::to_string_pretty(&hashes).unwrap() + "\n",
+        )
+        .unwrap();
+        return;
+    }
+
+    let snapshots: BTreeMap<String, String> =
+        serde_json::from_str(&std::fs::read_to_string(SNAPSHOTS_PATH).unwrap()).unwrap();
+
+    for (path, hash) in &hashes {
+        assert_eq!(
+            snapshots.get(path),
+            Some(hash),
+            "{path}: root menu looks different, run with OSCPIE_UPDATE_SNAPSHOTS=1 if that is intended"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::BTreeMap,
+    f32::consts::PI,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tiny_skia::Pixmap;
+
+use crate::{
+    components::pie_menu::{PieMenuComponent, Props},
+    prelude::*,
+    theme::Theme,
+};
+
+/// The session is repeated this many times and the fastest run is kept, to reduce noise.
+const RUNS: usize = 10;
+
+/// Animation time between scripted frames, as at the headset's 90 Hz. The clock is frozen
+/// otherwise, so that the hashes don't depend on how fast the session runs.
+const FRAME_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 90);
+
+/// Recorded result of a scripted session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Capture {
+    /// Hash of every frame, by step name.
+    pub frames: BTreeMap<String, String>,
+    /// Total time spent in each phase over the session, in nanoseconds.
+    pub timings: BTreeMap<String, u128>,
+}
+
+/// Fixed sequence of inputs, named after what they exercise.
+fn script() -> Vec<(&'static str, PieMenuInput)> {
+    vec![
+        ("neutral", PieMenuInput::new(0.0, 0.0, 0.0)),
+        ("hover_east", PieMenuInput::new(0.1, 1.0, 0.0)),
+        ("hover_south", PieMenuInput::new(PI * 0.6, 1.0, 0.0)),
+        ("press_south", PieMenuInput::new(PI * 0.6, 1.0, 1.0)),
+        ("release_south", PieMenuInput::new(PI * 0.6, 1.0, 0.0)),
+        ("hover_west", PieMenuInput::new(PI * 1.1, 0.8, 0.0)),
+        ("hover_north", PieMenuInput::new(PI * 1.6, 0.6, 0.0)),
+        ("hub", PieMenuInput::new(PI * 1.6, 0.2, 0.0)),
+    ]
+}
+
+fn menu() -> Menu {
+    Menu::new(
+        (0..6)
+            .map(|_| MenuItem::new(MenuItemAction::Noop, None))
+            .collect(),
+    )
+}
+
+/// Runs the scripted session, hashing every frame and timing the update and render phases.
+pub fn capture() -> Capture {
+    let mut capture = Capture::default();
+
+    for run in 0..RUNS {
+        freeze_clock();
+
+        let mut pie_menu = PieMenuComponent::new(
+            256.0,
+            256.0,
+            256.0 * 0.9,
+            &menu(),
+            &ActionContext::new(std::sync::mpsc::channel().0),
+            Theme::default(),
+        );
+
+        let mut pixmap = Pixmap::new(512, 512).unwrap();
+        let mut update_ns = 0;
+        let mut render_ns = 0;
+
+        for (name, input) in script() {
+            advance_clock(FRAME_INTERVAL);
+
+            let start = Instant::now();
+            pie_menu.update(&Props::new(input));
+            update_ns += start.elapsed().as_nanos();
+
+            pixmap.fill(tiny_skia::Color::TRANSPARENT);
+
+            let start = Instant::now();
+            pie_menu.render(&mut pixmap);
+            render_ns += start.elapsed().as_nanos();
+
+            if run == 0 {
+                capture
+                    .frames
+                    .insert(name.to_string(), format!("{:016x}", fnv1a(pixmap.data())));
+            }
+        }
+
+        for (phase, ns) in [("update", update_ns), ("render", render_ns)] {
+            let best = capture.timings.entry(phase.to_string()).or_insert(ns);
+            *best = (*best).min(ns);
+        }
+    }
+
+    unfreeze_clock();
+
+    capture
+}
+
+/// Compares a capture against a baseline. Returns the problems found, which is empty when the
+/// frames are identical and no phase got slower by more than `threshold_percent`.
+#[allow(clippy::cast_precision_loss)]
+pub fn compare(baseline: &Capture, current: &Capture, threshold_percent: f64) -> Vec<String> {
+    let mut problems = vec![];
+
+    for (name, hash) in &baseline.frames {
+        match current.frames.get(name) {
+            Some(current_hash) if current_hash == hash => {}
+            Some(current_hash) => {
+                problems.push(format!("Frame '{name}' changed: {hash} -> {current_hash}"));
+            }
+            None => problems.push(format!("Frame '{name}' is missing")),
+        }
+    }
+
+    for (phase, baseline_ns) in &baseline.timings {
+        let Some(current_ns) = current.timings.get(phase) else {
+            problems.push(format!("Phase '{phase}' is missing"));
+            continue;
+        };
+
+        let change = (*current_ns as f64 / (*baseline_ns).max(1) as f64 - 1.0) * 100.0;
+
+        if change > threshold_percent {
+            problems.push(format!(
+                "Phase '{phase}' is {change:.1}% slower: {baseline_ns}ns -> {current_ns}ns"
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Captures a session and either records it as the baseline or checks it against the baseline.
+/// Returns whether no regression was found.
+pub fn run(baseline_path: &Path, record: bool, threshold_percent: f64) -> Result<bool> {
+    let current = capture();
+
+    if record {
+        std::fs::write(baseline_path, serde_json::to_string_pretty(&current)?)?;
+        println!("Recorded {}", baseline_path.display());
+        return Ok(true);
+    }
+
+    let baseline: Capture = serde_json::from_reader(std::fs::File::open(baseline_path)?)?;
+
+    let problems = compare(&baseline, &current, threshold_percent);
+
+    for problem in &problems {
+        println!("{problem}");
+    }
+
+    for (phase, ns) in &current.timings {
+        println!("{phase}: {ns}ns");
+    }
+
+    Ok(problems.is_empty())
+}
+
+/// FNV-1a, used because its output is stable across builds unlike the std hasher.
+pub fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_is_deterministic() {
+        assert_eq!(capture().frames, capture().frames);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = Capture {
+            frames: BTreeMap::from([("a".to_string(), "1".to_string())]),
+            timings: BTreeMap::from([("render".to_string(), 100)]),
+        };
+
+        assert!(compare(&baseline, &baseline, 10.0).is_empty());
+
+        let slower = Capture {
+            timings: BTreeMap::from([("render".to_string(), 120)]),
+            ..baseline.clone()
+        };
+
+        assert_eq!(compare(&baseline, &slower, 10.0).len(), 1);
+        assert!(compare(&baseline, &slower, 25.0).is_empty());
+
+        let changed = Capture {
+            frames: BTreeMap::from([("a".to_string(), "2".to_string())]),
+            ..baseline.clone()
+        };
+
+        assert_eq!(compare(&baseline, &changed, 10.0).len(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+//! Head gaze selection, where the hovered item is the one the headset looks at. Meant for users
+//! who can press a button but can't easily use a thumbstick.
+
+use glam::{Affine3A, Vec2, Vec3A};
+
+use crate::hit_test;
+
+/// Radius of the menu relative to the overlay width, as the components draw it.
+const MENU_RADIUS: f32 = 0.45;
+
+/// Point of the menu the headset looks at, normalized by the menu radius with y pointing down as
+/// the menu is drawn. `None` while the headset looks away from the overlay's plane.
+pub fn gaze_to_point(hmd: Affine3A, overlay: Affine3A, overlay_width_m: f32) -> Option<Vec2> {
+    let to_overlay = overlay.inverse();
+    let origin = to_overlay.transform_point3a(hmd.translation);
+    let forward = to_overlay.transform_vector3a(hmd.transform_vector3a(Vec3A::NEG_Z));
+
+    if forward.z.abs() < 1e-6 {
+        return None;
+    }
+
+    let distance = -origin.z / forward.z;
+
+    if distance <= 0.0 {
+        return None;
+    }
+
+    let hit = origin + forward * distance;
+    let radius = overlay_width_m * MENU_RADIUS;
+
+    Some(Vec2::new(hit.x / radius, -hit.y / radius))
+}
+
+/// Angle and magnitude to select with, like a stick deflection. Looking off the menu selects
+/// nothing.
+pub fn gaze_to_polar(hmd: Affine3A, overlay: Affine3A, overlay_width_m: f32) -> (f32, f32) {
+    gaze_to_point(hmd, overlay, overlay_width_m)
+        .map(hit_test::point_to_polar)
+        .filter(|(_, magnitude)| !hit_test::is_outside(*magnitude))
+        .unwrap_or((0.0, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use glam::Vec3;
+
+    use super::*;
+
+    fn hmd_at(x: f32, y: f32) -> Affine3A {
+        Affine3A::from_translation(Vec3::new(x, y, 1.0))
+    }
+
+    #[test]
+    fn test_gaze_at_center() {
+        let point = gaze_to_point(hmd_at(0.0, 0.0), Affine3A::IDENTITY, 1.0).unwrap();
+
+        assert!(point.abs_diff_eq(Vec2::ZERO, 1e-6));
+    }
+
+    #[test]
+    fn test_gaze_is_normalized_by_the_menu_radius() {
+        let point = gaze_to_point(hmd_at(0.225, 0.225), Affine3A::IDENTITY, 1.0).unwrap();
+
+        // Up in the world is up on the menu, where y points down
+        assert!(point.abs_diff_eq(Vec2::new(0.5, -0.5), 1e-5));
+    }
+
+    #[test]
+    fn test_gaze_scales_with_the_overlay_width() {
+        let point = gaze_to_point(hmd_at(0.45, 0.0), Affine3A::IDENTITY, 2.0).unwrap();
+
+        assert!(point.abs_diff_eq(Vec2::new(0.5, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn test_gaze_away_from_the_overlay() {
+        let hmd = hmd_at(0.0, 0.0) * Affine3A::from_rotation_y(PI);
+
+        assert_eq!(gaze_to_point(hmd, Affine3A::IDENTITY, 1.0), None);
+        assert!(gaze_to_polar(hmd, Affine3A::IDENTITY, 1.0).1 < 1e-6);
+    }
+
+    #[test]
+    fn test_gaze_off_the_menu_selects_nothing() {
+        assert!(gaze_to_polar(hmd_at(1.0, 0.0), Affine3A::IDENTITY, 1.0).1 < 1e-6);
+
+        let (angle, magnitude) = gaze_to_polar(hmd_at(-0.3, 0.0), Affine3A::IDENTITY, 1.0);
+        assert!((angle - PI).abs() < 1e-5);
+        assert!((magnitude - 0.3 / 0.45).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{config, prelude::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+}
+
+/// What a swipe does to the open menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeAction {
+    Back,
+    Close,
+    ReturnToRoot,
+}
+
+impl From<config::types::SwipeAction> for SwipeAction {
+    fn from(action: config::types::SwipeAction) -> Self {
+        match action {
+            config::types::SwipeAction::Back => SwipeAction::Back,
+            config::types::SwipeAction::Close => SwipeAction::Close,
+            config::types::SwipeAction::ReturnToRoot => SwipeAction::ReturnToRoot,
+        }
+    }
+}
+
+/// Controller pose of the current frame.
+#[derive(Debug, Clone, Copy)]
+pub struct PoseSample {
+    pub transform: Affine3A,
+    /// Velocity in tracking space, in meters per second.
+    pub velocity: Vec3A,
+}
+
+/// Detects quick sideways flicks of the controller from its velocity.
+///
+/// Once a swipe fired, the next one needs the controller to slow down again and the cooldown to
+/// pass, so that the swing back does not count as a swipe in the other direction.
+pub struct SwipeDetector {
+    min_speed: f32,
+    cooldown_secs: f32,
+    left: Option<SwipeAction>,
+    right: Option<SwipeAction>,
+    cooldown_left_secs: f32,
+    armed: bool,
+}
+
+impl SwipeDetector {
+    pub fn new(config: &config::types::SwipeConfig) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let cooldown_secs = config.cooldown_ms as f32 / 1000.0;
+
+        Self {
+            min_speed: config.min_speed,
+            cooldown_secs,
+            left: config.left.map(SwipeAction::from),
+            right: config.right.map(SwipeAction::from),
+            cooldown_left_secs: 0.0,
+            armed: true,
+        }
+    }
+
+    pub fn update(&mut self, pose: &PoseSample, dt: f32) -> Option<SwipeAction> {
+        let direction = self.detect(pose, dt)?;
+
+        match direction {
+            SwipeDirection::Left => self.left,
+            SwipeDirection::Right => self.right,
+        }
+    }
+
+    fn detect(&mut self, pose: &PoseSample, dt: f32) -> Option<SwipeDirection> {
+        self.cooldown_left_secs = (self.cooldown_left_secs - dt).max(0.0);
+
+        let right_axis = pose.transform.matrix3.x_axis.normalize_or_zero();
+        let lateral_speed = pose.velocity.dot(right_axis);
+        let speed = pose.velocity.length();
+
+        if speed < self.min_speed * 0.5 {
+            self.armed = true;
+        }
+
+        // Mostly sideways, so that raising or pointing the controller is not taken for a swipe
+        let is_lateral = lateral_speed.abs() >= speed * 0.7;
+
+        if !self.armed
+            || self.cooldown_left_secs > 0.0
+            || !is_lateral
+            || lateral_speed.abs() < self.min_speed
+        {
+            return None;
+        }
+
+        self.armed = false;
+        self.cooldown_left_secs = self.cooldown_secs;
+
+        Some(if lateral_speed > 0.0 {
+            SwipeDirection::Right
+        } else {
+            SwipeDirection::Left
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> SwipeDetector {
+        SwipeDetector::new(&config::types::SwipeConfig {
+            min_speed: 1.0,
+            cooldown_ms: 300,
+            left: Some(config::types::SwipeAction::Back),
+            right: Some(config::types::SwipeAction::Close),
+        })
+    }
+
+    fn sample(velocity: Vec3A) -> PoseSample {
+        PoseSample {
+            transform: Affine3A::IDENTITY,
+            velocity,
+        }
+    }
+
+    #[test]
+    fn test_lateral_swipe() {
+        let mut detector = detector();
+
+        assert_eq!(detector.update(&sample(Vec3A::ZERO), 0.01), None);
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(-2.0, 0.0, 0.0)), 0.01),
+            Some(SwipeAction::Back)
+        );
+    }
+
+    #[test]
+    fn test_vertical_motion_is_ignored() {
+        let mut detector = detector();
+
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(1.1, 3.0, 0.0)), 0.01),
+            None
+        );
+    }
+
+    #[test]
+    fn test_swing_back_is_ignored() {
+        let mut detector = detector();
+
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(2.0, 0.0, 0.0)), 0.01),
+            Some(SwipeAction::Close)
+        );
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(-2.0, 0.0, 0.0)), 0.1),
+            None
+        );
+        assert_eq!(detector.update(&sample(Vec3A::ZERO), 0.1), None);
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(-2.0, 0.0, 0.0)), 0.05),
+            None
+        );
+        assert_eq!(
+            detector.update(&sample(Vec3A::new(-2.0, 0.0, 0.0)), 0.2),
+            Some(SwipeAction::Back)
+        );
+    }
+}
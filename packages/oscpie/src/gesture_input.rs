@@ -0,0 +1,72 @@
+//! Hand poses read from finger tracking, usable like buttons in the open gesture, e.g. making a
+//! fist to open the menu.
+
+use crate::openvr::input::SkeletonInput;
+
+/// Curl past which a finger counts as curled.
+const CURLED: f32 = 0.75;
+
+/// Curl under which a finger counts as extended.
+const EXTENDED: f32 = 0.3;
+
+const THUMB: usize = 0;
+const INDEX: usize = 1;
+
+/// Hand poses of one hand, as of the last update.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GestureInput {
+    /// All fingers curled.
+    pub is_fist: bool,
+    /// Index finger and thumb extended, the other fingers curled.
+    pub is_finger_gun: bool,
+}
+
+impl GestureInput {
+    /// Classifies the curl of the thumb, index, middle, ring and pinky fingers.
+    pub fn from_finger_curl(curl: [f32; 5]) -> Self {
+        let is_curled = |finger: usize| curl[finger] > CURLED;
+        let are_others_curled = (INDEX + 1..curl.len()).all(is_curled);
+
+        Self {
+            is_fist: is_curled(THUMB) && is_curled(INDEX) && are_others_curled,
+            is_finger_gun: curl[THUMB] < EXTENDED && curl[INDEX] < EXTENDED && are_others_curled,
+        }
+    }
+
+    /// Poses of a hand tracked by `skeleton`, none while it has no finger tracking.
+    pub fn from_skeleton(skeleton: &SkeletonInput) -> Self {
+        if skeleton.active {
+            Self::from_finger_curl(skeleton.finger_curl)
+        } else {
+            Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fist() {
+        let gesture = GestureInput::from_finger_curl([0.9, 0.95, 1.0, 1.0, 0.9]);
+
+        assert!(gesture.is_fist);
+        assert!(!gesture.is_finger_gun);
+    }
+
+    #[test]
+    fn test_finger_gun() {
+        let gesture = GestureInput::from_finger_curl([0.1, 0.05, 0.9, 0.95, 0.9]);
+
+        assert!(gesture.is_finger_gun);
+        assert!(!gesture.is_fist);
+    }
+
+    #[test]
+    fn test_open_hand() {
+        let gesture = GestureInput::from_finger_curl([0.1, 0.1, 0.2, 0.1, 0.1]);
+
+        assert_eq!(gesture, GestureInput::default());
+    }
+}
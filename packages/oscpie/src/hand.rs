@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use crate::{config, input_expression::InputExpression};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Decides which hand opens and drives the menu. With both hands allowed, the hand which did the
+/// open gesture last takes over.
+#[derive(Debug)]
+pub struct HandSelector {
+    /// Open gesture of each allowed hand, with whether it was down on the last update.
+    gestures: Vec<(Hand, InputExpression, bool)>,
+    active: Hand,
+}
+
+impl HandSelector {
+    /// `open_gesture` is written for the left hand and mirrored for the right one.
+    pub fn new(hand: config::types::Hand, open_gesture: &InputExpression) -> Self {
+        let left = (Hand::Left, open_gesture.clone(), false);
+        let right = (Hand::Right, open_gesture.mirrored(), false);
+
+        let gestures = match hand {
+            config::types::Hand::Left => vec![left],
+            config::types::Hand::Right => vec![right],
+            config::types::Hand::Both => vec![left, right],
+        };
+
+        Self {
+            active: gestures[0].0,
+            gestures,
+        }
+    }
+
+    /// Hand whose stick, click and pose drive the menu.
+    pub fn active(&self) -> Hand {
+        self.active
+    }
+
+    /// Evaluates the open gestures, and returns whether the active hand's gesture is down.
+    pub fn update(&mut self, state: &HashMap<&str, bool>) -> bool {
+        for (hand, gesture, was_down) in &mut self.gestures {
+            let is_down = gesture.evaluate(state);
+
+            if is_down && !*was_down && *hand != self.active {
+                log::info!("Switching to the {hand:?} hand");
+                self.active = *hand;
+            }
+
+            *was_down = is_down;
+        }
+
+        self.gestures
+            .iter()
+            .any(|(hand, _, is_down)| *hand == self.active && *is_down)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_left() -> InputExpression {
+        InputExpression::Action("OpenLeft".to_string())
+    }
+
+    #[test]
+    fn test_right_hand_mirrors_the_gesture() {
+        let mut selector = HandSelector::new(config::types::Hand::Right, &open_left());
+
+        assert_eq!(selector.active(), Hand::Right);
+        assert!(!selector.update(&HashMap::from([("OpenLeft", true)])));
+        assert!(selector.update(&HashMap::from([("OpenRight", true)])));
+    }
+
+    #[test]
+    fn test_both_hands_switch_on_open() {
+        let mut selector = HandSelector::new(config::types::Hand::Both, &open_left());
+        assert_eq!(selector.active(), Hand::Left);
+
+        assert!(selector.update(&HashMap::from([("OpenRight", true)])));
+        assert_eq!(selector.active(), Hand::Right);
+
+        // Still held on the right, the left one takes over once it is pressed
+        assert!(selector.update(&HashMap::from([("OpenRight", true), ("OpenLeft", true)])));
+        assert_eq!(selector.active(), Hand::Left);
+
+        assert!(!selector.update(&HashMap::from([("OpenRight", true)])));
+        assert_eq!(selector.active(), Hand::Left);
+    }
+}
@@ -0,0 +1,293 @@
+//! Geometry shared by every way of pointing at a menu.
+//!
+//! Positions are given relative to the menu center and normalized by its radius, with y pointing
+//! down as the menu is drawn. Angles are in radians, clockwise from east.
+
+use std::f32::consts::PI;
+
+use glam::Vec2;
+
+use crate::config;
+
+/// Inside this distance from the center nothing is selected.
+pub const HUB_RADIUS: f32 = 0.5;
+/// Beyond this distance the point is off the menu.
+pub const OUTER_RADIUS: f32 = 1.0;
+/// Inside this distance from the center nothing is selected in a grid, which is smaller than the
+/// hub so that the middle cells stay reachable.
+pub const GRID_DEADZONE: f32 = 0.15;
+
+/// Thresholds which keep an item hovered a little past where it starts being hovered, so that the
+/// selection doesn't flicker while the stick rests near an edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hysteresis {
+    /// Magnitude past which an item becomes hovered.
+    pub enter_magnitude: f32,
+    /// Magnitude under which the hovered item stops being hovered.
+    pub exit_magnitude: f32,
+    /// Angle the hovered item extends past its edges, and other items shrink by, in radians.
+    pub snap_margin: f32,
+}
+
+impl Default for Hysteresis {
+    fn default() -> Self {
+        Self::from_config(&config::types::SelectionConfig::default())
+    }
+}
+
+impl Hysteresis {
+    pub fn from_config(selection: &config::types::SelectionConfig) -> Self {
+        Self {
+            enter_magnitude: selection.enter_magnitude,
+            exit_magnitude: selection.exit_magnitude.min(selection.enter_magnitude),
+            snap_margin: selection.snap_margin_deg.to_radians().max(0.0),
+        }
+    }
+
+    /// Whether the item over `(start, end)` is hovered, given whether it was on the last update.
+    pub fn is_hovered(
+        &self,
+        angle: f32,
+        magnitude: f32,
+        (start, end): (f32, f32),
+        was_hovered: bool,
+    ) -> bool {
+        let margin = self.snap_margin;
+
+        if was_hovered {
+            magnitude > self.exit_magnitude && angle_in_slice(angle, (start - margin, end + margin))
+        } else {
+            magnitude > self.enter_magnitude
+                && angle_in_slice(angle, (start + margin, end - margin))
+        }
+    }
+
+    /// Whether the stick is far enough from the center to be over an item.
+    pub fn is_over_slices(&self, magnitude: f32, was_hovered: bool) -> bool {
+        if was_hovered {
+            magnitude > self.exit_magnitude
+        } else {
+            magnitude > self.enter_magnitude
+        }
+    }
+}
+
+/// Converts a stick deflection, where y points up, to an angle and magnitude.
+pub fn stick_to_polar(stick: Vec2) -> (f32, f32) {
+    point_to_polar(Vec2::new(stick.x, -stick.y))
+}
+
+/// Converts a normalized point to an angle and its distance from the center.
+pub fn point_to_polar(point: Vec2) -> (f32, f32) {
+    (point.y.atan2(point.x).rem_euclid(PI * 2.0), point.length())
+}
+
+pub fn is_in_hub(magnitude: f32) -> bool {
+    magnitude <= HUB_RADIUS
+}
+
+pub fn is_outside(magnitude: f32) -> bool {
+    magnitude > OUTER_RADIUS
+}
+
+/// Whether `angle` lies in the slice `(start, end)`. Slices may extend past a full turn.
+pub fn angle_in_slice(angle: f32, (start, end): (f32, f32)) -> bool {
+    (angle - start).rem_euclid(PI * 2.0) <= end - start
+}
+
+/// Index of the quick bar item under an angle and magnitude, which depends on the horizontal
+/// position alone. Nothing is selected while the input rests in the hub.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn polar_to_bar_item(angle: f32, magnitude: f32, count: usize) -> Option<usize> {
+    if count == 0 || is_in_hub(magnitude) {
+        return None;
+    }
+
+    let x = angle.cos() * magnitude.min(OUTER_RADIUS);
+    let index = ((x + 1.0) / 2.0 * count as f32) as usize;
+
+    Some(index.min(count - 1))
+}
+
+/// Number of `(columns, rows)` of the most square grid holding `count` items.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn grid_size(count: usize) -> (usize, usize) {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+
+    (columns, count.div_ceil(columns))
+}
+
+/// Index of the grid cell under an angle and magnitude. The outer ring is stretched onto the square
+/// around it so that the corner cells can be reached. Cells are numbered row by row and those past
+/// `count` select nothing.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn polar_to_grid_cell(angle: f32, magnitude: f32, count: usize) -> Option<usize> {
+    if count == 0 || magnitude <= GRID_DEADZONE {
+        return None;
+    }
+
+    let (columns, rows) = grid_size(count);
+    let (x, y) = (angle.cos(), angle.sin());
+    let magnitude = magnitude.min(OUTER_RADIUS) / x.abs().max(y.abs());
+
+    let cell = |position: f32, cells: usize| {
+        (((position + 1.0) / 2.0 * cells as f32) as usize).min(cells - 1)
+    };
+
+    let column = cell(x * magnitude, columns);
+    let row = cell(y * magnitude, rows);
+    let index = row * columns + column;
+
+    (index < count).then_some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_stick_to_polar() {
+        let (angle, magnitude) = stick_to_polar(Vec2::new(0.0, 1.0));
+        assert_close(angle, PI * 1.5);
+        assert_close(magnitude, 1.0);
+
+        let (angle, _) = stick_to_polar(Vec2::new(0.0, -1.0));
+        assert_close(angle, PI * 0.5);
+
+        let (angle, _) = stick_to_polar(Vec2::new(-1.0, 0.0));
+        assert_close(angle, PI);
+    }
+
+    #[test]
+    fn test_point_to_polar_is_in_range() {
+        for i in 0..16 {
+            #[allow(clippy::cast_precision_loss)]
+            let expected = PI * 2.0 * i as f32 / 16.0;
+            let (angle, magnitude) =
+                point_to_polar(Vec2::new(expected.cos(), expected.sin()) * 0.75);
+
+            assert!((0.0..PI * 2.0).contains(&angle));
+            assert!((angle - expected).abs() < 1e-5 || (angle - expected).abs() > PI * 2.0 - 1e-5);
+            assert_close(magnitude, 0.75);
+        }
+    }
+
+    #[test]
+    fn test_hub_and_outer_ring() {
+        assert!(is_in_hub(0.0));
+        assert!(is_in_hub(HUB_RADIUS));
+        assert!(!is_in_hub(HUB_RADIUS + 0.01));
+
+        assert!(!is_outside(OUTER_RADIUS));
+        assert!(is_outside(OUTER_RADIUS + 0.01));
+    }
+
+    #[test]
+    fn test_angle_in_slice() {
+        assert!(angle_in_slice(0.1, (0.0, 1.0)));
+        assert!(angle_in_slice(0.0, (0.0, 1.0)));
+        assert!(angle_in_slice(1.0, (0.0, 1.0)));
+        assert!(!angle_in_slice(1.1, (0.0, 1.0)));
+        assert!(!angle_in_slice(PI * 2.0 - 0.1, (0.0, 1.0)));
+    }
+
+    #[test]
+    fn test_angle_in_slice_wrapping_past_full_turn() {
+        let slice = (PI * 1.75, PI * 2.25);
+
+        assert!(angle_in_slice(PI * 1.9, slice));
+        assert!(angle_in_slice(0.1, slice));
+        assert!(angle_in_slice(PI * 2.1, slice));
+        assert!(!angle_in_slice(PI, slice));
+    }
+
+    #[test]
+    fn test_angle_in_slice_with_negative_start() {
+        let slice = (-PI * 0.25, PI * 0.25);
+
+        assert!(angle_in_slice(PI * 1.9, slice));
+        assert!(angle_in_slice(0.1, slice));
+        assert!(!angle_in_slice(PI * 0.5, slice));
+    }
+
+    #[test]
+    fn test_polar_to_bar_item() {
+        assert_eq!(polar_to_bar_item(0.0, 0.2, 4), None);
+        assert_eq!(polar_to_bar_item(PI, 1.0, 4), Some(0));
+        assert_eq!(polar_to_bar_item(PI * 1.4, 1.0, 4), Some(1));
+        assert_eq!(polar_to_bar_item(PI * 1.6, 1.0, 4), Some(2));
+        assert_eq!(polar_to_bar_item(0.0, 1.0, 4), Some(3));
+        assert_eq!(polar_to_bar_item(0.0, 1.5, 4), Some(3));
+        assert_eq!(polar_to_bar_item(0.0, 1.0, 0), None);
+    }
+
+    #[test]
+    fn test_hysteresis_magnitude() {
+        let hysteresis = Hysteresis::default();
+        let slice = (0.0, PI * 0.5);
+
+        assert!(!hysteresis.is_hovered(PI * 0.25, 0.5, slice, false));
+        assert!(hysteresis.is_hovered(PI * 0.25, 0.6, slice, false));
+        assert!(hysteresis.is_hovered(PI * 0.25, 0.5, slice, true));
+        assert!(!hysteresis.is_hovered(PI * 0.25, 0.4, slice, true));
+    }
+
+    #[test]
+    fn test_hysteresis_snap_margin() {
+        let hysteresis = Hysteresis::default();
+        let slice = (0.0, PI * 0.5);
+        let past_edge = PI * 0.5 + hysteresis.snap_margin * 0.5;
+
+        // The hovered item holds on past its edge, while the neighbour is not entered yet
+        assert!(hysteresis.is_hovered(past_edge, 1.0, slice, true));
+        assert!(!hysteresis.is_hovered(past_edge, 1.0, (PI * 0.5, PI), false));
+
+        assert!(!hysteresis.is_hovered(0.01, 1.0, slice, false));
+        assert!(hysteresis.is_hovered(0.1, 1.0, slice, false));
+    }
+
+    #[test]
+    fn test_grid_size() {
+        assert_eq!(grid_size(1), (1, 1));
+        assert_eq!(grid_size(9), (3, 3));
+        assert_eq!(grid_size(10), (4, 3));
+        assert_eq!(grid_size(17), (5, 4));
+    }
+
+    #[test]
+    fn test_polar_to_grid_cell() {
+        assert_eq!(polar_to_grid_cell(0.0, 0.1, 9), None);
+        assert_eq!(polar_to_grid_cell(0.0, 0.2, 9), Some(4));
+        assert_eq!(polar_to_grid_cell(0.0, 1.0, 9), Some(5));
+        assert_eq!(polar_to_grid_cell(PI * 1.5, 1.0, 9), Some(1));
+        assert_eq!(polar_to_grid_cell(0.0, 1.0, 0), None);
+    }
+
+    #[test]
+    fn test_polar_to_grid_cell_reaches_corners() {
+        assert_eq!(polar_to_grid_cell(PI * 1.25, 1.0, 49), Some(0));
+        assert_eq!(polar_to_grid_cell(PI * 0.25, 1.0, 49), Some(48));
+    }
+
+    #[test]
+    fn test_polar_to_grid_cell_past_the_last_item() {
+        assert_eq!(polar_to_grid_cell(PI * 0.25, 1.0, 10), None);
+        assert_eq!(polar_to_grid_cell(PI * 0.75, 1.0, 10), Some(8));
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{config, prelude::*};
+
+/// Digital actions which can be used in an [`InputExpression`]. `Fist` and `FingerGun` are hand
+/// poses read from finger tracking.
+pub const DIGITAL_ACTIONS: [&str; 10] = [
+    "OpenLeft",
+    "ClickLeft",
+    "GripLeft",
+    "FistLeft",
+    "FingerGunLeft",
+    "OpenRight",
+    "ClickRight",
+    "GripRight",
+    "FistRight",
+    "FingerGunRight",
+];
+
+/// Boolean expression over the state of digital actions, e.g. for chorded gestures like
+/// `All [GripLeft, OpenLeft]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputExpression {
+    Action(String),
+    All(Vec<InputExpression>),
+    Any(Vec<InputExpression>),
+    Not(Box<InputExpression>),
+}
+
+impl InputExpression {
+    pub fn from_config(expression: &config::types::InputExpression) -> Result<Self> {
+        Ok(match expression {
+            config::types::InputExpression::Action { name } => {
+                if !DIGITAL_ACTIONS.contains(&name.as_str()) {
+                    return Err(anyhow!(
+                        "Unknown action '{name}', expected one of {DIGITAL_ACTIONS:?}"
+                    ));
+                }
+
+                InputExpression::Action(name.clone())
+            }
+            config::types::InputExpression::All { of } => InputExpression::All(
+                of.iter()
+                    .map(InputExpression::from_config)
+                    .collect::<Result<_>>()?,
+            ),
+            config::types::InputExpression::Any { of } => InputExpression::Any(
+                of.iter()
+                    .map(InputExpression::from_config)
+                    .collect::<Result<_>>()?,
+            ),
+            config::types::InputExpression::Not { of } => {
+                InputExpression::Not(Box::new(InputExpression::from_config(of)?))
+            }
+        })
+    }
+
+    /// Evaluates the expression. Actions missing from `state` are treated as released.
+    pub fn evaluate(&self, state: &HashMap<&str, bool>) -> bool {
+        match self {
+            InputExpression::Action(name) => state.get(name.as_str()).copied().unwrap_or(false),
+            InputExpression::All(expressions) => expressions
+                .iter()
+                .all(|expression| expression.evaluate(state)),
+            InputExpression::Any(expressions) => expressions
+                .iter()
+                .any(|expression| expression.evaluate(state)),
+            InputExpression::Not(expression) => !expression.evaluate(state),
+        }
+    }
+
+    /// Same expression over the actions of the other hand, e.g. `OpenRight` for `OpenLeft`.
+    pub fn mirrored(&self) -> Self {
+        match self {
+            InputExpression::Action(name) => {
+                InputExpression::Action(if let Some(action) = name.strip_suffix("Left") {
+                    format!("{action}Right")
+                } else if let Some(action) = name.strip_suffix("Right") {
+                    format!("{action}Left")
+                } else {
+                    name.clone()
+                })
+            }
+            InputExpression::All(expressions) => {
+                InputExpression::All(expressions.iter().map(Self::mirrored).collect())
+            }
+            InputExpression::Any(expressions) => {
+                InputExpression::Any(expressions.iter().map(Self::mirrored).collect())
+            }
+            InputExpression::Not(expression) => {
+                InputExpression::Not(Box::new(expression.mirrored()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord() -> InputExpression {
+        InputExpression::from_config(
+            &serde_json::from_str(
+                r#"{
+                    "type": "All",
+                    "of": [
+                        { "type": "Action", "name": "GripLeft" },
+                        { "type": "Action", "name": "OpenLeft" }
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_chord_requires_all_actions() {
+        let expression = chord();
+
+        assert!(!expression.evaluate(&HashMap::from([("GripLeft", true)])));
+        assert!(!expression.evaluate(&HashMap::from([("GripLeft", true), ("OpenLeft", false)])));
+        assert!(expression.evaluate(&HashMap::from([("GripLeft", true), ("OpenLeft", true)])));
+    }
+
+    #[test]
+    fn test_not() {
+        let expression =
+            InputExpression::Not(Box::new(InputExpression::Action("ClickLeft".to_string())));
+
+        assert!(expression.evaluate(&HashMap::new()));
+        assert!(!expression.evaluate(&HashMap::from([("ClickLeft", true)])));
+    }
+
+    #[test]
+    fn test_unknown_action_is_rejected() {
+        let result = InputExpression::from_config(&config::types::InputExpression::Action {
+            name: "Jump".to_string(),
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let mirrored = chord().mirrored();
+
+        assert!(!mirrored.evaluate(&HashMap::from([("GripLeft", true), ("OpenLeft", true)])));
+        assert!(mirrored.evaluate(&HashMap::from([("GripRight", true), ("OpenRight", true)])));
+        assert_eq!(mirrored.mirrored(), chord());
+    }
+}
@@ -0,0 +1,116 @@
+//! Laser pointer selection, where SteamVR's laser points at the menu like at a dashboard panel and
+//! its trigger clicks.
+
+use glam::Vec2;
+
+use crate::{hit_test, openvr::OverlayEvent};
+
+/// Radius of the menu relative to half the overlay size, as the components draw it.
+const MENU_RADIUS: f32 = 0.9;
+
+/// Follows the mouse events which the laser pointer sends to the overlay.
+#[derive(Debug)]
+pub struct LaserPointer {
+    width: f32,
+    height: f32,
+    /// Normalized by the menu radius with y pointing down, while the laser is on the overlay.
+    point: Option<Vec2>,
+    is_down: bool,
+}
+
+impl LaserPointer {
+    /// `width` and `height` are the mouse scale of the overlay in pixels.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            point: None,
+            is_down: false,
+        }
+    }
+
+    pub fn on_event(&mut self, event: OverlayEvent) {
+        match event {
+            OverlayEvent::MouseMove { x, y } => {
+                let half = Vec2::new(self.width, self.height) * 0.5;
+
+                // Mouse positions start at the bottom left, while the menu is drawn from the top
+                let pixel = Vec2::new(x, self.height - y);
+
+                self.point = Some((pixel - half) / (half * MENU_RADIUS));
+            }
+            OverlayEvent::MouseButtonDown => self.is_down = true,
+            OverlayEvent::MouseButtonUp => self.is_down = false,
+            OverlayEvent::FocusLeave => {
+                self.point = None;
+                self.is_down = false;
+            }
+            OverlayEvent::FocusEnter
+            | OverlayEvent::Shown
+            | OverlayEvent::Hidden
+            | OverlayEvent::Other => {}
+        }
+    }
+
+    /// Angle and magnitude to select with, like a stick deflection. Pointing off the menu selects
+    /// nothing.
+    pub fn polar(&self) -> (f32, f32) {
+        self.point
+            .map(hit_test::point_to_polar)
+            .filter(|(_, magnitude)| !hit_test::is_outside(*magnitude))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    pub fn click(&self) -> f32 {
+        if self.is_down {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_pointing_at_the_center() {
+        let mut laser = LaserPointer::new(512.0, 512.0);
+        laser.on_event(OverlayEvent::MouseMove { x: 256.0, y: 256.0 });
+
+        assert!(laser.polar().1 < 1e-6);
+    }
+
+    #[test]
+    fn test_pointing_at_the_top() {
+        let mut laser = LaserPointer::new(512.0, 512.0);
+        laser.on_event(OverlayEvent::MouseMove { x: 256.0, y: 400.0 });
+
+        let (angle, magnitude) = laser.polar();
+        assert!((angle - PI * 1.5).abs() < 1e-5);
+        assert!((magnitude - 144.0 / 230.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_leaving_the_overlay_releases() {
+        let mut laser = LaserPointer::new(512.0, 512.0);
+        laser.on_event(OverlayEvent::MouseMove { x: 400.0, y: 256.0 });
+        laser.on_event(OverlayEvent::MouseButtonDown);
+        assert!(laser.click() > 0.5);
+
+        laser.on_event(OverlayEvent::FocusLeave);
+        assert!(laser.click() < 0.5);
+        assert!(laser.polar().1 < 1e-6);
+    }
+
+    #[test]
+    fn test_pointing_off_the_menu_selects_nothing() {
+        let mut laser = LaserPointer::new(512.0, 512.0);
+        laser.on_event(OverlayEvent::MouseMove { x: 2.0, y: 2.0 });
+
+        assert!(laser.polar().1 < 1e-6);
+    }
+}
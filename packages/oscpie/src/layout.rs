@@ -0,0 +1,283 @@
+use std::f32::consts::PI;
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinal {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Cardinal {
+    /// Angle in radians, measured clockwise from east as the menu is drawn on screen.
+    pub fn angle(self) -> f32 {
+        match self {
+            Cardinal::East => 0.0,
+            Cardinal::South => PI * 0.5,
+            Cardinal::West => PI,
+            Cardinal::North => PI * 1.5,
+        }
+    }
+}
+
+impl From<config::types::Cardinal> for Cardinal {
+    fn from(cardinal: config::types::Cardinal) -> Self {
+        match cardinal {
+            config::types::Cardinal::North => Cardinal::North,
+            config::types::Cardinal::East => Cardinal::East,
+            config::types::Cardinal::South => Cardinal::South,
+            config::types::Cardinal::West => Cardinal::West,
+        }
+    }
+}
+
+/// Computes the `(start, end)` angle of every slice in radians, clockwise from east.
+///
+/// Without anchors the slices are laid out clockwise from `rotation`. Anchored items are centered
+/// on their cardinal direction and the remaining items fill the leftover arcs in order, in which
+/// case `rotation` is ignored.
+#[allow(clippy::cast_precision_loss)]
+pub fn slice_angles(rotation: f32, anchors: &[Option<Cardinal>]) -> Vec<(f32, f32)> {
+    let count = anchors.len();
+
+    if count == 0 {
+        return vec![];
+    }
+
+    let width = PI * 2.0 / count as f32;
+
+    let mut pinned: Vec<(usize, f32)> = vec![];
+
+    for (index, anchor) in anchors.iter().enumerate() {
+        let Some(anchor) = anchor else {
+            continue;
+        };
+
+        if anchors[..index].contains(&Some(*anchor)) {
+            log::warn!("Item {index} is anchored to {anchor:?} which is already taken, ignoring");
+            continue;
+        }
+
+        pinned.push((index, anchor.angle()));
+    }
+
+    if pinned.is_empty() {
+        return (0..count)
+            .map(|i| {
+                (
+                    rotation + i as f32 * width,
+                    rotation + (i + 1) as f32 * width,
+                )
+            })
+            .collect();
+    }
+
+    pinned.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let next_pinned_angle = |k: usize| {
+        if k + 1 == pinned.len() {
+            pinned[0].1 + PI * 2.0
+        } else {
+            pinned[k + 1].1
+        }
+    };
+
+    let min_spacing = (0..pinned.len())
+        .map(|k| next_pinned_angle(k) - pinned[k].1)
+        .fold(PI * 2.0, f32::min);
+
+    let half_width = (width / 2.0).min(min_spacing / 2.0);
+
+    let mut slices = vec![(0.0, 0.0); count];
+
+    for (index, angle) in &pinned {
+        slices[*index] = (angle - half_width, angle + half_width);
+    }
+
+    let gaps: Vec<(f32, f32)> = (0..pinned.len())
+        .map(|k| (pinned[k].1 + half_width, next_pinned_angle(k) - half_width))
+        .collect();
+
+    let unpinned: Vec<usize> = (0..count)
+        .filter(|index| !pinned.iter().any(|(pinned_index, _)| pinned_index == index))
+        .collect();
+
+    let item_counts = distribute(unpinned.len(), &gaps);
+
+    let mut unpinned = unpinned.into_iter();
+
+    for (k, ((gap_start, gap_end), item_count)) in gaps.iter().zip(item_counts).enumerate() {
+        if item_count == 0 {
+            // Let the neighbouring anchored items cover the empty gap.
+            let middle = f32::midpoint(*gap_start, *gap_end);
+            slices[pinned[k].0].1 = middle;
+
+            let next = (k + 1) % pinned.len();
+            slices[pinned[next].0].0 = if next == 0 { middle - PI * 2.0 } else { middle };
+
+            continue;
+        }
+
+        let item_width = (gap_end - gap_start) / item_count as f32;
+
+        for i in 0..item_count {
+            let index = unpinned.next().unwrap();
+            slices[index] = (
+                gap_start + i as f32 * item_width,
+                gap_start + (i + 1) as f32 * item_width,
+            );
+        }
+    }
+
+    slices
+}
+
+/// Computes the `(start, end)` angle of every slice of a half circle centered on `towards`, laid
+/// out clockwise. Anchors don't apply, since half of the directions are off the menu.
+#[allow(clippy::cast_precision_loss)]
+pub fn half_circle_slice_angles(towards: Cardinal, count: usize) -> Vec<(f32, f32)> {
+    let start = towards.angle() - PI * 0.5;
+    let width = PI / count.max(1) as f32;
+
+    (0..count)
+        .map(|i| (start + i as f32 * width, start + (i + 1) as f32 * width))
+        .collect()
+}
+
+/// Splits `count` items across the gaps proportionally to their length.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn distribute(count: usize, gaps: &[(f32, f32)]) -> Vec<usize> {
+    let total: f32 = gaps.iter().map(|(start, end)| end - start).sum();
+
+    if count == 0 || total <= 0.0 {
+        return vec![0; gaps.len()];
+    }
+
+    let shares: Vec<f32> = gaps
+        .iter()
+        .map(|(start, end)| (end - start) / total * count as f32)
+        .collect();
+
+    let mut counts: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+
+    let mut by_remainder: Vec<usize> = (0..gaps.len()).collect();
+    by_remainder.sort_by(|a, b| {
+        (shares[*b] - shares[*b].floor()).total_cmp(&(shares[*a] - shares[*a].floor()))
+    });
+
+    for k in by_remainder
+        .into_iter()
+        .cycle()
+        .take(count - counts.iter().sum::<usize>())
+    {
+        counts[k] += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_slices(actual: &[(f32, f32)], expected: &[(f32, f32)]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for ((start, end), (expected_start, expected_end)) in actual.iter().zip(expected) {
+            assert!(
+                (start - expected_start).abs() < 1e-5 && (end - expected_end).abs() < 1e-5,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_layout() {
+        let slices = slice_angles(0.0, &[None, None, None, None]);
+
+        assert_slices(
+            &slices,
+            &[
+                (0.0, PI * 0.5),
+                (PI * 0.5, PI),
+                (PI, PI * 1.5),
+                (PI * 1.5, PI * 2.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_rotation() {
+        let slices = slice_angles(PI * 1.25, &[None, None, None, None]);
+
+        assert_slices(
+            &slices,
+            &[
+                (PI * 1.25, PI * 1.75),
+                (PI * 1.75, PI * 2.25),
+                (PI * 2.25, PI * 2.75),
+                (PI * 2.75, PI * 3.25),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_anchor_north() {
+        let slices = slice_angles(0.0, &[None, Some(Cardinal::North), None, None]);
+
+        assert_slices(
+            &slices,
+            &[
+                (PI * 1.75, PI * 2.25),
+                (PI * 1.25, PI * 1.75),
+                (PI * 2.25, PI * 2.75),
+                (PI * 2.75, PI * 3.25),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_anchors_cover_empty_gaps() {
+        let slices = slice_angles(0.0, &[Some(Cardinal::East), Some(Cardinal::West), None]);
+
+        let total: f32 = slices.iter().map(|(start, end)| end - start).sum();
+        assert!((total - PI * 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_duplicate_anchor_is_ignored() {
+        let slices = slice_angles(
+            0.0,
+            &[Some(Cardinal::South), Some(Cardinal::South), None, None],
+        );
+
+        assert_slices(&slices[0..1], &[(PI * 0.25, PI * 0.75)]);
+    }
+
+    #[test]
+    fn test_half_circle_towards_north() {
+        let slices = half_circle_slice_angles(Cardinal::North, 3);
+
+        assert_slices(
+            &slices,
+            &[
+                (PI, PI * 4.0 / 3.0),
+                (PI * 4.0 / 3.0, PI * 5.0 / 3.0),
+                (PI * 5.0 / 3.0, PI * 2.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_half_circle_towards_east_wraps() {
+        let slices = half_circle_slice_angles(Cardinal::East, 2);
+
+        assert_slices(&slices, &[(-PI * 0.5, 0.0), (0.0, PI * 0.5)]);
+    }
+}
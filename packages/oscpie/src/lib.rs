@@ -0,0 +1,1635 @@
+mod action_behaviours;
+mod analytics;
+mod attachment;
+mod audit;
+mod builder;
+mod components;
+mod config;
+mod contrast;
+mod control;
+mod d3d11;
+mod deadman;
+mod debug;
+mod devices;
+mod dirty_rows;
+#[cfg(all(test, feature = "osc"))]
+mod example_configs;
+mod frame_capture;
+mod gaze;
+mod gesture;
+mod gesture_input;
+mod hand;
+mod hit_test;
+mod input_expression;
+mod laser;
+mod layout;
+mod menu;
+mod notifications;
+mod openvr;
+#[cfg(feature = "osc")]
+mod osc;
+mod overlay_layers;
+mod overlay_texture;
+mod pose_prediction;
+mod power;
+mod prelude;
+mod preview;
+mod registration;
+mod resource;
+mod runtime;
+mod side_effects;
+mod soak;
+mod storage;
+mod theme;
+mod tutorial;
+mod utils;
+mod validator;
+mod versioned;
+mod vulkan;
+
+use std::{
+    collections::HashMap,
+    f32::consts::PI,
+    sync::mpsc::{channel, Receiver},
+};
+
+use crate::{debug::rt_debug, prelude::*};
+use action_behaviours::key_stroke;
+use analytics::Analytics;
+use anyhow::Result;
+use components::{
+    breadcrumb::BreadcrumbComponent, menu_transition::MenuTransitionComponent, pie_menu,
+    tutorial::TutorialComponent, MenuComponent,
+};
+use config::{Config, ConfigWatcher, MenuSnippet};
+use contrast::AutoContrast;
+use control::ControlMessage;
+use deadman::{Deadman, DeadmanState};
+use devices::DeviceInfo;
+use dirty_rows::{DirtyRows, FrameDiff};
+use gesture::{PoseSample, SwipeAction, SwipeDetector};
+use hand::HandSelector;
+use hit_test::Hysteresis;
+use input_expression::InputExpression;
+use laser::LaserPointer;
+use notifications::{NotificationHistory, NOTIFICATIONS_MENU_ID};
+use oscpie_ui::{sprite::SpriteSheet, Component};
+use overlay_layers::{OverlayLayer, OverlayStack};
+use resource::{get_sprite_sheet, Animations, Labels, ANIMATIONS, LABELS, SPRITE_SHEET};
+use theme::Theme;
+use tiny_skia::Pixmap;
+use tutorial::Tutorial;
+
+pub use builder::CoreBuilder;
+pub use menu::{ActionContext, ActionResult, MenuActionBehaviour, Severity};
+
+const CONFIG_PATH: &str = "config/config.json";
+
+/// Font of the labels when the config does not name one.
+const DEFAULT_FONT_PATH: &str = "C:\\Windows\\Fonts\\segoeui.ttf";
+
+struct AppInput {
+    angle: f32,
+    magnitude: f32,
+    click: f32,
+    open_menu: bool,
+    pose: Option<PoseSample>,
+    /// Whether the controller driving the menu is tracked.
+    is_tracked: bool,
+}
+
+trait App {
+    fn on_update(&mut self, _input: AppInput) -> Result<()>;
+    /// Draws the menu, returning the rows which changed since the previous frame.
+    fn on_render(&mut self, _: &mut Pixmap) -> Result<Option<DirtyRows>>;
+}
+
+struct AppImpl {
+    config: Config,
+    fps: Fps,
+    interval_timer_update: IntervalTimer,
+    interval_timer_render: IntervalTimer,
+    should_render: bool,
+    current_pie_menu_component: MenuComponent,
+    /// Zoom from the previous menu, while it runs.
+    menu_transition: Option<MenuTransitionComponent>,
+    /// Set when the menu stack is pushed or popped, for the next menu to zoom in.
+    should_transition: bool,
+    breadcrumb: BreadcrumbComponent,
+    theme: Theme,
+    hysteresis: Hysteresis,
+    menu_map: HashMap<MenuId, Menu>,
+    action_context: ActionContext,
+    event_receiver: Receiver<AppEvent>,
+    menu_stack: Vec<MenuId>,
+    /// Active entry of [`Config::profiles`], the config's own root is used while it is `None`.
+    profile: Option<String>,
+    /// Application key of the VR app rendering the scene, as of the last check.
+    scene_app: Option<String>,
+    /// Connected tracked devices, as of the last check.
+    devices: Vec<DeviceInfo>,
+    is_open: bool,
+    open_menu_state_machine: ClickStateMachine,
+    after_action: AfterAction,
+    tutorial: Option<Tutorial>,
+    tutorial_component: TutorialComponent,
+    auto_contrast: Option<AutoContrast>,
+    contrast_time_delta: TimeDelta,
+    notifications: NotificationHistory,
+    swipe_detector: Option<SwipeDetector>,
+    swipe_time_delta: TimeDelta,
+    analytics: Option<Analytics>,
+    /// When the menu was opened or the last action fired, while it is open.
+    select_started_at: Option<std::time::Instant>,
+    deadman: Deadman,
+    deadman_time_delta: TimeDelta,
+    /// Input of the last frame with tracking, replayed while the selection is frozen.
+    last_tracked_input: PieMenuInput,
+    /// Set by [`ControlMessage::Shutdown`] or when SteamVR quits, the main loop exits once it sees
+    /// it.
+    should_exit: bool,
+    /// Toasts with their icon, to be shown as SteamVR notifications by the main loop.
+    pending_notifications: Vec<(String, Option<String>)>,
+    /// Whether SteamVR shows the menu overlay, as of its last event.
+    is_overlay_visible: bool,
+    /// Whether the laser pointer is on the menu overlay.
+    has_overlay_focus: bool,
+    config_watcher: ConfigWatcher,
+    /// Previous frame, to upload only what changed.
+    frame_diff: FrameDiff,
+}
+
+impl AppImpl {
+    fn new(configuration: &Config) -> AppImpl {
+        let (event_sender, event_receiver) = channel();
+        let action_context = ActionContext::new(event_sender);
+
+        let menu_map = Self::create_menu_map(configuration);
+        let theme = Theme::from_config(&configuration.theme);
+        let size = Self::menu_size(configuration);
+
+        Self {
+            config: configuration.clone(),
+            fps: Fps::new(60),
+            interval_timer_update: IntervalTimer::new(1000.0),
+            interval_timer_render: IntervalTimer::new(1000.0),
+            should_render: true,
+            current_pie_menu_component: Self::create_pie_menu(
+                menu_map
+                    .get(&MenuId::from_config(&configuration.root))
+                    .unwrap(),
+                &action_context,
+                theme,
+                size,
+            ),
+            menu_transition: None,
+            should_transition: false,
+            breadcrumb: BreadcrumbComponent::new(&[configuration.root.inner()], theme, size),
+            theme,
+            hysteresis: Hysteresis::from_config(&configuration.selection),
+            menu_map,
+            action_context,
+            event_receiver,
+            menu_stack: vec![MenuId::from_config(&configuration.root)],
+            profile: None,
+            scene_app: None,
+            devices: Vec::new(),
+            is_open: false,
+            open_menu_state_machine: ClickStateMachine::new(),
+            after_action: configuration.after_action.into(),
+            tutorial: (!configuration.tutorial_completed).then(Tutorial::new),
+            tutorial_component: Self::create_tutorial_component(size),
+            auto_contrast: configuration
+                .auto_contrast
+                .map(|source| AutoContrast::new(source.into())),
+            contrast_time_delta: TimeDelta::new(),
+            notifications: NotificationHistory::new(configuration.notification_history),
+            swipe_detector: configuration.swipe.as_ref().map(SwipeDetector::new),
+            swipe_time_delta: TimeDelta::new(),
+            analytics: configuration.analytics.then(|| {
+                analytics::load(&Self::analytics_path()).unwrap_or_else(|e| {
+                    log::warn!("Failed to load analytics, starting over: {e}");
+                    Analytics::default()
+                })
+            }),
+            select_started_at: None,
+            deadman: Deadman::new(&configuration.tracking_loss),
+            deadman_time_delta: TimeDelta::new(),
+            last_tracked_input: PieMenuInput::new(0.0, 0.0, 0.0),
+            should_exit: false,
+            pending_notifications: Vec::new(),
+            is_overlay_visible: false,
+            has_overlay_focus: false,
+            config_watcher: ConfigWatcher::new(CONFIG_PATH),
+            frame_diff: FrameDiff::default(),
+        }
+    }
+
+    fn create_menu_map(configuration: &Config) -> HashMap<MenuId, Menu> {
+        let mut menu_map = HashMap::new();
+
+        for (id, menu) in &configuration.menus {
+            let menu: Menu = Menu::from_config(menu);
+            menu_map.insert(MenuId::from_config(id), menu);
+        }
+
+        menu_map
+    }
+
+    fn root_menu(configuration: &Config, profile: Option<&str>) -> MenuId {
+        let root = profile
+            .and_then(|profile| configuration.profiles.get(profile))
+            .map_or(&configuration.root, |profile| &profile.root);
+
+        MenuId::from_config(root)
+    }
+
+    /// Size of the menu texture in pixels.
+    #[allow(clippy::cast_precision_loss)]
+    fn menu_size(configuration: &Config) -> f32 {
+        configuration.overlay.texture_size() as f32
+    }
+
+    fn create_pie_menu(
+        menu: &Menu,
+        action_context: &ActionContext,
+        theme: Theme,
+        size: f32,
+    ) -> MenuComponent {
+        MenuComponent::new(menu, action_context, theme, size)
+    }
+
+    fn create_tutorial_component(size: f32) -> TutorialComponent {
+        TutorialComponent::new(size * 0.5, size * 0.5, components::pie_radius(size))
+    }
+
+    fn replace_pie_menu(&mut self) {
+        let should_transition = std::mem::take(&mut self.should_transition);
+
+        let Some(menu_id) = self.menu_stack.last().cloned() else {
+            log::error!("No menu ID found in the stack");
+            return;
+        };
+
+        let menu = if menu_id.inner() == NOTIFICATIONS_MENU_ID {
+            Some(self.notifications.menu())
+        } else {
+            self.menu_map.get(&menu_id).cloned()
+        };
+
+        if let Some(menu) = menu {
+            let size = Self::menu_size(&self.config);
+            let menu = if self.menu_stack.len() > 1 {
+                menu.with_back_item()
+            } else {
+                menu
+            };
+
+            let outgoing = std::mem::replace(
+                &mut self.current_pie_menu_component,
+                Self::create_pie_menu(&menu, &self.action_context, self.theme, size),
+            );
+
+            if should_transition {
+                let origin = outgoing.selected_point();
+                self.menu_transition = Some(MenuTransitionComponent::new(outgoing, origin));
+            }
+
+            let names: Vec<&str> = self.menu_stack.iter().map(MenuId::inner).collect();
+            self.breadcrumb = BreadcrumbComponent::new(&names, self.theme, size);
+            self.breadcrumb
+                .update(&self.current_pie_menu_component.breadcrumb_props());
+        } else {
+            log::error!("Menu with ID {menu_id:?} not found");
+        }
+    }
+
+    fn apply_snippet(&mut self, snippet: MenuSnippet) -> Result<()> {
+        let mut merged = config::merge_snippet(&self.config, snippet)?;
+        config::resolve_library(&mut merged);
+
+        self.menu_map = Self::create_menu_map(&merged);
+        self.config = merged;
+        self.replace_pie_menu();
+
+        Ok(())
+    }
+
+    /// Reads the config file again. On error the current config stays active. The sprite sheet and
+    /// animations are loaded once at startup and are kept as they are.
+    fn reload_config(&mut self) -> Result<()> {
+        let config = config::reload(CONFIG_PATH)?;
+        self.config_watcher.mark_seen();
+
+        if let Some(sprite_sheet) = get_sprite_sheet() {
+            check_config(&config, sprite_sheet)?;
+        }
+
+        let menu_map = Self::create_menu_map(&config);
+
+        if self
+            .profile
+            .as_ref()
+            .is_some_and(|profile| !config.profiles.contains_key(profile))
+        {
+            log::warn!("Profile {:?} was removed, switching back", self.profile);
+            self.profile = None;
+        }
+
+        let root = Self::root_menu(&config, self.profile.as_deref());
+
+        // Stay in the current sub menu if it survived the reload
+        let stack_is_valid = self.menu_stack.first() == Some(&root)
+            && self
+                .menu_stack
+                .iter()
+                .all(|id| id.inner() == NOTIFICATIONS_MENU_ID || menu_map.contains_key(id));
+
+        if !stack_is_valid {
+            self.menu_stack = vec![root];
+        }
+
+        self.menu_map = menu_map;
+        self.after_action = config.after_action.into();
+        self.swipe_detector = config.swipe.as_ref().map(SwipeDetector::new);
+        self.deadman = Deadman::new(&config.tracking_loss);
+        self.theme = Theme::from_config(&config.theme);
+        self.hysteresis = Hysteresis::from_config(&config.selection);
+
+        if config.overlay.texture_size() != self.config.overlay.texture_size() {
+            self.tutorial_component = Self::create_tutorial_component(Self::menu_size(&config));
+        }
+
+        self.config = config;
+        self.replace_pie_menu();
+
+        Ok(())
+    }
+
+    fn poll_config_changes(&mut self) {
+        if !self.config_watcher.poll() {
+            return;
+        }
+
+        log::info!("{CONFIG_PATH} changed, reloading");
+        self.on_control_message(ControlMessage::ReloadConfig);
+    }
+
+    fn open_menu(&mut self, menu_id: MenuId) {
+        if menu_id.inner() != NOTIFICATIONS_MENU_ID && !self.menu_map.contains_key(&menu_id) {
+            log::warn!("Cannot open menu {menu_id:?}, it does not exist");
+            return;
+        }
+
+        self.menu_stack.truncate(1);
+
+        if self.menu_stack.first() != Some(&menu_id) {
+            self.menu_stack.push(menu_id);
+        }
+
+        self.replace_pie_menu();
+        self.is_open = true;
+    }
+
+    fn switch_profile(&mut self, profile: Option<String>) {
+        self.menu_stack = vec![Self::root_menu(&self.config, profile.as_deref())];
+        self.profile = profile;
+    }
+
+    /// Switches to the profile listing the app which now renders the scene, or back to the
+    /// config's own root when none does.
+    fn on_devices(&mut self, devices: Vec<DeviceInfo>) {
+        rt_debug(|| {
+            (
+                "50_devices".to_string(),
+                devices
+                    .iter()
+                    .map(DeviceInfo::describe)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        });
+
+        for device in &devices {
+            if devices::has_run_low(&self.devices, device) {
+                self.action_context.toast(
+                    Severity::Info,
+                    format!("Battery low: {}", device.describe()),
+                );
+            }
+        }
+
+        self.devices = devices;
+    }
+
+    fn on_scene_app(&mut self, app_key: Option<String>) {
+        if app_key == self.scene_app {
+            return;
+        }
+
+        log::info!("Scene app changed to {app_key:?}");
+
+        let profile = app_key.as_ref().and_then(|app_key| {
+            let mut profiles: Vec<_> = self
+                .config
+                .profiles
+                .iter()
+                .filter(|(_, profile)| profile.apps.contains(app_key))
+                .map(|(name, _)| name.clone())
+                .collect();
+            profiles.sort_unstable();
+            profiles.into_iter().next()
+        });
+
+        self.scene_app = app_key;
+
+        if profile != self.profile {
+            log::info!("Switching to profile {profile:?}");
+            self.switch_profile(profile);
+            self.replace_pie_menu();
+        }
+    }
+
+    fn on_control_message(&mut self, message: ControlMessage) {
+        match message {
+            ControlMessage::MergeMenuSnippet { snippet } => match self.apply_snippet(snippet) {
+                Ok(()) => self
+                    .action_context
+                    .toast(Severity::Info, "Menu snippet merged"),
+                Err(e) => {
+                    log::warn!("Failed to merge menu snippet: {e}");
+                    self.action_context.toast(
+                        Severity::Error,
+                        format!("Failed to merge menu snippet: {e}"),
+                    );
+                }
+            },
+            ControlMessage::SetSceneBrightness { brightness } => {
+                if let Some(auto_contrast) = &mut self.auto_contrast {
+                    auto_contrast.set_brightness(brightness);
+                } else {
+                    log::debug!("Ignoring scene brightness, auto contrast is disabled");
+                }
+            }
+            ControlMessage::ReloadConfig => match self.reload_config() {
+                Ok(()) => self.action_context.toast(Severity::Info, "Config reloaded"),
+                Err(e) => {
+                    log::warn!("Failed to reload config: {e}");
+                    self.action_context
+                        .toast(Severity::Error, format!("Failed to reload config: {e}"));
+                }
+            },
+            ControlMessage::OpenMenu { menu } => self.open_menu(MenuId::from_config(&menu)),
+            ControlMessage::CloseMenu => self.is_open = false,
+            ControlMessage::SetVisibility { visible } => self.is_open = visible,
+            ControlMessage::LogRecentActions => {
+                for entry in audit::entries() {
+                    log::info!("{entry}");
+                }
+            }
+            ControlMessage::Shutdown => {
+                log::info!("Shutdown requested over the control channel");
+                self.should_exit = true;
+            }
+        }
+    }
+
+    fn on_system_event(&mut self, event: openvr::SystemEvent) {
+        match event {
+            openvr::SystemEvent::Quit => {
+                log::info!("SteamVR is quitting");
+                self.should_exit = true;
+            }
+            openvr::SystemEvent::Other => {}
+        }
+    }
+
+    fn on_overlay_event(&mut self, event: openvr::OverlayEvent) {
+        match event {
+            openvr::OverlayEvent::Shown => self.is_overlay_visible = true,
+            openvr::OverlayEvent::Hidden => self.is_overlay_visible = false,
+            openvr::OverlayEvent::FocusEnter => self.has_overlay_focus = true,
+            openvr::OverlayEvent::FocusLeave => self.has_overlay_focus = false,
+            _ => return,
+        }
+
+        rt_debug(|| {
+            (
+                "40_overlay".to_string(),
+                format!(
+                    "Overlay visible: {}, focused: {}",
+                    self.is_overlay_visible, self.has_overlay_focus
+                ),
+            )
+        });
+    }
+
+    /// Handles an event emitted by an action. Returns whether the pie menu has to be rebuilt.
+    fn on_app_event(&mut self, event: AppEvent) -> bool {
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.on_event(&event);
+        }
+
+        match event {
+            AppEvent::PopStack => {
+                if self.menu_stack.len() > 1 {
+                    self.menu_stack.pop();
+                    self.should_transition = true;
+                    return true;
+                }
+
+                log::warn!("Attempted to pop the root menu, ignoring.");
+            }
+            AppEvent::PushStack(to) => {
+                self.menu_stack.push(to.clone());
+                self.should_transition = true;
+                return true;
+            }
+            AppEvent::Toast {
+                severity,
+                message,
+                icon,
+            } => {
+                log::info!("Toast ({severity:?}): {message}");
+
+                self.pending_notifications.push((message.clone(), icon));
+
+                self.notifications.push(severity, message);
+
+                // Keep the notifications menu up to date while it is open
+                if self
+                    .menu_stack
+                    .last()
+                    .is_some_and(|id| id.inner() == NOTIFICATIONS_MENU_ID)
+                {
+                    return true;
+                }
+            }
+            AppEvent::Haptics => {
+                // TODO: Trigger haptic vibration once an output action is available
+                log::debug!("Haptics requested");
+            }
+            AppEvent::ActionFired { action, keep_open } => {
+                if let Some(analytics) = &mut self.analytics {
+                    if let Some(started_at) =
+                        self.select_started_at.replace(std::time::Instant::now())
+                    {
+                        analytics.record_selection(action, started_at.elapsed());
+                    }
+                }
+
+                return self.on_action_fired(keep_open);
+            }
+            AppEvent::StartTutorial => {
+                self.tutorial = Some(Tutorial::new());
+            }
+            AppEvent::SwitchProfile(profile) => {
+                if !self.config.profiles.contains_key(&profile) {
+                    log::warn!("Cannot switch to profile '{profile}', it does not exist");
+                    return false;
+                }
+
+                self.switch_profile(Some(profile));
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Applies [`AfterAction`] for an item. Returns whether the pie menu has to be rebuilt.
+    fn on_action_fired(&mut self, keep_open: Option<bool>) -> bool {
+        match self.after_action.resolve(keep_open) {
+            AfterAction::StayOpen => {}
+            AfterAction::Close => {
+                self.is_open = false;
+            }
+            AfterAction::ReturnToRoot => {
+                if self.menu_stack.len() > 1 {
+                    self.menu_stack.truncate(1);
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn analytics_path() -> std::path::PathBuf {
+        resolve_path(CONFIG_PATH, analytics::ANALYTICS_FILE)
+    }
+
+    /// Counts opens and saves the statistics whenever the menu closes.
+    fn track_analytics(&mut self) {
+        let Some(analytics) = &mut self.analytics else {
+            return;
+        };
+
+        match (self.select_started_at.is_some(), self.is_open) {
+            (false, true) => {
+                analytics.record_open();
+                self.select_started_at = Some(std::time::Instant::now());
+            }
+            (true, false) => {
+                self.select_started_at = None;
+
+                if let Err(e) = analytics::save(&Self::analytics_path(), analytics) {
+                    log::warn!("Failed to save analytics: {e}");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles the events sent since the last call, returns whether the menu has to be replaced.
+    fn drain_app_events(&mut self) -> bool {
+        let mut should_replace_menu = false;
+
+        while let Ok(event) = self.event_receiver.try_recv() {
+            should_replace_menu |= self.on_app_event(event);
+        }
+
+        should_replace_menu
+    }
+
+    /// Applies a swipe gesture. Returns whether the pie menu has to be rebuilt.
+    fn on_swipe(&mut self, action: SwipeAction) -> bool {
+        match action {
+            // Goes through the event so that the tutorial sees it like the back item
+            SwipeAction::Back => self.action_context.emit(AppEvent::PopStack),
+            SwipeAction::Close => self.is_open = false,
+            SwipeAction::ReturnToRoot => {
+                if self.menu_stack.len() > 1 {
+                    self.menu_stack.truncate(1);
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn update_tutorial(&mut self, input: &PieMenuInput) {
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
+
+        tutorial.on_input(input);
+
+        if tutorial.is_done() {
+            log::info!("Tutorial completed");
+            self.finish_tutorial();
+            return;
+        }
+
+        let step = tutorial.step();
+
+        // The back item is always the first one in sub menus
+        let target_angle = if step == tutorial::TutorialStep::GoBack {
+            self.current_pie_menu_component.item_angle(0)
+        } else {
+            None
+        };
+
+        self.tutorial_component
+            .update(&components::tutorial::Props::new(step, target_angle));
+    }
+
+    fn finish_tutorial(&mut self) {
+        self.tutorial = None;
+
+        if self.config.tutorial_completed {
+            return;
+        }
+
+        self.config.tutorial_completed = true;
+
+        if let Err(e) = config::update(CONFIG_PATH, |config| config.tutorial_completed = true) {
+            log::warn!("Failed to save tutorial completion: {e}");
+        }
+
+        self.config_watcher.mark_seen();
+    }
+}
+
+impl App for AppImpl {
+    fn on_update(&mut self, input: AppInput) -> Result<()> {
+        let timing_check = TimingCheck::new();
+        self.should_render = true;
+
+        let AppInput {
+            angle,
+            magnitude,
+            click,
+            open_menu,
+            pose,
+            is_tracked,
+        } = input;
+
+        let deadman_dt = self.deadman_time_delta.update_and_get_secs();
+
+        let open_menu_state_machine_event = self.open_menu_state_machine.update(open_menu);
+
+        if let Some(ClickStateMachineEvent::Click) = open_menu_state_machine_event {
+            self.is_open = !self.is_open;
+
+            if !self.is_open && self.tutorial.is_some() {
+                log::info!("Tutorial dismissed");
+                self.finish_tutorial();
+            }
+        }
+
+        self.track_analytics();
+
+        // Drained even while the menu is closed, so that toasts from reloads, imports and the
+        // control channel are shown as they happen
+        let mut should_replace_menu = self.drain_app_events();
+
+        // Cull if the menu is not open
+        if !self.is_open {
+            if should_replace_menu {
+                self.replace_pie_menu();
+            }
+
+            self.menu_transition = None;
+            self.deadman.reset();
+            key_stroke::release_held_keys();
+            return Ok(());
+        }
+
+        let swipe_dt = self.swipe_time_delta.update_and_get_secs();
+        let swipe = match (&mut self.swipe_detector, &pose) {
+            (Some(swipe_detector), Some(pose)) => swipe_detector.update(pose, swipe_dt),
+            _ => None,
+        };
+
+        if let Some(action) = swipe {
+            should_replace_menu |= self.on_swipe(action);
+            // The back swipe goes through the event channel
+            should_replace_menu |= self.drain_app_events();
+        }
+
+        if should_replace_menu {
+            self.replace_pie_menu();
+        }
+
+        if !self.is_open {
+            return Ok(());
+        }
+
+        let pie_menu_input = match self.deadman.update(is_tracked, deadman_dt) {
+            DeadmanState::Tracking => {
+                self.last_tracked_input = PieMenuInput {
+                    angle,
+                    magnitude,
+                    click,
+                };
+                self.last_tracked_input
+            }
+            DeadmanState::Frozen => self.last_tracked_input,
+            DeadmanState::Expired => {
+                self.is_open = false;
+                self.deadman.reset();
+
+                // Handled right away, the events of this frame have already been processed
+                self.on_app_event(AppEvent::Toast {
+                    severity: Severity::Info,
+                    message: "Menu closed because the controller lost tracking".to_string(),
+                    icon: None,
+                });
+
+                return Ok(());
+            }
+        };
+
+        self.update_tutorial(&pie_menu_input);
+
+        let dt = self.contrast_time_delta.update_and_get_secs();
+        let contrast = self
+            .auto_contrast
+            .as_mut()
+            .map_or(0.0, |auto_contrast| auto_contrast.update(dt));
+
+        self.current_pie_menu_component.update(
+            &pie_menu::Props::new(pie_menu_input)
+                .contrast(contrast)
+                .hysteresis(self.hysteresis),
+        );
+
+        if let Some(menu_transition) = &mut self.menu_transition {
+            menu_transition.update();
+
+            if menu_transition.is_done() {
+                self.menu_transition = None;
+            }
+        }
+
+        self.fps.update();
+
+        let time_elapsed_ns = timing_check.get_time_ns();
+
+        if self.interval_timer_update.update() {
+            log::info!("update: {time_elapsed_ns}ns");
+            log::info!("fps: {}", self.fps.get_fps());
+        }
+
+        Ok(())
+    }
+
+    fn on_render(&mut self, pixmap: &mut Pixmap) -> Result<Option<DirtyRows>> {
+        let timing_check = TimingCheck::new();
+
+        if !self.is_open {
+            return Ok(None);
+        }
+
+        pixmap.fill(tiny_skia::Color::from_rgba(0.0, 0.0, 0.0, 0.0).unwrap());
+
+        if self.should_render {
+            self.should_render = false;
+        } else {
+            return Ok(self.frame_diff.update(pixmap));
+        }
+
+        match &self.menu_transition {
+            Some(menu_transition) => {
+                menu_transition.render(&self.current_pie_menu_component, pixmap);
+            }
+            None => self.current_pie_menu_component.render(pixmap),
+        }
+
+        self.breadcrumb.render(pixmap);
+
+        if self.tutorial.is_some() {
+            self.tutorial_component.render(pixmap);
+        }
+
+        let dirty = self.frame_diff.update(pixmap);
+
+        if self.interval_timer_render.update() {
+            log::info!("render: {}ns", timing_check.get_time_ns());
+        }
+
+        Ok(dirty)
+    }
+}
+
+/// Logs every problem of the config, and fails if any of them is an error.
+fn check_config(config: &Config, sprite_sheet: &SpriteSheet) -> Result<()> {
+    let problems = config::validate(config, sprite_sheet);
+
+    for problem in &problems {
+        if problem.is_error() {
+            log::error!("{problem}");
+        } else {
+            log::warn!("{problem}");
+        }
+    }
+
+    let errors = problems.iter().filter(|problem| problem.is_error()).count();
+
+    if errors > 0 {
+        return Err(anyhow!(
+            "The config has {errors} error(s), see the log for details"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Loads the config and its resources, creating the app driven by the main loop.
+fn load_app() -> Result<(Config, AppImpl)> {
+    if !std::path::Path::new(CONFIG_PATH).exists() {
+        log::info!("{CONFIG_PATH} does not exist, writing a starter config");
+        config::write_default(CONFIG_PATH)?;
+    }
+
+    let config = config::load(CONFIG_PATH)?;
+
+    let sprite_sheet = SpriteSheet::load(resolve_path(CONFIG_PATH, &config.sprite_sheet))
+        .map_err(|e| anyhow!("Failed to load the sprite sheet: {e}"))?;
+
+    check_config(&config, &sprite_sheet)?;
+
+    SPRITE_SHEET.set(sprite_sheet).unwrap();
+
+    ANIMATIONS
+        .set(Animations::from_config(&config.animations))
+        .unwrap();
+
+    let font_path = config.font.path.as_ref().map_or_else(
+        || std::path::PathBuf::from(DEFAULT_FONT_PATH),
+        |path| resolve_path(CONFIG_PATH, path),
+    );
+
+    match oscpie_ui::font::Font::load(font_path) {
+        Ok(font) => {
+            let _ = LABELS.set(Labels {
+                font,
+                size: config.font.size,
+            });
+        }
+        Err(e) => log::warn!("Failed to load the font, labels are hidden: {e}"),
+    }
+
+    #[cfg(feature = "osc")]
+    osc::init(&config.osc);
+
+    let app = AppImpl::new(&config);
+
+    Ok((config, app))
+}
+
+/// Runs oscpie on the runtime in the config. `gpu` overrides the config's `overlay.gpu`.
+fn app(gpu: Option<config::types::GpuSelector>) -> Result<()> {
+    let _held_keys = key_stroke::HeldKeysGuard;
+
+    power::register_current_thread("main");
+
+    let (mut config, mut app) = load_app()?;
+
+    if gpu.is_some() {
+        config.overlay.gpu = gpu;
+    }
+
+    let control_receiver = control::listen();
+
+    let size = config.overlay.texture_size();
+    let mut pixmap =
+        Pixmap::new(size, size).ok_or_else(|| anyhow!("Invalid menu resolution {size}"))?;
+    let mut runtime = runtime::create(&config, &pixmap)?;
+
+    run_loop(
+        &config,
+        &mut app,
+        runtime.as_mut(),
+        &mut pixmap,
+        &control_receiver,
+    )
+}
+
+/// Runs `oscpie --mock`, the main loop with the menu opened and closed by a scripted runtime
+/// instead of SteamVR. Prints what was asked of the runtime.
+#[cfg(feature = "mock")]
+fn mock_app(frames: usize) -> Result<()> {
+    let (config, mut app) = load_app()?;
+
+    let size = config.overlay.texture_size();
+    let mut pixmap =
+        Pixmap::new(size, size).ok_or_else(|| anyhow!("Invalid menu resolution {size}"))?;
+    let mut runtime = openvr::mock::MockRuntime::toggle_menu(frames);
+
+    run_loop(&config, &mut app, &mut runtime, &mut pixmap, &channel().1)?;
+
+    for call in runtime.calls() {
+        println!("{call:?}");
+    }
+
+    Ok(())
+}
+
+/// Laser pointer for an overlay showing `pixmap`.
+#[allow(clippy::cast_precision_loss)]
+fn laser_pointer(pixmap: &Pixmap) -> LaserPointer {
+    LaserPointer::new(pixmap.width() as f32, pixmap.height() as f32)
+}
+
+/// Hand selector for the open gesture and hands allowed by `config`.
+fn hand_selector(config: &Config) -> Result<HandSelector> {
+    let open_gesture = InputExpression::from_config(&config.open_gesture)?;
+    Ok(HandSelector::new(config.hand, &open_gesture))
+}
+
+/// Runs frames until the app exits. `pixmap` is the one `runtime` was created with.
+fn run_loop(
+    config: &Config,
+    app: &mut AppImpl,
+    runtime: &mut dyn runtime::XrRuntime,
+    pixmap: &mut Pixmap,
+    control_receiver: &Receiver<ControlMessage>,
+) -> Result<()> {
+    let mut hand_selector = hand_selector(&app.config)?;
+    // What the hand selector was built from, to rebuild it when a reload changes it
+    let mut hand_config = (app.config.open_gesture.clone(), app.config.hand);
+    // Where the menu overlay was last placed, for head gaze selection
+    let mut menu_transform = None;
+
+    let mut overlay_stack = OverlayStack::new();
+    let mut was_menu_visible = false;
+
+    let mut laser = laser_pointer(pixmap);
+    // Applied by the runtime when it started, and again whenever a reload changes it
+    let mut overlay_config = config.overlay.clone();
+
+    let mut interval_timer = IntervalTimer::new(1000.0);
+    let mut scene_app_timer = IntervalTimer::new(1000.0);
+
+    let demo = false;
+    let mut is_asleep = false;
+
+    // std::thread::spawn(move || debug_window());
+
+    loop {
+        let timing = TimingCheck::new();
+
+        while let Ok(message) = control_receiver.try_recv() {
+            app.on_control_message(message);
+        }
+
+        for event in runtime.poll_events()? {
+            match event {
+                runtime::RuntimeEvent::Quit => app.on_system_event(openvr::SystemEvent::Quit),
+                runtime::RuntimeEvent::Overlay(event) => {
+                    if app.config.selection.mode == config::types::SelectionMode::Laser {
+                        laser.on_event(event);
+                    }
+
+                    app.on_overlay_event(event);
+                }
+            }
+        }
+
+        app.poll_config_changes();
+
+        let size = app.config.overlay.texture_size();
+
+        if size != pixmap.width() {
+            log::info!("Resizing the menu to {size}x{size}");
+
+            *pixmap =
+                Pixmap::new(size, size).ok_or_else(|| anyhow!("Invalid menu resolution {size}"))?;
+            runtime.resize(pixmap, &app.config.overlay)?;
+            laser = laser_pointer(pixmap);
+        }
+
+        if app.config.overlay != overlay_config {
+            overlay_config = app.config.overlay.clone();
+            runtime.apply_overlay_config(&overlay_config)?;
+        }
+
+        if app.config.open_gesture != hand_config.0 || app.config.hand != hand_config.1 {
+            hand_config = (app.config.open_gesture.clone(), app.config.hand);
+
+            match hand_selector(&app.config) {
+                Ok(selector) => hand_selector = selector,
+                Err(e) => log::warn!("Keeping the previous open gesture: {e}"),
+            }
+        }
+
+        if app.should_exit {
+            runtime.hide()?;
+            return Ok(());
+        }
+
+        let is_idle = !demo && runtime.is_idle();
+
+        if is_idle != is_asleep {
+            is_asleep = is_idle;
+
+            if is_asleep {
+                log::info!("Headset is idle, going to sleep");
+                runtime.hide()?;
+            } else {
+                log::info!("Headset is active again, waking up");
+            }
+        }
+
+        if is_asleep {
+            std::thread::sleep(power::SLEEP_POLL_INTERVAL);
+            continue;
+        }
+
+        if !demo && scene_app_timer.update() {
+            app.on_scene_app(runtime.scene_application_key());
+            app.on_devices(runtime.devices());
+        }
+
+        let input = if demo {
+            let time_as_seconds = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f32();
+
+            let angle = (time_as_seconds * PI * 2.0 * 0.1) % (PI * 2.0);
+            let magnitude = f32::midpoint((time_as_seconds * PI * 2.0 * 1.0).cos(), 1.0);
+
+            AppInput {
+                angle,
+                magnitude,
+                click: 0.0,
+                open_menu: false,
+                pose: None,
+                is_tracked: true,
+            }
+        } else {
+            let digital_actions = runtime.update_input()?;
+            let open_menu = hand_selector.update(&digital_actions);
+            let hand = hand_selector.active();
+            let hand_input = runtime.hand_input(hand)?;
+
+            #[allow(clippy::cast_precision_loss)]
+            let prediction_secs = app.config.pose_prediction_ms as f32 / 1000.0;
+            let predicted = hand_input.pose.map(|pose| {
+                pose_prediction::predict(
+                    pose.transform,
+                    pose.velocity,
+                    pose.angular_velocity,
+                    prediction_secs,
+                )
+            });
+
+            if let Some(transform) = runtime.place_menu(hand, predicted)? {
+                menu_transform = Some(transform);
+            }
+
+            rt_debug(|| {
+                (
+                    "20_click".to_string(),
+                    format!(
+                        "{hand:?} click: {:?}, select: {:?}",
+                        hand_input.click, hand_input.select
+                    ),
+                )
+            });
+
+            rt_debug(|| {
+                (
+                    "30_pose".to_string(),
+                    format!("{hand:?} pose: {:?}", hand_input.pose),
+                )
+            });
+
+            let mut click = if hand_input.click { 1.0 } else { 0.0 };
+
+            let (angle, magnitude) = match (app.config.selection.mode, menu_transform) {
+                (config::types::SelectionMode::HeadGaze, Some(menu_transform)) => {
+                    runtime.hmd_pose().map_or((0.0, 0.0), |hmd| {
+                        gaze::gaze_to_polar(hmd, menu_transform, app.config.overlay.width_m)
+                    })
+                }
+                (config::types::SelectionMode::Laser, _) => {
+                    click = laser.click();
+                    laser.polar()
+                }
+                _ => hit_test::stick_to_polar(hand_input.select),
+            };
+
+            AppInput {
+                angle,
+                magnitude,
+                click,
+                open_menu,
+                pose: hand_input.pose.map(|pose| PoseSample {
+                    transform: pose.transform,
+                    velocity: pose.velocity,
+                }),
+                is_tracked: hand_input.pose.is_some(),
+            }
+        };
+
+        app.on_update(input)?;
+
+        for (message, icon) in app.pending_notifications.drain(..) {
+            let icon = icon.and_then(|icon| get_sprite_sheet()?.cutout(&icon));
+
+            if let Err(e) = runtime.notify(&message, icon.as_ref()) {
+                log::warn!("Failed to show a notification: {e}");
+            }
+        }
+        let dirty = app.on_render(pixmap)?;
+
+        overlay_stack.request(OverlayLayer::Menu, app.is_open);
+        let is_menu_visible = overlay_stack.is_visible(OverlayLayer::Menu);
+
+        if is_menu_visible != was_menu_visible {
+            runtime.set_menu_open(is_menu_visible)?;
+            was_menu_visible = is_menu_visible;
+        }
+
+        runtime.submit(pixmap, dirty, is_menu_visible)?;
+
+        let time_elapsed_ns = timing.get_time_ns();
+        if interval_timer.update() {
+            rt_debug(|| {
+                (
+                    "10_FPS".to_string(),
+                    format!("whole process: {time_elapsed_ns}ns"),
+                )
+            });
+        }
+
+        runtime.wait_frame()?;
+    }
+}
+
+fn merge_snippet_file(snippet_path: &str) -> Result<()> {
+    let file = std::fs::File::open(snippet_path).map_err(|e| anyhow!(e.to_string()))?;
+    let snippet: MenuSnippet = serde_json::from_reader(file).map_err(|e| anyhow!(e.to_string()))?;
+
+    // Checked against the menus of included files too, but only the config file is written
+    config::merge_snippet(&config::load(CONFIG_PATH)?, snippet.clone())?;
+
+    config::update(CONFIG_PATH, |config| {
+        config::append_menus(&mut config.menus, snippet.menus);
+    })
+}
+
+/// Runs the command given on the command line, the overlay by default.
+fn run() {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().collect();
+
+    // `--gpu <index or name>`
+    let gpu = args
+        .iter()
+        .position(|arg| arg == "--gpu")
+        .and_then(|index| args.get(index + 1))
+        .map(|gpu| config::types::GpuSelector::parse(gpu));
+
+    match args.get(1).map(String::as_str) {
+        Some("--merge-snippet") => {
+            let Some(snippet_path) = args.get(2) else {
+                log::error!("Usage: oscpie --merge-snippet <snippet.json>");
+                std::process::exit(1);
+            };
+
+            merge_snippet_file(snippet_path).unwrap();
+        }
+        Some("--dump-schema") => {
+            let path = args.get(2).map_or("config.schema.json", String::as_str);
+
+            if let Err(e) = config::schema().and_then(|schema| Ok(std::fs::write(path, schema)?)) {
+                log::error!("Failed to write the config schema to {path}: {e}");
+                std::process::exit(1);
+            }
+
+            println!("{path}");
+        }
+        Some("validate-config") => {
+            let dry_run_actions = args.iter().any(|arg| arg == "--dry-run-actions");
+            let config_path = args
+                .iter()
+                .skip(2)
+                .find(|arg| !arg.starts_with("--"))
+                .map_or(CONFIG_PATH, String::as_str);
+
+            match validator::validate_config(config_path, dry_run_actions) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    log::error!("Failed to validate {config_path}: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some("preview") => {
+            let mut output_dir = "previews";
+            let mut config_path = CONFIG_PATH;
+
+            let mut rest = args.iter().skip(2);
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--out" => {
+                        let Some(dir) = rest.next() else {
+                            log::error!("Usage: oscpie preview [--out <dir>] [config.json]");
+                            std::process::exit(1);
+                        };
+                        output_dir = dir;
+                    }
+                    path => config_path = path,
+                }
+            }
+
+            if let Err(e) = preview::render_previews(config_path, std::path::Path::new(output_dir))
+            {
+                log::error!("Failed to render previews of {config_path}: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some("capture-frames") => {
+            let record = args.iter().any(|arg| arg == "--record");
+            let threshold_percent = args
+                .iter()
+                .position(|arg| arg == "--threshold")
+                .and_then(|index| args.get(index + 1))
+                .map_or(Ok(20.0), |threshold| threshold.parse())
+                .unwrap_or_else(|e| {
+                    log::error!("Invalid threshold: {e}");
+                    std::process::exit(1);
+                });
+            let baseline_path = args
+                .iter()
+                .position(|arg| arg == "--baseline")
+                .and_then(|index| args.get(index + 1))
+                .map_or("frame_baseline.json", String::as_str);
+
+            match frame_capture::run(
+                std::path::Path::new(baseline_path),
+                record,
+                threshold_percent,
+            ) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    log::error!("Failed to capture frames: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some("analytics-report") => {
+            let output = args
+                .iter()
+                .position(|arg| arg == "--out")
+                .and_then(|index| args.get(index + 1))
+                .map(std::path::Path::new);
+
+            let path = AppImpl::analytics_path();
+
+            if let Err(e) = analytics::report(&path, output) {
+                log::error!("Failed to report {}: {e}", path.display());
+                std::process::exit(1);
+            }
+        }
+        Some("--soak") => {
+            let Some(hours) = args
+                .get(2)
+                .and_then(|hours| hours.parse::<f64>().ok())
+                .filter(|hours| *hours > 0.0)
+            else {
+                log::error!("Usage: oscpie --soak <hours>");
+                std::process::exit(1);
+            };
+
+            match soak::run(std::time::Duration::from_secs_f64(hours * 3600.0)) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    log::error!("Soak test failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(flag @ ("--register" | "--unregister")) => {
+            if let Err(e) = registration::run(flag == "--register") {
+                log::error!("Failed to {}: {e}", &flag[2..]);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "mock")]
+        Some("--mock") => {
+            let frames = args
+                .get(2)
+                .and_then(|frames| frames.parse().ok())
+                .unwrap_or(600);
+
+            if let Err(e) = mock_app(frames) {
+                log::error!("Mock run failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some("--power-report") => {
+            power::spawn_report(std::time::Duration::from_secs(60));
+            app(gpu).unwrap();
+        }
+        _ => app(gpu).unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvr::mock::{MockCall, MockFrame, MockRuntime};
+
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_value(serde_json::json!({
+            "root": "root",
+            "sprite_sheet": "spritesheet.json",
+            "tutorial_completed": true,
+            "menus": {
+                "root": {
+                    "items": [{ "action": { "type": "Notifications" } }]
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn run_script(runtime: &mut MockRuntime) -> AppImpl {
+        run_script_with(&config(), runtime)
+    }
+
+    fn run_script_with(config: &Config, runtime: &mut MockRuntime) -> AppImpl {
+        let mut app = AppImpl::new(config);
+        let size = config.overlay.texture_size();
+        let mut pixmap = Pixmap::new(size, size).unwrap();
+
+        run_loop(config, &mut app, runtime, &mut pixmap, &channel().1).unwrap();
+
+        app
+    }
+
+    #[test]
+    fn test_open_gesture_shows_the_menu() {
+        let mut runtime = MockRuntime::toggle_menu(3);
+        let app = run_script(&mut runtime);
+
+        let calls = runtime.calls();
+        let shown = calls
+            .iter()
+            .filter(|call| **call == MockCall::Submit { visible: true })
+            .count();
+
+        assert!(shown >= 3, "{calls:?}");
+        assert_eq!(
+            calls
+                .iter()
+                .filter(|call| matches!(call, MockCall::SetMenuOpen { .. }))
+                .collect::<Vec<_>>(),
+            vec![
+                &MockCall::SetMenuOpen { is_open: true },
+                &MockCall::SetMenuOpen { is_open: false },
+            ]
+        );
+        assert_eq!(
+            calls
+                .iter()
+                .rev()
+                .find(|call| matches!(call, MockCall::Submit { .. })),
+            Some(&MockCall::Submit { visible: false })
+        );
+        assert!(app.should_exit);
+    }
+
+    #[test]
+    fn test_menu_follows_the_active_hand() {
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+        run_script(&mut runtime);
+
+        let Some(MockCall::PlaceMenu {
+            hand,
+            transform: Some(transform),
+        }) = runtime.calls().first().cloned()
+        else {
+            panic!("The menu was not placed first");
+        };
+
+        assert_eq!(hand, hand::Hand::Left);
+        assert!(transform.abs_diff_eq(Affine3A::IDENTITY, 1e-6));
+    }
+
+    #[test]
+    fn test_menu_follows_a_reloaded_hand() {
+        let config = config();
+        let mut app = AppImpl::new(&config);
+        let mut pixmap = Pixmap::new(512, 512).unwrap();
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+
+        // As a reload would have left it
+        app.config.hand = config::types::Hand::Right;
+
+        run_loop(&config, &mut app, &mut runtime, &mut pixmap, &channel().1).unwrap();
+
+        assert!(matches!(
+            runtime.calls().first(),
+            Some(MockCall::PlaceMenu {
+                hand: hand::Hand::Right,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_closes_when_the_controller_loses_tracking() {
+        let config = Config {
+            tracking_loss: config::types::TrackingLossConfig {
+                freeze_ms: 0,
+                close_ms: 0,
+            },
+            ..config()
+        };
+        let mut runtime = MockRuntime::new(
+            std::iter::once(MockFrame::tracked().pressing("OpenLeft"))
+                .chain(std::iter::repeat_with(MockFrame::default).take(3)),
+        );
+        let app = run_script_with(&config, &mut runtime);
+
+        let calls = runtime.calls();
+
+        assert!(!app.is_open);
+        assert!(
+            calls.contains(&MockCall::SetMenuOpen { is_open: false }),
+            "{calls:?}"
+        );
+        assert!(
+            calls.iter().any(|call| matches!(
+                call,
+                MockCall::Notify { message, .. } if message.contains("lost tracking")
+            )),
+            "{calls:?}"
+        );
+    }
+
+    /// Runs one frame with the menu closed, returns what was asked of the runtime until the frame
+    /// was submitted.
+    fn run_closed_frame(app: &mut AppImpl) -> Vec<MockCall> {
+        let config = config();
+        let size = config.overlay.texture_size();
+        let mut pixmap = Pixmap::new(size, size).unwrap();
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+
+        run_loop(&config, app, &mut runtime, &mut pixmap, &channel().1).unwrap();
+
+        assert!(!app.is_open);
+
+        runtime
+            .calls()
+            .into_iter()
+            .take_while(|call| !matches!(call, MockCall::Submit { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn test_notifies_toasts_while_closed() {
+        let mut app = AppImpl::new(&config());
+        app.action_context.toast(Severity::Info, "Config reloaded");
+
+        let calls = run_closed_frame(&mut app);
+
+        assert!(
+            calls.contains(&MockCall::Notify {
+                message: "Config reloaded".to_string(),
+                has_icon: false,
+            }),
+            "{calls:?}"
+        );
+    }
+
+    #[test]
+    fn test_notifies_low_battery_while_closed() {
+        let controller = |battery_level| DeviceInfo {
+            index: 1,
+            class: openvr::TrackedDeviceClass::Controller,
+            role: Some(openvr::TrackedControllerRole::LeftHand),
+            battery_level: Some(battery_level),
+            is_charging: false,
+        };
+
+        let mut app = AppImpl::new(&config());
+        app.on_devices(vec![controller(0.5)]);
+        app.on_devices(vec![controller(0.1)]);
+
+        let calls = run_closed_frame(&mut app);
+
+        assert!(
+            calls.contains(&MockCall::Notify {
+                message: "Battery low: Left controller 10%".to_string(),
+                has_icon: false,
+            }),
+            "{calls:?}"
+        );
+    }
+
+    #[test]
+    fn test_applies_a_changed_overlay_config() {
+        let config = config();
+        let mut app = AppImpl::new(&config);
+        let mut pixmap = Pixmap::new(512, 512).unwrap();
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+
+        // As a reload would have left it
+        app.config.overlay.width_m = 0.5;
+
+        run_loop(&config, &mut app, &mut runtime, &mut pixmap, &channel().1).unwrap();
+
+        assert_eq!(
+            runtime
+                .calls()
+                .iter()
+                .filter(|call| matches!(call, MockCall::ApplyOverlayConfig { .. }))
+                .collect::<Vec<_>>(),
+            vec![&MockCall::ApplyOverlayConfig { width_m: 0.5 }]
+        );
+    }
+
+    #[test]
+    fn test_resizes_to_the_configured_resolution() {
+        let config = config();
+        let mut app = AppImpl::new(&config);
+        let mut pixmap = Pixmap::new(256, 256).unwrap();
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+
+        run_loop(&config, &mut app, &mut runtime, &mut pixmap, &channel().1).unwrap();
+
+        assert_eq!(pixmap.width(), config.overlay.texture_size());
+        assert_eq!(
+            runtime
+                .calls()
+                .iter()
+                .filter(|call| matches!(call, MockCall::Resize { .. }))
+                .collect::<Vec<_>>(),
+            vec![&MockCall::Resize { size: 512 }]
+        );
+    }
+}
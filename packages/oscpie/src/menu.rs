@@ -1,28 +1,134 @@
-use std::{cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc, sync::mpsc::Sender};
+use std::{
+    cell::RefCell, collections::HashMap, fmt::Debug, rc::Rc, sync::mpsc::Sender, time::Duration,
+};
+
+use anyhow::Result;
 
 use crate::{
-    action_behaviours::{exec::ExecOneShotButtonAction, key_stroke::KeyStrokeButtonAction},
+    action_behaviours::{
+        custom, exec::ExecOneShotButtonAction, key_stroke::KeyStrokeButtonAction,
+        system_volume::SystemVolumeSliderAction, toggle::ToggleButtonAction,
+    },
     config,
+    layout::Cardinal,
+    notifications::NOTIFICATIONS_MENU_ID,
 };
 
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     PopStack,
     PushStack(MenuId),
+    Toast {
+        severity: Severity,
+        message: String,
+        /// Sprite shown next to the message in SteamVR's notification.
+        icon: Option<String>,
+    },
+    Haptics,
+    /// An item's action has run. `keep_open` is the item's override of [`AfterAction`].
+    ActionFired {
+        action: String,
+        keep_open: Option<bool>,
+    },
+    StartTutorial,
+    /// Makes the named entry of the config's `profiles` the active one.
+    SwitchProfile(String),
+}
+
+/// What the menu does after an item's action has run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfterAction {
+    StayOpen,
+    Close,
+    ReturnToRoot,
+}
+
+impl AfterAction {
+    /// Resolves the behaviour for an item, where `keep_open` overrides the default.
+    pub fn resolve(self, keep_open: Option<bool>) -> AfterAction {
+        match keep_open {
+            Some(true) => AfterAction::StayOpen,
+            Some(false) => AfterAction::Close,
+            None => self,
+        }
+    }
+}
+
+impl From<config::types::AfterAction> for AfterAction {
+    fn from(after_action: config::types::AfterAction) -> Self {
+        match after_action {
+            config::types::AfterAction::StayOpen => AfterAction::StayOpen,
+            config::types::AfterAction::Close => AfterAction::Close,
+            config::types::AfterAction::ReturnToRoot => AfterAction::ReturnToRoot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Error,
+}
+
+/// Outcome of a single action execution, shown as an item badge and recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionResult {
+    Success,
+    Failure(String),
+}
+
+/// Handed to behaviours when they run so that they can talk back to the app.
+#[derive(Debug, Clone)]
+pub struct ActionContext {
+    event_sender: Sender<AppEvent>,
+}
+
+impl ActionContext {
+    pub(crate) fn new(event_sender: Sender<AppEvent>) -> Self {
+        Self { event_sender }
+    }
+
+    pub(crate) fn emit(&mut self, event: AppEvent) {
+        if let Err(e) = self.event_sender.send(event) {
+            log::warn!("Failed to emit app event: {e}");
+        }
+    }
+
+    pub fn toast(&mut self, severity: Severity, message: impl Into<String>) {
+        self.emit(AppEvent::Toast {
+            severity,
+            message: message.into(),
+            icon: None,
+        });
+    }
+
+    /// Like [`ActionContext::toast`], with a sprite from the sprite sheet as the icon.
+    pub fn toast_with_icon(
+        &mut self,
+        severity: Severity,
+        message: impl Into<String>,
+        icon: impl Into<String>,
+    ) {
+        self.emit(AppEvent::Toast {
+            severity,
+            message: message.into(),
+            icon: Some(icon.into()),
+        });
+    }
+
+    pub fn haptics(&mut self) {
+        self.emit(AppEvent::Haptics);
+    }
 }
 
 #[derive(Debug)]
 pub struct AppEventMenuActionBehaviour {
-    event_sender: Sender<AppEvent>,
     event: AppEvent,
 }
 
 impl AppEventMenuActionBehaviour {
-    pub fn new(event_sender: Sender<AppEvent>, event: AppEvent) -> Self {
-        Self {
-            event_sender,
-            event,
-        }
+    pub fn new(event: AppEvent) -> Self {
+        Self { event }
     }
 }
 
@@ -31,14 +137,52 @@ impl MenuActionBehaviour<bool> for AppEventMenuActionBehaviour {
         false
     }
 
-    fn on_change(&mut self, _value: bool) {
-        self.event_sender.send(self.event.clone());
+    fn on_change(&mut self, _value: bool, ctx: &mut ActionContext) -> ActionResult {
+        ctx.emit(self.event.clone());
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        match &self.event {
+            AppEvent::PopStack => "Back".to_string(),
+            AppEvent::PushStack(to) => format!("Open menu '{}'", to.0),
+            AppEvent::Toast { message, .. } => format!("Toast '{message}'"),
+            AppEvent::Haptics => "Haptics".to_string(),
+            AppEvent::ActionFired { .. } => "Action fired".to_string(),
+            AppEvent::StartTutorial => "Start tutorial".to_string(),
+            AppEvent::SwitchProfile(profile) => format!("Switch to profile '{profile}'"),
+        }
+    }
+
+    fn is_navigation(&self) -> bool {
+        true
     }
 }
 
 pub trait MenuActionBehaviour<T>: Debug {
     fn value(&self) -> T;
-    fn on_change(&mut self, value: T);
+    fn on_change(&mut self, value: T, ctx: &mut ActionContext) -> ActionResult;
+
+    /// Short human readable summary of what the action does.
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Checks whether the action is able to run, without causing any side effects.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the action would fail.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether the action moves around the menus itself, in which case the menu is left as is
+    /// after it runs.
+    fn is_navigation(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -48,6 +192,10 @@ impl MenuId {
     pub fn new(id: String) -> Self {
         MenuId(id)
     }
+
+    pub fn inner(&self) -> &str {
+        &self.0
+    }
 }
 
 impl MenuId {
@@ -56,25 +204,28 @@ impl MenuId {
     }
 }
 
+/// How long `Hold` actions are held for when the config does not say.
+const DEFAULT_HOLD_MS: u64 = 800;
+
 #[derive(Debug, Clone)]
 pub enum MenuItemAction {
     Noop,
     OneShotButton(Rc<RefCell<dyn MenuActionBehaviour<bool>>>),
     Button(Rc<RefCell<dyn MenuActionBehaviour<bool>>>),
+    /// Runs once the item has been pressed for the duration.
+    HoldButton(Rc<RefCell<dyn MenuActionBehaviour<bool>>>, Duration),
+    /// Adjusted by turning the stick while the item is held.
+    Slider(Rc<RefCell<dyn MenuActionBehaviour<f32>>>),
 }
 
 impl MenuItemAction {
-    pub fn from_config(
-        action: &config::types::MenuItemAction,
-        event_sender: Sender<AppEvent>,
-    ) -> MenuItemAction {
+    pub fn from_config(action: &config::types::MenuItemAction) -> MenuItemAction {
         match action {
-            config::types::MenuItemAction::SubMenu { to } => MenuItemAction::OneShotButton(
-                Rc::new(RefCell::new(AppEventMenuActionBehaviour::new(
-                    event_sender,
-                    AppEvent::PushStack(MenuId::from_config(to)),
-                ))),
-            ),
+            config::types::MenuItemAction::SubMenu { to } => {
+                MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+                    AppEventMenuActionBehaviour::new(AppEvent::PushStack(MenuId::from_config(to))),
+                )))
+            }
             config::types::MenuItemAction::KeyStroke { key_stroke } => {
                 MenuItemAction::OneShotButton(Rc::new(RefCell::new(KeyStrokeButtonAction::new(
                     key_stroke.clone().into(),
@@ -86,6 +237,74 @@ impl MenuItemAction {
                     args.clone(),
                 ))))
             }
+            config::types::MenuItemAction::StartTutorial => MenuItemAction::OneShotButton(Rc::new(
+                RefCell::new(AppEventMenuActionBehaviour::new(AppEvent::StartTutorial)),
+            )),
+            config::types::MenuItemAction::Notifications => MenuItemAction::OneShotButton(Rc::new(
+                RefCell::new(AppEventMenuActionBehaviour::new(AppEvent::PushStack(
+                    MenuId::new(NOTIFICATIONS_MENU_ID.to_string()),
+                ))),
+            )),
+            config::types::MenuItemAction::Toggle { on, off, persist } => {
+                let (Some(on), Some(off)) = (
+                    MenuItemAction::from_config(on).behaviour(),
+                    MenuItemAction::from_config(off).behaviour(),
+                ) else {
+                    log::warn!("Toggle actions need an action to run for both on and off");
+                    return MenuItemAction::Noop;
+                };
+
+                MenuItemAction::OneShotButton(Rc::new(RefCell::new(ToggleButtonAction::new(
+                    on,
+                    off,
+                    persist.clone(),
+                ))))
+            }
+            config::types::MenuItemAction::Library { item } => {
+                log::warn!("Library item '{item}' was not resolved");
+                MenuItemAction::Noop
+            }
+            config::types::MenuItemAction::SwitchProfile { profile } => {
+                MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+                    AppEventMenuActionBehaviour::new(AppEvent::SwitchProfile(profile.clone())),
+                )))
+            }
+            config::types::MenuItemAction::Hold { action, hold_ms } => {
+                let Some(behaviour) = MenuItemAction::from_config(action).behaviour() else {
+                    log::warn!("Hold actions need an action to run");
+                    return MenuItemAction::Noop;
+                };
+
+                MenuItemAction::HoldButton(
+                    behaviour,
+                    Duration::from_millis(hold_ms.unwrap_or(DEFAULT_HOLD_MS)),
+                )
+            }
+            config::types::MenuItemAction::Slider { target } => Self::slider_from_config(target),
+            config::types::MenuItemAction::Custom(action) => {
+                MenuItemAction::OneShotButton(custom::create(action))
+            }
+        }
+    }
+
+    fn slider_from_config(target: &config::types::SliderTarget) -> MenuItemAction {
+        match target {
+            config::types::SliderTarget::OscFloat { parameter } => {
+                custom::create_slider("OscFloat", serde_json::json!({ "parameter": parameter }))
+                    .map_or(MenuItemAction::Noop, MenuItemAction::Slider)
+            }
+            config::types::SliderTarget::SystemVolume => {
+                MenuItemAction::Slider(Rc::new(RefCell::new(SystemVolumeSliderAction::new())))
+            }
+        }
+    }
+
+    pub fn behaviour(&self) -> Option<Rc<RefCell<dyn MenuActionBehaviour<bool>>>> {
+        match self {
+            MenuItemAction::Noop | MenuItemAction::Slider(_) => None,
+            MenuItemAction::OneShotButton(behaviour)
+            | MenuItemAction::Button(behaviour)
+            | MenuItemAction::HoldButton(behaviour, _) => Some(behaviour.clone()),
         }
     }
 }
@@ -94,20 +313,37 @@ impl MenuItemAction {
 pub struct MenuItem {
     action: MenuItemAction,
     icon: Option<String>,
+    label: Option<String>,
+    anchor: Option<Cardinal>,
+    keep_open: Option<bool>,
 }
 
 impl MenuItem {
     pub fn new(action: MenuItemAction, icon: Option<String>) -> Self {
-        MenuItem { action, icon }
+        MenuItem {
+            action,
+            icon,
+            label: None,
+            anchor: None,
+            keep_open: None,
+        }
     }
 
-    pub fn from_config(item: &config::types::MenuItem, event_sender: Sender<AppEvent>) -> Self {
+    pub fn from_config(item: &config::types::MenuItem) -> Self {
         MenuItem {
-            action: MenuItemAction::from_config(&item.action, event_sender),
+            action: MenuItemAction::from_config(&item.action),
             icon: item.icon.clone(),
+            label: item.label.clone(),
+            anchor: item.anchor.map(Cardinal::from),
+            keep_open: item.keep_open,
         }
     }
 
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     pub fn action(&self) -> &MenuItemAction {
         &self.action
     }
@@ -115,25 +351,79 @@ impl MenuItem {
     pub fn icon(&self) -> Option<&String> {
         self.icon.as_ref()
     }
+
+    pub fn label(&self) -> Option<&String> {
+        self.label.as_ref()
+    }
+
+    pub fn anchor(&self) -> Option<Cardinal> {
+        self.anchor
+    }
+
+    pub fn keep_open(&self) -> Option<bool> {
+        self.keep_open
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Menu {
     pub items: Vec<MenuItem>,
+    /// Rotation in radians, clockwise.
+    pub rotation: f32,
+    pub layout: MenuLayout,
+    /// Pie menus with more items are split into pages of this many.
+    pub page_size: Option<usize>,
 }
 
 impl Menu {
     pub fn new(items: Vec<MenuItem>) -> Self {
-        Menu { items }
+        Menu {
+            items,
+            rotation: 0.0,
+            layout: MenuLayout::Pie,
+            page_size: None,
+        }
     }
 
-    pub fn from_config(menu: &config::types::Menu, event_sender: Sender<AppEvent>) -> Self {
+    pub fn from_config(menu: &config::types::Menu) -> Self {
         Menu {
-            items: menu
-                .items
-                .iter()
-                .map(|item| MenuItem::from_config(item, event_sender.clone()))
-                .collect(),
+            items: menu.items.iter().map(MenuItem::from_config).collect(),
+            rotation: menu.rotation.to_radians(),
+            layout: menu.layout.into(),
+            page_size: menu.page_size,
+        }
+    }
+
+    /// Returns a copy with a back item in front, as shown when the menu is not the root.
+    pub fn with_back_item(&self) -> Menu {
+        let back_action = MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+            AppEventMenuActionBehaviour::new(AppEvent::PopStack),
+        )));
+
+        let mut menu = self.clone();
+        menu.items
+            .insert(0, MenuItem::new(back_action, Some("back".to_string())));
+        menu
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuLayout {
+    Pie,
+    QuickBar,
+    HalfCircle(Cardinal),
+    Grid,
+}
+
+impl From<config::types::MenuLayout> for MenuLayout {
+    fn from(layout: config::types::MenuLayout) -> Self {
+        match layout {
+            config::types::MenuLayout::Pie => MenuLayout::Pie,
+            config::types::MenuLayout::QuickBar => MenuLayout::QuickBar,
+            config::types::MenuLayout::HalfCircle { towards } => {
+                MenuLayout::HalfCircle(towards.into())
+            }
+            config::types::MenuLayout::Grid => MenuLayout::Grid,
         }
     }
 }
@@ -142,6 +432,7 @@ pub struct MenuSetup {
     pub menus: HashMap<MenuId, Menu>,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct PieMenuInput {
     pub angle: f32,
     pub magnitude: f32,
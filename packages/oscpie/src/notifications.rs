@@ -0,0 +1,142 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use crate::prelude::*;
+
+/// Id of the built-in menu listing past notifications.
+pub const NOTIFICATIONS_MENU_ID: &str = "notifications";
+
+/// Notifications are shown in the menu with their message cut to this many characters.
+const LABEL_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Keeps the latest toasts so that they can be reviewed after they are gone.
+pub struct NotificationHistory {
+    notifications: VecDeque<Notification>,
+    capacity: usize,
+}
+
+impl NotificationHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            notifications: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, severity: Severity, message: String) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.notifications.len() == self.capacity {
+            self.notifications.pop_front();
+        }
+
+        self.notifications
+            .push_back(Notification { severity, message });
+    }
+
+    /// Builds the notifications menu, newest first.
+    pub fn menu(&self) -> Menu {
+        Menu::new(
+            self.notifications
+                .iter()
+                .rev()
+                .map(|notification| {
+                    MenuItem::new(
+                        MenuItemAction::OneShotButton(Rc::new(RefCell::new(
+                            NotificationBehaviour::new(notification.clone()),
+                        ))),
+                        Some(severity_icon(notification.severity).to_string()),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+fn severity_icon(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "notification_info",
+        Severity::Error => "notification_error",
+    }
+}
+
+/// Item of the notifications menu. Selecting it logs the full message.
+#[derive(Debug)]
+struct NotificationBehaviour {
+    notification: Notification,
+}
+
+impl NotificationBehaviour {
+    fn new(notification: Notification) -> Self {
+        Self { notification }
+    }
+}
+
+impl MenuActionBehaviour<bool> for NotificationBehaviour {
+    fn value(&self) -> bool {
+        false
+    }
+
+    fn on_change(&mut self, _value: bool, _ctx: &mut ActionContext) -> ActionResult {
+        log::info!(
+            "Notification ({:?}): {}",
+            self.notification.severity,
+            self.notification.message
+        );
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        let message = &self.notification.message;
+
+        match message.char_indices().nth(LABEL_LENGTH) {
+            Some((end, _)) => format!("{}…", &message[..end]),
+            None => message.clone(),
+        }
+    }
+
+    fn is_navigation(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_latest() {
+        let mut history = NotificationHistory::new(2);
+
+        history.push(Severity::Info, "first".to_string());
+        history.push(Severity::Error, "second".to_string());
+        history.push(Severity::Info, "third".to_string());
+
+        let menu = history.menu();
+
+        assert_eq!(menu.items.len(), 2);
+        assert_eq!(menu.items[0].icon().unwrap(), "notification_info");
+        assert_eq!(menu.items[1].icon().unwrap(), "notification_error");
+    }
+
+    #[test]
+    fn test_label_is_truncated() {
+        let behaviour = NotificationBehaviour::new(Notification {
+            severity: Severity::Error,
+            message: "x".repeat(LABEL_LENGTH + 10),
+        });
+
+        assert_eq!(
+            behaviour.describe(),
+            format!("{}…", "x".repeat(LABEL_LENGTH))
+        );
+    }
+}
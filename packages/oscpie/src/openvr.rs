@@ -1,4 +1,8 @@
+// Covers every action type of SteamVR action manifests, not only the ones oscpie binds
+#[allow(dead_code)]
 pub mod input;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 
 use crate::prelude::*;
 use anyhow::{anyhow, Result};
@@ -7,7 +11,7 @@ use log::{debug, trace};
 use openvr_sys::{self as sys, VkDevice_T, VkInstance_T, VkPhysicalDevice_T, VkQueue_T};
 use std::{
     ffi::{c_void, CStr},
-    path::PathBuf,
+    path::Path,
     rc::Rc,
 };
 use vulkano::{
@@ -18,6 +22,7 @@ use vulkano::{
 
 #[derive(Debug, Clone, Copy)]
 pub enum TextureType {
+    DirectX = 0,
     Vulkan = 2,
 }
 
@@ -35,9 +40,59 @@ pub enum TrackingUniverseOrigin {
     RawAndUncalibrated = 2,
 }
 
+/// Index of the headset among the tracked devices, which is always the first one.
+pub const HMD_DEVICE_INDEX: u32 = sys::k_unTrackedDeviceIndex_Hmd;
+
+pub const MAX_TRACKED_DEVICES: u32 = sys::k_unMaxTrackedDeviceCount;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackedControllerRole {
+    LeftHand = 1,
+    RightHand = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackedDeviceClass {
+    Hmd,
+    Controller,
+    GenericTracker,
+    /// Base stations and cameras.
+    TrackingReference,
+    Other,
+}
+
+/// Event sent to an overlay which takes mouse input from the laser pointer. Positions are in
+/// pixels of the mouse scale, from the bottom left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverlayEvent {
+    MouseMove {
+        x: f32,
+        y: f32,
+    },
+    MouseButtonDown,
+    MouseButtonUp,
+    /// The laser pointer entered the overlay.
+    FocusEnter,
+    /// The laser pointer left the overlay.
+    FocusLeave,
+    Shown,
+    Hidden,
+    Other,
+}
+
+/// Event sent to the whole application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemEvent {
+    /// SteamVR is shutting down, the application has to exit.
+    Quit,
+    Other,
+}
+
 #[derive(Debug)]
 pub enum TextureHandle<'a> {
     Vulkan(&'a Image, &'a Queue),
+    /// An `ID3D11Texture2D` created as a shared resource.
+    DirectX(*mut c_void),
     OpenGL(&'a mut c_void),
 }
 
@@ -141,6 +196,22 @@ impl Handle<OpenVr> {
         })))
     }
 
+    pub fn applications(&self) -> Result<Handle<ApplicationsInterface>> {
+        let sys = get_interface::<sys::VR_IVRApplications_FnTable>(sys::IVRApplications_Version)?;
+
+        Ok(Handle(Rc::new(ApplicationsInterface {
+            sys: unsafe { CastRc::new(self.0.clone(), sys) },
+        })))
+    }
+
+    pub fn notifications(&self) -> Result<Handle<NotificationsInterface>> {
+        let sys = get_interface::<sys::VR_IVRNotifications_FnTable>(sys::IVRNotifications_Version)?;
+
+        Ok(Handle(Rc::new(NotificationsInterface {
+            sys: unsafe { CastRc::new(self.0.clone(), sys) },
+        })))
+    }
+
     pub fn compositor(&self) -> Result<Handle<CompositorInterface>> {
         let sys = get_interface::<sys::VR_IVRCompositor_FnTable>(sys::IVRCompositor_Version)?;
 
@@ -149,32 +220,36 @@ impl Handle<OpenVr> {
         })))
     }
 
-    pub fn input(&self, action_manifest_path: Option<PathBuf>) -> Result<input::generated::Input> {
+    /// Loads the action manifest at `action_manifest_path`, whose actions are then read by name.
+    pub fn input(&self, action_manifest_path: &Path) -> Result<input::Input> {
         let sys = get_interface::<sys::VR_IVRInput_FnTable>(sys::IVRInput_Version)?;
 
-        if let Some(path) = &action_manifest_path {
-            log::info!("Setting action manifest path: {}", path.display());
-
-            let path = path.canonicalize().unwrap();
-
-            // TODO: Multibyte string conversion?
-            let error = unsafe {
-                sys.SetActionManifestPath.unwrap()(
-                    std::ffi::CString::new(path.to_string_lossy().as_bytes())?
-                        .as_ptr()
-                        .cast_mut(),
-                )
-            };
-
-            if error != sys::EVRInputError_VRInputError_None {
-                return Err(anyhow::anyhow!(
-                    "Failed to set action manifest path: {}",
-                    error
-                ));
-            }
+        log::info!(
+            "Setting action manifest path: {}",
+            action_manifest_path.display()
+        );
+
+        let manifest =
+            input::ActionManifest::parse(&std::fs::read_to_string(action_manifest_path)?)?;
+        let path = action_manifest_path.canonicalize()?;
+
+        // TODO: Multibyte string conversion?
+        let error = unsafe {
+            sys.SetActionManifestPath.unwrap()(
+                std::ffi::CString::new(path.to_string_lossy().as_bytes())?
+                    .as_ptr()
+                    .cast_mut(),
+            )
+        };
+
+        if error != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set action manifest path: {}",
+                error
+            ));
         }
 
-        let input = input::generated::Input::new(unsafe { CastRc::new(self.0.clone(), sys) })?;
+        let input = input::Input::new(unsafe { CastRc::new(self.0.clone(), sys) }, &manifest)?;
 
         Ok(input)
     }
@@ -189,13 +264,432 @@ impl Drop for OpenVr {
     }
 }
 
+/// How recently a tracked device has been used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceActivityLevel {
+    Unknown,
+    /// No activity for the last 10 seconds.
+    Idle,
+    UserInteraction,
+    /// No activity for the last 0.5 seconds.
+    UserInteractionTimeout,
+    /// Idle for at least 5 seconds after being in use.
+    Standby,
+    IdleTimeout,
+}
+
 pub struct SystemInterface {
     sys: CastRc<sys::VR_IVRSystem_FnTable>,
 }
 
-impl SystemInterface {}
+impl Handle<SystemInterface> {
+    pub fn hmd_activity_level(&self) -> DeviceActivityLevel {
+        let level = unsafe {
+            self.0.sys.get().GetTrackedDeviceActivityLevel.unwrap()(sys::k_unTrackedDeviceIndex_Hmd)
+        };
+
+        match level {
+            sys::EDeviceActivityLevel_k_EDeviceActivityLevel_Idle => DeviceActivityLevel::Idle,
+            sys::EDeviceActivityLevel_k_EDeviceActivityLevel_UserInteraction => {
+                DeviceActivityLevel::UserInteraction
+            }
+            sys::EDeviceActivityLevel_k_EDeviceActivityLevel_UserInteraction_Timeout => {
+                DeviceActivityLevel::UserInteractionTimeout
+            }
+            sys::EDeviceActivityLevel_k_EDeviceActivityLevel_Standby => {
+                DeviceActivityLevel::Standby
+            }
+            sys::EDeviceActivityLevel_k_EDeviceActivityLevel_Idle_Timeout => {
+                DeviceActivityLevel::IdleTimeout
+            }
+            _ => DeviceActivityLevel::Unknown,
+        }
+    }
+
+    /// Index of the DXGI adapter SteamVR renders on, for creating Direct3D devices on the same
+    /// GPU. `None` when SteamVR does not say.
+    pub fn dxgi_adapter_index(&self) -> Option<u32> {
+        let mut adapter_index = -1;
+
+        unsafe { self.0.sys.get().GetDXGIOutputInfo.unwrap()(&mut adapter_index) };
+
+        u32::try_from(adapter_index).ok()
+    }
+
+    /// LUID of the GPU the headset is connected to, for finding the same GPU through other APIs.
+    /// `None` when SteamVR does not say.
+    pub fn output_device_luid(&self) -> Option<u64> {
+        let mut luid = 0;
+
+        unsafe {
+            self.0.sys.get().GetOutputDevice.unwrap()(
+                &mut luid,
+                sys::ETextureType_TextureType_DirectX,
+                std::ptr::null_mut(),
+            );
+        };
+
+        (luid != 0).then_some(luid)
+    }
+
+    /// Current pose of the headset, or `None` while it is not tracked.
+    pub fn hmd_pose(&self, tracking_universe_origin: TrackingUniverseOrigin) -> Option<Affine3A> {
+        self.device_pose(HMD_DEVICE_INDEX, tracking_universe_origin)
+    }
+
+    /// Current pose of a tracked device, or `None` while it is not tracked.
+    pub fn device_pose(
+        &self,
+        device_index: u32,
+        tracking_universe_origin: TrackingUniverseOrigin,
+    ) -> Option<Affine3A> {
+        let empty_pose = sys::TrackedDevicePose_t {
+            mDeviceToAbsoluteTracking: sys::HmdMatrix34_t {
+                m: [
+                    [0.0, 0.0, 0.0, 0.0],
+                    [0.0, 0.0, 0.0, 0.0],
+                    [0.0, 0.0, 0.0, 0.0],
+                ],
+            },
+            vVelocity: sys::HmdVector3_t { v: [0.0, 0.0, 0.0] },
+            vAngularVelocity: sys::HmdVector3_t { v: [0.0, 0.0, 0.0] },
+            eTrackingResult: 0,
+            bPoseIsValid: false,
+            bDeviceIsConnected: false,
+        };
+
+        // Poses are written for every device up to the requested one
+        let mut poses = vec![empty_pose; device_index as usize + 1];
+
+        unsafe {
+            self.0.sys.get().GetDeviceToAbsoluteTrackingPose.unwrap()(
+                tracking_universe_origin as sys::ETrackingUniverseOrigin,
+                0.0,
+                poses.as_mut_ptr(),
+                device_index + 1,
+            );
+        }
+
+        let pose = poses[device_index as usize];
+
+        pose.bPoseIsValid
+            .then(|| from_hmd_matrix34_t(pose.mDeviceToAbsoluteTracking))
+    }
+
+    /// Next pending event of the application, or `None` once there are no more.
+    pub fn poll_next_event(&self) -> Option<SystemEvent> {
+        let mut event: sys::VREvent_t = unsafe { std::mem::zeroed() };
+
+        let has_event = unsafe {
+            self.0.sys.get().PollNextEvent.unwrap()(
+                &mut event,
+                u32::try_from(std::mem::size_of::<sys::VREvent_t>()).ok()?,
+            )
+        };
+
+        if !has_event {
+            return None;
+        }
+
+        Some(match event.eventType as sys::EVREventType {
+            sys::EVREventType_VREvent_Quit => SystemEvent::Quit,
+            _ => SystemEvent::Other,
+        })
+    }
+
+    /// Tells SteamVR that the application is exiting after a [`SystemEvent::Quit`], so that it
+    /// does not wait for it and kill it.
+    pub fn acknowledge_quit_exiting(&self) {
+        unsafe {
+            self.0.sys.get().AcknowledgeQuit_Exiting.unwrap()();
+        }
+    }
+
+    /// Class of the device at `device_index`, or `None` when there is no device there.
+    pub fn device_class(&self, device_index: u32) -> Option<TrackedDeviceClass> {
+        let class = unsafe { self.0.sys.get().GetTrackedDeviceClass.unwrap()(device_index) };
+
+        match class {
+            sys::ETrackedDeviceClass_TrackedDeviceClass_Invalid => None,
+            sys::ETrackedDeviceClass_TrackedDeviceClass_HMD => Some(TrackedDeviceClass::Hmd),
+            sys::ETrackedDeviceClass_TrackedDeviceClass_Controller => {
+                Some(TrackedDeviceClass::Controller)
+            }
+            sys::ETrackedDeviceClass_TrackedDeviceClass_GenericTracker => {
+                Some(TrackedDeviceClass::GenericTracker)
+            }
+            sys::ETrackedDeviceClass_TrackedDeviceClass_TrackingReference => {
+                Some(TrackedDeviceClass::TrackingReference)
+            }
+            _ => Some(TrackedDeviceClass::Other),
+        }
+    }
+
+    /// Hand the controller at `device_index` is held in, `None` for other devices.
+    pub fn controller_role(&self, device_index: u32) -> Option<TrackedControllerRole> {
+        let role = unsafe {
+            self.0
+                .sys
+                .get()
+                .GetControllerRoleForTrackedDeviceIndex
+                .unwrap()(device_index)
+        };
+
+        match role {
+            sys::ETrackedControllerRole_TrackedControllerRole_LeftHand => {
+                Some(TrackedControllerRole::LeftHand)
+            }
+            sys::ETrackedControllerRole_TrackedControllerRole_RightHand => {
+                Some(TrackedControllerRole::RightHand)
+            }
+            _ => None,
+        }
+    }
+
+    fn float_property(
+        &self,
+        device_index: u32,
+        property: sys::ETrackedDeviceProperty,
+    ) -> Option<f32> {
+        let mut error = sys::ETrackedPropertyError_TrackedProp_Success;
+
+        let value = unsafe {
+            self.0.sys.get().GetFloatTrackedDeviceProperty.unwrap()(
+                device_index,
+                property,
+                &mut error,
+            )
+        };
+
+        (error == sys::ETrackedPropertyError_TrackedProp_Success).then_some(value)
+    }
+
+    fn bool_property(
+        &self,
+        device_index: u32,
+        property: sys::ETrackedDeviceProperty,
+    ) -> Option<bool> {
+        let mut error = sys::ETrackedPropertyError_TrackedProp_Success;
+
+        let value = unsafe {
+            self.0.sys.get().GetBoolTrackedDeviceProperty.unwrap()(
+                device_index,
+                property,
+                &mut error,
+            )
+        };
+
+        (error == sys::ETrackedPropertyError_TrackedProp_Success).then_some(value)
+    }
+
+    /// Charge of the device's battery from `0.0` to `1.0`, `None` for devices without one.
+    pub fn battery_level(&self, device_index: u32) -> Option<f32> {
+        let has_battery = self.bool_property(
+            device_index,
+            sys::ETrackedDeviceProperty_Prop_DeviceProvidesBatteryStatus_Bool,
+        )?;
+
+        if !has_battery {
+            return None;
+        }
+
+        self.float_property(
+            device_index,
+            sys::ETrackedDeviceProperty_Prop_DeviceBatteryPercentage_Float,
+        )
+    }
+
+    pub fn is_charging(&self, device_index: u32) -> bool {
+        self.bool_property(
+            device_index,
+            sys::ETrackedDeviceProperty_Prop_DeviceIsCharging_Bool,
+        )
+        .unwrap_or(false)
+    }
+
+    /// Index of the controller held in the hand of `role`, or `None` while there is none.
+    pub fn controller_index(&self, role: TrackedControllerRole) -> Option<u32> {
+        let index = unsafe {
+            self.0
+                .sys
+                .get()
+                .GetTrackedDeviceIndexForControllerRole
+                .unwrap()(role as sys::ETrackedControllerRole)
+        };
+
+        (index != sys::k_unTrackedDeviceIndexInvalid).then_some(index)
+    }
+}
+
+pub struct ApplicationsInterface {
+    sys: CastRc<sys::VR_IVRApplications_FnTable>,
+}
+
+impl Handle<ApplicationsInterface> {
+    /// Application key of the app rendering the scene, such as `steam.app.438100`. `None` while
+    /// no scene app runs, or when it is not a registered application.
+    pub fn scene_application_key(&self) -> Option<String> {
+        let process_id = unsafe { self.0.sys.get().GetCurrentSceneProcessId.unwrap()() };
+
+        if process_id == 0 {
+            return None;
+        }
+
+        let mut app_key = [0u8; sys::k_unMaxApplicationKeyLength as usize];
+
+        let error = unsafe {
+            self.0.sys.get().GetApplicationKeyByProcessId.unwrap()(
+                process_id,
+                app_key.as_mut_ptr().cast::<i8>(),
+                sys::k_unMaxApplicationKeyLength,
+            )
+        };
+
+        if error != sys::EVRApplicationError_VRApplicationError_None {
+            return None;
+        }
+
+        Some(
+            CStr::from_bytes_until_nul(&app_key)
+                .ok()?
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+
+    /// Registers the applications of a `.vrmanifest` file. Temporary manifests are forgotten
+    /// when SteamVR exits.
+    pub fn add_application_manifest(&self, path: &std::path::Path, temporary: bool) -> Result<()> {
+        let path = std::ffi::CString::new(path.to_string_lossy().as_bytes())?;
+
+        let error = unsafe {
+            self.0.sys.get().AddApplicationManifest.unwrap()(path.as_ptr().cast_mut(), temporary)
+        };
+
+        if error != sys::EVRApplicationError_VRApplicationError_None {
+            return Err(anyhow!("Failed to add application manifest: {}", error));
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_application_manifest(&self, path: &std::path::Path) -> Result<()> {
+        let path = std::ffi::CString::new(path.to_string_lossy().as_bytes())?;
+
+        let error = unsafe {
+            self.0.sys.get().RemoveApplicationManifest.unwrap()(path.as_ptr().cast_mut())
+        };
+
+        if error != sys::EVRApplicationError_VRApplicationError_None {
+            return Err(anyhow!("Failed to remove application manifest: {}", error));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_application_installed(&self, app_key: &str) -> Result<bool> {
+        let app_key = std::ffi::CString::new(app_key)?;
+
+        Ok(
+            unsafe {
+                self.0.sys.get().IsApplicationInstalled.unwrap()(app_key.as_ptr().cast_mut())
+            },
+        )
+    }
+
+    /// Launches the application along with SteamVR.
+    pub fn set_auto_start(&self, app_key: &str, auto_start: bool) -> Result<()> {
+        let app_key = std::ffi::CString::new(app_key)?;
+
+        let error = unsafe {
+            self.0.sys.get().SetApplicationAutoLaunch.unwrap()(
+                app_key.as_ptr().cast_mut(),
+                auto_start,
+            )
+        };
+
+        if error != sys::EVRApplicationError_VRApplicationError_None {
+            return Err(anyhow!("Failed to set application auto launch: {}", error));
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone)]
+/// Image of a notification, as straight alpha RGBA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationBitmap {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl NotificationBitmap {
+    pub fn from_pixmap(pixmap: &tiny_skia::Pixmap) -> Self {
+        let data = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        Self {
+            data,
+            width: pixmap.width(),
+            height: pixmap.height(),
+        }
+    }
+}
+
+pub struct NotificationsInterface {
+    sys: CastRc<sys::VR_IVRNotifications_FnTable>,
+}
+
+impl Handle<NotificationsInterface> {
+    /// Shows a toast in SteamVR's notification area on behalf of `overlay`, which goes away on its
+    /// own.
+    pub fn create_notification(
+        &self,
+        overlay: &Overlay,
+        text: &str,
+        icon: Option<&NotificationBitmap>,
+    ) -> Result<()> {
+        let text = std::ffi::CString::new(text)?;
+
+        let mut image = icon.map(|icon| sys::NotificationBitmap_t {
+            m_pImageData: icon.data.as_ptr().cast_mut().cast::<c_void>(),
+            m_nWidth: i32::try_from(icon.width).unwrap_or(0),
+            m_nHeight: i32::try_from(icon.height).unwrap_or(0),
+            m_nBytesPerPixel: 4,
+        });
+
+        let mut notification_id = 0;
+
+        let error = unsafe {
+            self.0.sys.get().CreateNotification.unwrap()(
+                overlay.overlay_handle,
+                0,
+                sys::EVRNotificationType_Transient,
+                text.as_ptr().cast_mut(),
+                sys::EVRNotificationStyle_Application,
+                image
+                    .as_mut()
+                    .map_or(std::ptr::null_mut(), std::ptr::from_mut),
+                &mut notification_id,
+            )
+        };
+
+        if error != sys::EVRNotificationError_VRNotificationError_OK {
+            return Err(anyhow!("Failed to create notification: {}", error));
+        }
+
+        Ok(())
+    }
+}
+
 pub struct CompositorInterface {
     sys: CastRc<sys::VR_IVRCompositor_FnTable>,
 }
@@ -314,6 +808,81 @@ impl Overlay {
         Ok(())
     }
 
+    /// Overlays with a higher sort order are drawn on top where they overlap.
+    pub fn set_sort_order(&self, sort_order: u32) -> Result<()> {
+        let error = unsafe {
+            self.interface.0.sys.get().SetOverlaySortOrder.unwrap()(self.overlay_handle, sort_order)
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set overlay sort order: {}",
+                error
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn set_width_in_meters(&self, width: f32) -> Result<()> {
+        let error = unsafe {
+            self.interface.0.sys.get().SetOverlayWidthInMeters.unwrap()(self.overlay_handle, width)
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!("Failed to set overlay width: {}", error));
+        }
+
+        Ok(())
+    }
+
+    /// Opacity of the whole overlay, from `0.0` to `1.0`.
+    pub fn set_alpha(&self, alpha: f32) -> Result<()> {
+        let error = unsafe {
+            self.interface.0.sys.get().SetOverlayAlpha.unwrap()(self.overlay_handle, alpha)
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!("Failed to set overlay alpha: {}", error));
+        }
+
+        Ok(())
+    }
+
+    /// Bends the overlay around the viewer, from `0.0` for flat to `1.0` for a full circle.
+    pub fn set_curvature(&self, curvature: f32) -> Result<()> {
+        let error = unsafe {
+            self.interface.0.sys.get().SetOverlayCurvature.unwrap()(self.overlay_handle, curvature)
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set overlay curvature: {}",
+                error
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Tint multiplied into the overlay's texture, with each channel from `0.0` to `1.0`.
+    pub fn set_color(&self, red: f32, green: f32, blue: f32) -> Result<()> {
+        let error = unsafe {
+            self.interface.0.sys.get().SetOverlayColor.unwrap()(
+                self.overlay_handle,
+                red,
+                green,
+                blue,
+            )
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!("Failed to set overlay color: {}", error));
+        }
+
+        Ok(())
+    }
+
     pub fn set_overlay_raw(
         &self,
         buffer: &[u8],
@@ -348,28 +917,37 @@ impl Overlay {
     }
 
     pub fn set_overlay_texture(&self, texture: &mut Texture) -> Result<()> {
-        let TextureHandle::Vulkan(ref vulkan_image, ref queue) = texture.handle else {
-            return Err(anyhow::anyhow!("Unsupported texture type"));
-        };
-
-        let mut texture_pointer = sys::VRVulkanTextureData_t {
-            m_nImage: vulkan_image.handle().as_raw(),
-            m_pDevice: vulkan_image.device().handle().as_raw() as *mut VkDevice_T,
-            m_pPhysicalDevice: vulkan_image.device().physical_device().handle().as_raw()
-                as *mut VkPhysicalDevice_T,
-            m_pInstance: vulkan_image.device().instance().handle().as_raw() as *mut VkInstance_T,
-            m_pQueue: queue.handle().as_raw() as *mut VkQueue_T,
-            m_nQueueFamilyIndex: queue.queue_family_index(),
-            m_nWidth: vulkan_image.extent()[0],
-            m_nHeight: vulkan_image.extent()[1],
-            m_nFormat: vulkan_image.format() as u32,
-            m_nSampleCount: vulkan_image.samples() as u32,
+        let mut texture_pointer;
+
+        let handle = match texture.handle {
+            TextureHandle::Vulkan(vulkan_image, queue) => {
+                texture_pointer = sys::VRVulkanTextureData_t {
+                    m_nImage: vulkan_image.handle().as_raw(),
+                    m_pDevice: vulkan_image.device().handle().as_raw() as *mut VkDevice_T,
+                    m_pPhysicalDevice: vulkan_image.device().physical_device().handle().as_raw()
+                        as *mut VkPhysicalDevice_T,
+                    m_pInstance: vulkan_image.device().instance().handle().as_raw()
+                        as *mut VkInstance_T,
+                    m_pQueue: queue.handle().as_raw() as *mut VkQueue_T,
+                    m_nQueueFamilyIndex: queue.queue_family_index(),
+                    m_nWidth: vulkan_image.extent()[0],
+                    m_nHeight: vulkan_image.extent()[1],
+                    m_nFormat: vulkan_image.format() as u32,
+                    m_nSampleCount: vulkan_image.samples() as u32,
+                };
+
+                trace!("{texture_pointer:?}");
+
+                std::ptr::from_mut(&mut texture_pointer).cast::<std::os::raw::c_void>()
+            }
+            TextureHandle::DirectX(d3d11_texture) => d3d11_texture,
+            TextureHandle::OpenGL(_) => {
+                return Err(anyhow::anyhow!("Unsupported texture type"));
+            }
         };
 
-        trace!("{texture_pointer:?}");
-
         let mut texture = sys::Texture_t {
-            handle: std::ptr::from_mut(&mut texture_pointer).cast::<std::os::raw::c_void>(),
+            handle,
             eType: texture.texture_type as i32,
             eColorSpace: texture.color_space as i32,
         };
@@ -416,6 +994,107 @@ impl Overlay {
         Ok(())
     }
 
+    /// Attaches the overlay to a tracked device, placed at `transform` relative to it. SteamVR
+    /// then moves the overlay along with the device.
+    pub fn set_overlay_transform_tracked_device_relative(
+        &self,
+        device_index: u32,
+        transform: Affine3A,
+    ) -> Result<()> {
+        let error = unsafe {
+            self.interface
+                .0
+                .sys
+                .get()
+                .SetOverlayTransformTrackedDeviceRelative
+                .unwrap()(
+                self.overlay_handle,
+                device_index,
+                &mut to_hmd_matrix34_t(transform),
+            )
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set overlay transform tracked device relative: {}",
+                error
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Lets SteamVR's laser pointer interact with the overlay, sending mouse events in a space of
+    /// `width` by `height` pixels.
+    pub fn enable_mouse_input(&self, width: f32, height: f32) -> Result<()> {
+        let sys = self.interface.0.sys.get();
+
+        let error = unsafe {
+            sys.SetOverlayInputMethod.unwrap()(
+                self.overlay_handle,
+                sys::VROverlayInputMethod_VROverlayInputMethod_Mouse,
+            )
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set overlay input method: {}",
+                error
+            ));
+        }
+
+        let error = unsafe {
+            sys.SetOverlayMouseScale.unwrap()(
+                self.overlay_handle,
+                &mut sys::HmdVector2_t { v: [width, height] },
+            )
+        };
+
+        if error != sys::EVROverlayError_VROverlayError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to set overlay mouse scale: {}",
+                error
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Next pending event of the overlay, or `None` once there are no more.
+    pub fn poll_next_event(&self) -> Option<OverlayEvent> {
+        let mut event: sys::VREvent_t = unsafe { std::mem::zeroed() };
+
+        let has_event = unsafe {
+            self.interface.0.sys.get().PollNextOverlayEvent.unwrap()(
+                self.overlay_handle,
+                &mut event,
+                u32::try_from(std::mem::size_of::<sys::VREvent_t>()).ok()?,
+            )
+        };
+
+        if !has_event {
+            return None;
+        }
+
+        Some(match event.eventType as sys::EVREventType {
+            sys::EVREventType_VREvent_MouseMove => {
+                let mouse = unsafe { event.data.mouse };
+
+                OverlayEvent::MouseMove {
+                    x: mouse.x,
+                    y: mouse.y,
+                }
+            }
+            sys::EVREventType_VREvent_MouseButtonDown => OverlayEvent::MouseButtonDown,
+            sys::EVREventType_VREvent_MouseButtonUp => OverlayEvent::MouseButtonUp,
+            sys::EVREventType_VREvent_FocusEnter => OverlayEvent::FocusEnter,
+            sys::EVREventType_VREvent_FocusLeave => OverlayEvent::FocusLeave,
+            sys::EVREventType_VREvent_OverlayShown => OverlayEvent::Shown,
+            sys::EVREventType_VREvent_OverlayHidden => OverlayEvent::Hidden,
+            _ => OverlayEvent::Other,
+        })
+    }
+
     pub fn wait_frame_sync(&self, timeout: u32) -> Result<()> {
         let error = unsafe { self.interface.0.sys.get().WaitFrameSync.unwrap()(timeout) };
 
@@ -501,4 +1180,14 @@ mod tests {
         assert!((a.y_axis - c.y_axis).length() < 1e-6);
         assert!((a.z_axis - c.z_axis).length() < 1e-6);
     }
+    #[test]
+    fn test_notification_bitmap_is_straight_alpha() {
+        let mut pixmap = tiny_skia::Pixmap::new(2, 1).unwrap();
+        pixmap.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 128));
+
+        let bitmap = NotificationBitmap::from_pixmap(&pixmap);
+
+        assert_eq!((bitmap.width, bitmap.height), (2, 1));
+        assert_eq!(&bitmap.data[..4], &[255, 0, 0, 128]);
+    }
 }
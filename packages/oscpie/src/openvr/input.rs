@@ -1,6 +1,575 @@
-#[rustfmt::skip]
-#[allow(nonstandard_style, unused, clippy::all, clippy::pedantic)]
-pub mod generated;
+//! SteamVR input, with the actions and action sets read from the action manifest when starting
+//! up. Actions are looked up by the last part of their name, e.g. `ClickLeft` for
+//! `/actions/main/in/ClickLeft`.
 
-#[allow(unused)]
-mod prelude;
+use std::collections::HashMap;
+
+use openvr_sys::{self as sys, VRActiveActionSet_t};
+use serde::Deserialize;
+
+use crate::openvr::{from_hmd_matrix34_t, CastRc, TrackingUniverseOrigin};
+use crate::prelude::*;
+
+/// Type of an action, as written in the action manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionType {
+    Boolean,
+    Vector1,
+    Vector2,
+    Vector3,
+    Vibration,
+    Pose,
+    Skeleton,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestAction {
+    name: String,
+    #[serde(rename = "type")]
+    action_type: ActionType,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestActionSet {
+    name: String,
+}
+
+/// The parts of `action_manifests.json` oscpie reads itself. SteamVR reads the rest, such as the
+/// default bindings.
+#[derive(Debug, Deserialize)]
+pub struct ActionManifest {
+    actions: Vec<ManifestAction>,
+    action_sets: Vec<ManifestActionSet>,
+}
+
+impl ActionManifest {
+    pub fn parse(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Types of the actions by short name.
+    fn action_types(&self) -> Result<HashMap<&str, ActionType>> {
+        let mut action_types = HashMap::new();
+
+        for action in &self.actions {
+            if action_types
+                .insert(short_name(&action.name), action.action_type)
+                .is_some()
+            {
+                return Err(anyhow!(
+                    "Action '{}' has the same name as another one in a different action set",
+                    action.name
+                ));
+            }
+        }
+
+        Ok(action_types)
+    }
+}
+
+/// `ClickLeft` for `/actions/main/in/ClickLeft` and `main` for `/actions/main`.
+fn short_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+/// Lowest priority of an overlay's action set which takes its inputs away from the game,
+/// `k_nActionSetOverlayGlobalPriorityMin`.
+pub const OVERLAY_GLOBAL_PRIORITY: i32 = 0x0100_0000;
+
+struct Action {
+    handle: sys::VRActionHandle_t,
+    action_type: ActionType,
+}
+
+#[derive(Debug, Clone)]
+pub struct BooleanInput {
+    pub active: bool,
+    pub state: bool,
+    pub changed: bool,
+    pub update_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vector1Input {
+    pub active: bool,
+    pub value: f32,
+    pub delta_value: f32,
+    pub update_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vector2Input {
+    pub active: bool,
+    pub value: Vec2,
+    pub delta: Vec2,
+    pub update_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Vector3Input {
+    pub active: bool,
+    pub value: Vec3A,
+    pub delta: Vec3A,
+    pub update_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseInput {
+    pub active: bool,
+    pub pose: Option<Affine3A>,
+    /// Velocity in tracking space, in meters per second.
+    pub velocity: Vec3A,
+    /// Angular velocity in tracking space, in radians per second.
+    pub angular_velocity: Vec3A,
+}
+
+#[derive(Debug, Clone)]
+pub struct SkeletonInput {
+    pub active: bool,
+    /// Curl of the thumb, index, middle, ring and pinky fingers, from `0.0` for straight to `1.0`
+    /// for curled into a fist.
+    pub finger_curl: [f32; 5],
+    /// Splay between adjacent fingers, from the thumb and index finger on.
+    pub finger_splay: [f32; 4],
+}
+
+pub struct Input {
+    sys: CastRc<sys::VR_IVRInput_FnTable>,
+    active_action_sets: Vec<sys::VRActiveActionSet_t>,
+    actions: HashMap<String, Action>,
+    action_sets: HashMap<String, sys::VRActionSetHandle_t>,
+}
+
+impl Input {
+    pub(in crate::openvr) fn new(
+        sys: CastRc<sys::VR_IVRInput_FnTable>,
+        manifest: &ActionManifest,
+    ) -> Result<Self> {
+        // Rejects short names which would be ambiguous
+        manifest.action_types()?;
+
+        let mut actions = HashMap::new();
+
+        for action in &manifest.actions {
+            actions.insert(
+                short_name(&action.name).to_string(),
+                Action {
+                    handle: Self::get_action_handle(sys.get(), &action.name)?,
+                    action_type: action.action_type,
+                },
+            );
+        }
+
+        let action_sets = manifest
+            .action_sets
+            .iter()
+            .map(|set| {
+                Ok((
+                    short_name(&set.name).to_string(),
+                    Self::get_action_set_handle(sys.get(), &set.name)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Input {
+            sys,
+            active_action_sets: vec![],
+            actions,
+            action_sets,
+        })
+    }
+
+    fn action_handle(&self, name: &str, expected: ActionType) -> Result<sys::VRActionHandle_t> {
+        let action = self
+            .actions
+            .get(name)
+            .ok_or_else(|| anyhow!("Action '{name}' is not in the action manifest"))?;
+
+        if action.action_type != expected {
+            return Err(anyhow!(
+                "Action '{name}' is a {:?} action, not {expected:?}",
+                action.action_type
+            ));
+        }
+
+        Ok(action.handle)
+    }
+
+    fn action_set_handle(&self, name: &str) -> Result<sys::VRActionSetHandle_t> {
+        self.action_sets
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow!("Action set '{name}' is not in the action manifest"))
+    }
+
+    /// Activates the action set named like `main`, for reading its actions after the next
+    /// [`Input::update`]. Sets of higher `priority` take inputs bound in several sets, and from
+    /// [`OVERLAY_GLOBAL_PRIORITY`] on from the game as well.
+    pub fn activate_set(&mut self, action_set: &str, priority: i32) -> Result<()> {
+        let handle = self.action_set_handle(action_set)?;
+        self.activate_action_set(handle, priority);
+
+        Ok(())
+    }
+
+    pub fn deactivate_set(&mut self, action_set: &str) -> Result<()> {
+        let handle = self.action_set_handle(action_set)?;
+        self.deactivate_action_set(handle);
+
+        Ok(())
+    }
+
+    pub fn digital(&self, name: &str) -> Result<BooleanInput> {
+        self.get_digital_action_data(self.action_handle(name, ActionType::Boolean)?)
+    }
+
+    pub fn vector1(&self, name: &str) -> Result<Vector1Input> {
+        self.get_vector1_action_data(self.action_handle(name, ActionType::Vector1)?)
+    }
+
+    pub fn vector2(&self, name: &str) -> Result<Vector2Input> {
+        self.get_vector2_action_data(self.action_handle(name, ActionType::Vector2)?)
+    }
+
+    pub fn vector3(&self, name: &str) -> Result<Vector3Input> {
+        self.get_vector3_action_data(self.action_handle(name, ActionType::Vector3)?)
+    }
+
+    pub fn pose(&self, name: &str, origin: TrackingUniverseOrigin) -> Result<PoseInput> {
+        self.get_pose_action_data(origin, self.action_handle(name, ActionType::Pose)?)
+    }
+
+    pub fn skeleton(&self, name: &str) -> Result<SkeletonInput> {
+        self.get_skeletal_summary_data(self.action_handle(name, ActionType::Skeleton)?)
+    }
+
+    pub fn update(&mut self) -> Result<()> {
+        let len = u32::try_from(self.active_action_sets.len())?;
+
+        let result = unsafe {
+            self.sys.get().UpdateActionState.unwrap()(
+                self.active_action_sets.as_mut_ptr(),
+                u32::try_from(std::mem::size_of::<VRActiveActionSet_t>())?,
+                len,
+            )
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to update action state: {:?}",
+                result
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn get_action_handle(
+        sys: &sys::VR_IVRInput_FnTable,
+        action_name: &str,
+    ) -> Result<sys::VRActionHandle_t> {
+        let mut action_handle = sys::VRActionHandle_t::default();
+        let c_action_name = std::ffi::CString::new(action_name).unwrap();
+
+        let result = unsafe {
+            sys.GetActionHandle.unwrap()(c_action_name.as_ptr().cast_mut(), &mut action_handle)
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get action handle for '{}': {:?}",
+                action_name,
+                result
+            ));
+        }
+
+        Ok(action_handle)
+    }
+
+    pub fn get_action_set_handle(
+        sys: &sys::VR_IVRInput_FnTable,
+        action_set_name: &str,
+    ) -> Result<sys::VRActionSetHandle_t> {
+        let mut action_set_handle = sys::VRActionSetHandle_t::default();
+        let c_action_set_name = std::ffi::CString::new(action_set_name).unwrap();
+
+        let result = unsafe {
+            sys.GetActionSetHandle.unwrap()(
+                c_action_set_name.as_ptr().cast_mut(),
+                &mut action_set_handle,
+            )
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get action set handle for '{}': {:?}",
+                action_set_name,
+                result
+            ));
+        }
+
+        Ok(action_set_handle)
+    }
+
+    fn activate_action_set(&mut self, action_set_handle: sys::VRActionSetHandle_t, priority: i32) {
+        let active_action_set = sys::VRActiveActionSet_t {
+            ulActionSet: action_set_handle,
+            ulRestrictedToDevice: 0,
+            ulSecondaryActionSet: 0,
+            unPadding: 0,
+            nPriority: priority,
+        };
+
+        self.deactivate_action_set(action_set_handle);
+
+        self.active_action_sets.push(active_action_set);
+    }
+
+    fn deactivate_action_set(&mut self, action_set_handle: sys::VRActionSetHandle_t) {
+        self.active_action_sets
+            .retain(|set| set.ulActionSet != action_set_handle);
+    }
+
+    fn get_digital_action_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<BooleanInput> {
+        let mut data = sys::InputDigitalActionData_t {
+            bActive: false,
+            bState: false,
+            bChanged: false,
+            fUpdateTime: 0.0,
+            activeOrigin: 0,
+        };
+
+        log::trace!("Getting digital action data for handle: {action_handle:?}");
+
+        let result = unsafe {
+            self.sys.get().GetDigitalActionData.unwrap()(
+                action_handle,
+                &mut data,
+                u32::try_from(std::mem::size_of::<sys::InputDigitalActionData_t>())?,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get digital action data: {:?}",
+                result
+            ));
+        }
+
+        Ok(BooleanInput {
+            active: data.bActive,
+            state: data.bState,
+            changed: data.bChanged,
+            update_time: data.fUpdateTime,
+        })
+    }
+
+    fn get_analog_action_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<sys::InputAnalogActionData_t> {
+        let mut data = sys::InputAnalogActionData_t {
+            bActive: false,
+            fUpdateTime: 0.0,
+            activeOrigin: 0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            deltaX: 0.0,
+            deltaY: 0.0,
+            deltaZ: 0.0,
+        };
+
+        log::trace!("Getting analog action data for handle: {action_handle:?}");
+
+        let result = unsafe {
+            self.sys.get().GetAnalogActionData.unwrap()(
+                action_handle,
+                &mut data,
+                u32::try_from(std::mem::size_of::<sys::InputAnalogActionData_t>())?,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get analog action data: {:?}",
+                result
+            ));
+        }
+
+        Ok(data)
+    }
+
+    fn get_vector1_action_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<Vector1Input> {
+        let data = self.get_analog_action_data(action_handle)?;
+
+        Ok(Vector1Input {
+            active: data.bActive,
+            value: data.x,
+            delta_value: data.deltaX,
+            update_time: data.fUpdateTime,
+        })
+    }
+
+    fn get_vector2_action_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<Vector2Input> {
+        let data = self.get_analog_action_data(action_handle)?;
+
+        Ok(Vector2Input {
+            active: data.bActive,
+            value: Vec2::new(data.x, data.y),
+            delta: Vec2::new(data.deltaX, data.deltaY),
+            update_time: data.fUpdateTime,
+        })
+    }
+
+    fn get_vector3_action_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<Vector3Input> {
+        let data = self.get_analog_action_data(action_handle)?;
+
+        Ok(Vector3Input {
+            active: data.bActive,
+            value: Vec3A::new(data.x, data.y, data.z),
+            delta: Vec3A::new(data.deltaX, data.deltaY, data.deltaZ),
+            update_time: data.fUpdateTime,
+        })
+    }
+
+    fn get_pose_action_data(
+        &self,
+        tracking_universe_origin: TrackingUniverseOrigin,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<PoseInput> {
+        let mut data = sys::InputPoseActionData_t {
+            bActive: false,
+            activeOrigin: 0,
+            pose: sys::TrackedDevicePose_t {
+                mDeviceToAbsoluteTracking: sys::HmdMatrix34_t {
+                    m: [
+                        [0.0, 0.0, 0.0, 0.0],
+                        [0.0, 0.0, 0.0, 0.0],
+                        [0.0, 0.0, 0.0, 0.0],
+                    ],
+                },
+                vVelocity: sys::HmdVector3_t { v: [0.0, 0.0, 0.0] },
+                vAngularVelocity: sys::HmdVector3_t { v: [0.0, 0.0, 0.0] },
+                eTrackingResult: 0,
+                bPoseIsValid: false,
+                bDeviceIsConnected: false,
+            },
+        };
+
+        let result = unsafe {
+            self.sys.get().GetPoseActionDataForNextFrame.unwrap()(
+                action_handle,
+                tracking_universe_origin as sys::ETrackingUniverseOrigin, // TODO: Origin
+                &mut data,
+                u32::try_from(std::mem::size_of::<sys::InputPoseActionData_t>())?,
+                sys::k_ulInvalidInputValueHandle,
+            )
+        };
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get pose action data: {:?}",
+                result
+            ));
+        }
+
+        Ok(PoseInput {
+            active: data.bActive,
+            // Being active only means the action is bound, the device may still have lost tracking
+            pose: (data.pose.bPoseIsValid && data.pose.bDeviceIsConnected)
+                .then(|| from_hmd_matrix34_t(data.pose.mDeviceToAbsoluteTracking)),
+            velocity: Vec3A::from_array(data.pose.vVelocity.v),
+            angular_velocity: Vec3A::from_array(data.pose.vAngularVelocity.v),
+        })
+    }
+
+    fn get_skeletal_summary_data(
+        &self,
+        action_handle: sys::VRActionHandle_t,
+    ) -> Result<SkeletonInput> {
+        let mut data = sys::VRSkeletalSummaryData_t {
+            flFingerCurl: [0.0; 5],
+            flFingerSplay: [0.0; 4],
+        };
+
+        let result = unsafe {
+            self.sys.get().GetSkeletalSummaryData.unwrap()(
+                action_handle,
+                sys::EVRSummaryType_VRSummaryType_FromAnimation,
+                &mut data,
+            )
+        };
+
+        // Controllers without finger tracking have no skeleton to summarize
+        if result == sys::EVRInputError_VRInputError_NoData {
+            return Ok(SkeletonInput {
+                active: false,
+                finger_curl: [0.0; 5],
+                finger_splay: [0.0; 4],
+            });
+        }
+
+        if result != sys::EVRInputError_VRInputError_None {
+            return Err(anyhow::anyhow!(
+                "Failed to get skeletal summary data: {:?}",
+                result
+            ));
+        }
+
+        Ok(SkeletonInput {
+            active: true,
+            finger_curl: data.flFingerCurl,
+            finger_splay: data.flFingerSplay,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_manifest() {
+        let manifest =
+            ActionManifest::parse(include_str!("../../config/action_manifests.json")).unwrap();
+        let action_types = manifest.action_types().unwrap();
+
+        assert_eq!(action_types["ClickLeft"], ActionType::Boolean);
+        assert_eq!(action_types["SelectRight"], ActionType::Vector2);
+        assert_eq!(action_types["PoseLeft"], ActionType::Pose);
+        assert_eq!(action_types["SkeletonRight"], ActionType::Skeleton);
+        assert_eq!(short_name(&manifest.action_sets[0].name), "main");
+    }
+
+    #[test]
+    fn test_rejects_ambiguous_names() {
+        let manifest = ActionManifest::parse(
+            r#"{
+                "actions": [
+                    { "name": "/actions/main/in/Click", "type": "boolean" },
+                    { "name": "/actions/menu/in/Click", "type": "boolean" }
+                ],
+                "action_sets": [{ "name": "/actions/main" }, { "name": "/actions/menu" }]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(manifest.action_types().is_err());
+    }
+}
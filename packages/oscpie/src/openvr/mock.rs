@@ -0,0 +1,225 @@
+//! Stand-in for SteamVR, replaying scripted input and recording what oscpie asks of it, so that the
+//! main loop runs without a headset, e.g. in CI.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+};
+
+use tiny_skia::Pixmap;
+
+use crate::{
+    config::types::OverlayConfig,
+    dirty_rows::DirtyRows,
+    hand::Hand,
+    input_expression::DIGITAL_ACTIONS,
+    overlay_layers::OverlayLayer,
+    prelude::*,
+    runtime::{HandInput, HandPose, RuntimeEvent, XrRuntime},
+};
+
+/// Input for one frame of the main loop.
+#[derive(Debug, Clone, Default)]
+pub struct MockFrame {
+    pub events: Vec<RuntimeEvent>,
+    /// Digital actions held down, such as `OpenLeft`.
+    pub pressed: Vec<&'static str>,
+    pub click: bool,
+    pub select: Vec2,
+    pub pose: Option<HandPose>,
+    pub hmd_pose: Option<Affine3A>,
+}
+
+impl MockFrame {
+    /// Controller held still at the origin, nothing pressed.
+    pub fn tracked() -> Self {
+        Self {
+            pose: Some(HandPose {
+                transform: Affine3A::IDENTITY,
+                velocity: Vec3A::ZERO,
+                angular_velocity: Vec3A::ZERO,
+            }),
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn pressing(mut self, action: &'static str) -> Self {
+        self.pressed.push(action);
+        self
+    }
+}
+
+/// What oscpie asked of the runtime, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockCall {
+    PlaceMenu {
+        hand: Hand,
+        transform: Option<Affine3A>,
+    },
+    Notify {
+        message: String,
+        has_icon: bool,
+    },
+    SetMenuOpen {
+        is_open: bool,
+    },
+    Submit {
+        visible: bool,
+    },
+    Resize {
+        size: u32,
+    },
+    ApplyOverlayConfig {
+        width_m: f32,
+    },
+    Hide,
+}
+
+/// Runtime playing back [`MockFrame`]s, one per frame. Asks oscpie to quit once they run out.
+#[derive(Debug, Default)]
+pub struct MockRuntime {
+    script: VecDeque<MockFrame>,
+    frame: MockFrame,
+    calls: RefCell<Vec<MockCall>>,
+}
+
+impl MockRuntime {
+    pub fn new(script: impl IntoIterator<Item = MockFrame>) -> Self {
+        Self {
+            script: script.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Opens the menu, leaves it open for `frames` frames and closes it again.
+    pub fn toggle_menu(frames: usize) -> Self {
+        let open = || MockFrame::tracked().pressing("OpenLeft");
+
+        Self::new(
+            std::iter::once(open())
+                .chain(std::iter::repeat_with(MockFrame::tracked).take(frames))
+                .chain([open(), MockFrame::tracked()]),
+        )
+    }
+
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    fn record(&self, call: MockCall) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl XrRuntime for MockRuntime {
+    fn poll_events(&mut self) -> Result<Vec<RuntimeEvent>> {
+        match self.script.pop_front() {
+            Some(frame) => {
+                self.frame = frame;
+                Ok(self.frame.events.clone())
+            }
+            None => {
+                self.frame = MockFrame::default();
+                Ok(vec![RuntimeEvent::Quit])
+            }
+        }
+    }
+
+    fn update_input(&mut self) -> Result<HashMap<&'static str, bool>> {
+        Ok(DIGITAL_ACTIONS
+            .iter()
+            .map(|name| (*name, self.frame.pressed.contains(name)))
+            .collect())
+    }
+
+    fn hand_input(&self, _hand: Hand) -> Result<HandInput> {
+        Ok(HandInput {
+            click: self.frame.click,
+            select: self.frame.select,
+            pose: self.frame.pose,
+        })
+    }
+
+    fn hmd_pose(&self) -> Option<Affine3A> {
+        self.frame.hmd_pose
+    }
+
+    fn place_menu(&mut self, hand: Hand, transform: Option<Affine3A>) -> Result<Option<Affine3A>> {
+        self.record(MockCall::PlaceMenu { hand, transform });
+
+        Ok(transform.map(|transform| OverlayLayer::Menu.offset(transform)))
+    }
+
+    fn notify(&self, message: &str, icon: Option<&Pixmap>) -> Result<()> {
+        self.record(MockCall::Notify {
+            message: message.to_string(),
+            has_icon: icon.is_some(),
+        });
+
+        Ok(())
+    }
+
+    fn set_menu_open(&mut self, is_open: bool) -> Result<()> {
+        self.record(MockCall::SetMenuOpen { is_open });
+
+        Ok(())
+    }
+
+    fn submit(&mut self, _pixmap: &Pixmap, _dirty: Option<DirtyRows>, visible: bool) -> Result<()> {
+        self.record(MockCall::Submit { visible });
+
+        Ok(())
+    }
+
+    fn resize(&mut self, pixmap: &Pixmap, _config: &OverlayConfig) -> Result<()> {
+        self.record(MockCall::Resize {
+            size: pixmap.width(),
+        });
+
+        Ok(())
+    }
+
+    fn apply_overlay_config(&mut self, config: &OverlayConfig) -> Result<()> {
+        self.record(MockCall::ApplyOverlayConfig {
+            width_m: config.width_m,
+        });
+
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<()> {
+        self.record(MockCall::Hide);
+
+        Ok(())
+    }
+
+    fn wait_frame(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quits_after_the_script() {
+        let mut runtime = MockRuntime::new([MockFrame::tracked()]);
+
+        assert!(runtime.poll_events().unwrap().is_empty());
+        assert_eq!(runtime.poll_events().unwrap(), vec![RuntimeEvent::Quit]);
+    }
+
+    #[test]
+    fn test_replays_pressed_actions() {
+        let mut runtime = MockRuntime::new([MockFrame::tracked().pressing("GripRight")]);
+
+        runtime.poll_events().unwrap();
+        let actions = runtime.update_input().unwrap();
+
+        assert_eq!(actions.len(), DIGITAL_ACTIONS.len());
+        assert!(actions["GripRight"]);
+        assert!(!actions["OpenLeft"]);
+    }
+}
@@ -0,0 +1,96 @@
+mod dns;
+mod packet;
+mod query;
+mod receiver;
+mod sender;
+
+use std::{sync::OnceLock, time::Duration};
+
+use crate::config;
+
+pub use packet::{OscMessage, OscValue};
+pub use receiver::OscReceiver;
+pub use sender::OscSender;
+
+/// Prefix of the addresses avatar parameters are sent to.
+pub const AVATAR_PARAMETER_PREFIX: &str = "/avatar/parameters/";
+
+static SENDER: OnceLock<OscSender> = OnceLock::new();
+static RECEIVER: OnceLock<OscReceiver> = OnceLock::new();
+
+/// How long to wait for a `VRChat` client to answer the `OSCQuery` discovery.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Sets up sending and receiving. Failures are logged, the menu works without OSC.
+///
+/// With `discover`, the send address is taken from `OSCQuery` and the receiver is advertised to
+/// `OSCQuery` clients, falling back to the configured host and ports.
+pub fn init(config: &config::types::OscConfig) {
+    let discovered = if config.discover {
+        query::discover_vrchat(DISCOVERY_TIMEOUT).unwrap_or_else(|e| {
+            log::warn!("OSCQuery discovery failed: {e}");
+            None
+        })
+    } else {
+        None
+    };
+
+    let (host, send_port) = discovered.map_or_else(
+        || (config.host.clone(), config.send_port),
+        |address| (address.ip().to_string(), address.port()),
+    );
+
+    if let Err(e) = init_sender(&host, send_port) {
+        log::warn!("Failed to set up OSC, OSC actions will fail: {e}");
+    }
+
+    if let Err(e) = init_receiver(config.receive_port) {
+        log::warn!("Failed to listen for OSC, menu items will not reflect avatar state: {e}");
+        return;
+    }
+
+    if config.discover {
+        if let Err(e) = query::advertise("oscpie", config.receive_port) {
+            log::warn!("Failed to advertise OSCQuery service: {e}");
+        }
+    }
+}
+
+/// Sets up the sender shared by all OSC actions. Only the first call has an effect.
+pub fn init_sender(host: &str, port: u16) -> Result<(), std::io::Error> {
+    let sender = OscSender::new(host, port)?;
+
+    log::info!("Sending OSC to {host}:{port}");
+
+    if SENDER.set(sender).is_err() {
+        log::warn!("OSC sender is already initialized");
+    }
+
+    Ok(())
+}
+
+pub fn get_sender() -> Option<&'static OscSender> {
+    SENDER.get()
+}
+
+/// Starts listening for parameter changes. Only the first call has an effect.
+pub fn init_receiver(port: u16) -> Result<(), std::io::Error> {
+    let receiver = OscReceiver::listen(port)?;
+
+    log::info!("Receiving OSC on port {port}");
+
+    if RECEIVER.set(receiver).is_err() {
+        log::warn!("OSC receiver is already initialized");
+    }
+
+    Ok(())
+}
+
+/// Latest value received for an avatar parameter, if the receiver is running and has seen it.
+pub fn get_avatar_parameter(parameter: &str) -> Option<OscValue> {
+    RECEIVER.get()?.get(&avatar_parameter_address(parameter))
+}
+
+pub fn avatar_parameter_address(parameter: &str) -> String {
+    format!("{AVATAR_PARAMETER_PREFIX}{parameter}")
+}
@@ -0,0 +1,341 @@
+use std::net::Ipv4Addr;
+
+use anyhow::anyhow;
+
+use crate::prelude::*;
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_SRV: u16 = 33;
+
+const CLASS_IN: u16 = 1;
+/// Set on a question to ask for a unicast response.
+const UNICAST_RESPONSE: u16 = 0x8000;
+/// Bytes of the shortest question: the root name, a type and a class.
+const MIN_QUESTION_LEN: usize = 1 + 2 + 2;
+/// Bytes of the shortest record: a question, a TTL and the length of no data.
+const MIN_RECORD_LEN: usize = MIN_QUESTION_LEN + 4 + 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub name: String,
+    pub record_type: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Ptr(String),
+    Srv { port: u16, target: String },
+    Txt(Vec<String>),
+    Other(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub name: String,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+/// Minimal DNS message, as far as needed for mDNS service discovery.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsMessage {
+    pub is_response: bool,
+    pub questions: Vec<Question>,
+    pub records: Vec<Record>,
+}
+
+impl DnsMessage {
+    pub fn query(name: &str, record_type: u16) -> Self {
+        Self {
+            is_response: false,
+            questions: vec![Question {
+                name: name.to_string(),
+                record_type,
+            }],
+            records: vec![],
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+
+        let flags: u16 = if self.is_response { 0x8400 } else { 0 };
+
+        for value in [
+            0,
+            flags,
+            u16::try_from(self.questions.len()).unwrap_or(u16::MAX),
+            u16::try_from(self.records.len()).unwrap_or(u16::MAX),
+            0,
+            0,
+        ] {
+            buffer.extend_from_slice(&value.to_be_bytes());
+        }
+
+        for question in &self.questions {
+            write_name(&mut buffer, &question.name);
+            buffer.extend_from_slice(&question.record_type.to_be_bytes());
+            buffer.extend_from_slice(&(CLASS_IN | UNICAST_RESPONSE).to_be_bytes());
+        }
+
+        for record in &self.records {
+            write_name(&mut buffer, &record.name);
+
+            let (record_type, data) = match &record.data {
+                RecordData::A(address) => (TYPE_A, address.octets().to_vec()),
+                RecordData::Ptr(name) => {
+                    let mut data = vec![];
+                    write_name(&mut data, name);
+                    (TYPE_PTR, data)
+                }
+                RecordData::Srv { port, target } => {
+                    let mut data = vec![0, 0, 0, 0];
+                    data.extend_from_slice(&port.to_be_bytes());
+                    write_name(&mut data, target);
+                    (TYPE_SRV, data)
+                }
+                RecordData::Txt(entries) => {
+                    let mut data = vec![];
+                    for entry in entries {
+                        data.push(u8::try_from(entry.len()).unwrap_or(u8::MAX));
+                        data.extend_from_slice(&entry.as_bytes()[..entry.len().min(255)]);
+                    }
+                    (TYPE_TXT, data)
+                }
+                RecordData::Other(record_type) => (*record_type, vec![]),
+            };
+
+            buffer.extend_from_slice(&record_type.to_be_bytes());
+            buffer.extend_from_slice(&CLASS_IN.to_be_bytes());
+            buffer.extend_from_slice(&record.ttl.to_be_bytes());
+            buffer.extend_from_slice(&u16::try_from(data.len()).unwrap_or(0).to_be_bytes());
+            buffer.extend_from_slice(&data);
+        }
+
+        buffer
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = Reader { data, offset: 0 };
+
+        reader.read_u16()?; // ID
+        let flags = reader.read_u16()?;
+        let question_count = usize::from(reader.read_u16()?);
+        let record_count = [reader.read_u16()?, reader.read_u16()?, reader.read_u16()?]
+            .into_iter()
+            .map(usize::from)
+            .sum::<usize>();
+
+        // Counts come from the network, don't trust them further than the packet reaches
+        if question_count * MIN_QUESTION_LEN + record_count * MIN_RECORD_LEN
+            > data.len() - reader.offset
+        {
+            return Err(anyhow!("DNS message is too short for its counts"));
+        }
+
+        let mut message = DnsMessage {
+            is_response: flags & 0x8000 != 0,
+            ..DnsMessage::default()
+        };
+
+        for _ in 0..question_count {
+            let name = reader.read_name()?;
+            let record_type = reader.read_u16()?;
+            reader.read_u16()?; // Class
+
+            message.questions.push(Question { name, record_type });
+        }
+
+        for _ in 0..record_count {
+            let name = reader.read_name()?;
+            let record_type = reader.read_u16()?;
+            reader.read_u16()?; // Class
+            let ttl = u32::from(reader.read_u16()?) << 16 | u32::from(reader.read_u16()?);
+            let length = usize::from(reader.read_u16()?);
+            let end = reader.offset + length;
+
+            let data = match record_type {
+                TYPE_A => {
+                    let octets = reader.read_bytes(4)?;
+                    RecordData::A(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+                }
+                TYPE_PTR => RecordData::Ptr(reader.read_name()?),
+                TYPE_SRV => {
+                    reader.read_bytes(4)?; // Priority and weight
+                    let port = reader.read_u16()?;
+                    RecordData::Srv {
+                        port,
+                        target: reader.read_name()?,
+                    }
+                }
+                TYPE_TXT => {
+                    let mut entries = vec![];
+                    while reader.offset < end {
+                        let length = usize::from(reader.read_bytes(1)?[0]);
+                        entries.push(String::from_utf8_lossy(reader.read_bytes(length)?).into());
+                    }
+                    RecordData::Txt(entries)
+                }
+                other => RecordData::Other(other),
+            };
+
+            reader.offset = end;
+
+            message.records.push(Record { name, ttl, data });
+        }
+
+        Ok(message)
+    }
+}
+
+fn write_name(buffer: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        buffer.push(u8::try_from(label.len()).unwrap_or(63).min(63));
+        buffer.extend_from_slice(&label.as_bytes()[..label.len().min(63)]);
+    }
+
+    buffer.push(0);
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn read_bytes(&mut self, length: usize) -> Result<&[u8]> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + length)
+            .ok_or_else(|| anyhow!("Unexpected end of DNS message"))?;
+
+        self.offset += length;
+
+        Ok(bytes)
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.read_bytes(2)?;
+
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads a possibly compressed name.
+    fn read_name(&mut self) -> Result<String> {
+        let mut labels = vec![];
+        let mut position = self.offset;
+        let mut jumped = false;
+
+        // Bounded so that pointer loops in malformed messages terminate
+        for _ in 0..128 {
+            let length = *self
+                .data
+                .get(position)
+                .ok_or_else(|| anyhow!("Unexpected end of DNS name"))?;
+
+            if length & 0xc0 == 0xc0 {
+                let low = *self
+                    .data
+                    .get(position + 1)
+                    .ok_or_else(|| anyhow!("Unexpected end of DNS name"))?;
+
+                if !jumped {
+                    self.offset = position + 2;
+                    jumped = true;
+                }
+
+                position = usize::from(length & 0x3f) << 8 | usize::from(low);
+                continue;
+            }
+
+            if length == 0 {
+                if !jumped {
+                    self.offset = position + 1;
+                }
+
+                return Ok(labels.join("."));
+            }
+
+            let start = position + 1;
+            let label = self
+                .data
+                .get(start..start + usize::from(length))
+                .ok_or_else(|| anyhow!("Unexpected end of DNS label"))?;
+
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            position = start + usize::from(length);
+        }
+
+        Err(anyhow!("DNS name is too long"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_rejects_overstated_counts() {
+        let mut data = vec![0; 4];
+        data.extend_from_slice(&[0xff; 8]);
+
+        assert!(DnsMessage::decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let message = DnsMessage {
+            is_response: true,
+            questions: vec![],
+            records: vec![
+                Record {
+                    name: "_oscjson._tcp.local".to_string(),
+                    ttl: 120,
+                    data: RecordData::Ptr("oscpie._oscjson._tcp.local".to_string()),
+                },
+                Record {
+                    name: "oscpie._oscjson._tcp.local".to_string(),
+                    ttl: 120,
+                    data: RecordData::Srv {
+                        port: 8080,
+                        target: "oscpie.local".to_string(),
+                    },
+                },
+                Record {
+                    name: "oscpie.local".to_string(),
+                    ttl: 120,
+                    data: RecordData::A(Ipv4Addr::LOCALHOST),
+                },
+                Record {
+                    name: "oscpie._oscjson._tcp.local".to_string(),
+                    ttl: 120,
+                    data: RecordData::Txt(vec!["txtvers=1".to_string()]),
+                },
+            ],
+        };
+
+        assert_eq!(DnsMessage::decode(&message.encode()).unwrap(), message);
+    }
+
+    #[test]
+    fn test_decode_compressed_name() {
+        let mut data = DnsMessage::query("_oscjson._tcp.local", TYPE_PTR).encode();
+
+        // Answer whose name points back at the question name at offset 12
+        data[7] = 1;
+        data.extend_from_slice(&[0xc0, 12]);
+        data.extend_from_slice(&TYPE_A.to_be_bytes());
+        data.extend_from_slice(&CLASS_IN.to_be_bytes());
+        data.extend_from_slice(&120u32.to_be_bytes());
+        data.extend_from_slice(&4u16.to_be_bytes());
+        data.extend_from_slice(&[127, 0, 0, 1]);
+
+        let message = DnsMessage::decode(&data).unwrap();
+
+        assert_eq!(message.records[0].name, "_oscjson._tcp.local");
+        assert_eq!(message.records[0].data, RecordData::A(Ipv4Addr::LOCALHOST));
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::anyhow;
+
+use crate::{config, prelude::*};
+
+/// Argument of an OSC message. Only the types used by avatar parameters are supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscValue {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+}
+
+impl From<config::types::OscValue> for OscValue {
+    fn from(value: config::types::OscValue) -> Self {
+        match value {
+            config::types::OscValue::Int(value) => OscValue::Int(value),
+            config::types::OscValue::Float(value) => OscValue::Float(value),
+            config::types::OscValue::Bool(value) => OscValue::Bool(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscValue>,
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, args: Vec<OscValue>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+
+        write_string(&mut buffer, &self.address);
+
+        let type_tags: String = std::iter::once(',')
+            .chain(self.args.iter().map(|arg| match arg {
+                OscValue::Int(_) => 'i',
+                OscValue::Float(_) => 'f',
+                OscValue::Bool(true) => 'T',
+                OscValue::Bool(false) => 'F',
+            }))
+            .collect();
+
+        write_string(&mut buffer, &type_tags);
+
+        for arg in &self.args {
+            match arg {
+                OscValue::Int(value) => buffer.extend_from_slice(&value.to_be_bytes()),
+                OscValue::Float(value) => buffer.extend_from_slice(&value.to_be_bytes()),
+                OscValue::Bool(_) => {}
+            }
+        }
+
+        buffer
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = Reader { data, offset: 0 };
+
+        let address = reader.read_string()?;
+        let type_tags = reader.read_string()?;
+
+        let Some(type_tags) = type_tags.strip_prefix(',') else {
+            return Err(anyhow!("Missing type tags in message to {address}"));
+        };
+
+        let args = type_tags
+            .chars()
+            .map(|tag| match tag {
+                'i' => Ok(OscValue::Int(i32::from_be_bytes(reader.read_4()?))),
+                'f' => Ok(OscValue::Float(f32::from_be_bytes(reader.read_4()?))),
+                'T' => Ok(OscValue::Bool(true)),
+                'F' => Ok(OscValue::Bool(false)),
+                _ => Err(anyhow!(
+                    "Unsupported type tag '{tag}' in message to {address}"
+                )),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { address, args })
+    }
+}
+
+/// Decodes a packet into its messages, flattening bundles.
+pub fn decode_packet(data: &[u8]) -> Result<Vec<OscMessage>> {
+    let Some(mut rest) = data.strip_prefix(b"#bundle\0") else {
+        return Ok(vec![OscMessage::decode(data)?]);
+    };
+
+    // Time tag, messages are applied as soon as they arrive
+    rest = rest.get(8..).ok_or_else(|| anyhow!("Truncated bundle"))?;
+
+    let mut messages = vec![];
+
+    while !rest.is_empty() {
+        let mut reader = Reader {
+            data: rest,
+            offset: 0,
+        };
+
+        let size = usize::try_from(i32::from_be_bytes(reader.read_4()?))?;
+        let element = rest
+            .get(4..4 + size)
+            .ok_or_else(|| anyhow!("Truncated bundle element"))?;
+
+        messages.extend(decode_packet(element)?);
+        rest = &rest[4 + size..];
+    }
+
+    Ok(messages)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn read_4(&mut self) -> Result<[u8; 4]> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + 4)
+            .ok_or_else(|| anyhow!("Unexpected end of packet"))?;
+
+        self.offset += 4;
+
+        Ok(bytes.try_into()?)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let rest = &self.data[self.offset.min(self.data.len())..];
+        let length = rest
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| anyhow!("Unterminated string"))?;
+
+        let string = std::str::from_utf8(&rest[..length])?.to_string();
+
+        self.offset += (length + 4) & !3;
+
+        Ok(string)
+    }
+}
+
+/// Writes a null terminated string padded to a multiple of four bytes.
+fn write_string(buffer: &mut Vec<u8>, string: &str) {
+    buffer.extend_from_slice(string.as_bytes());
+    buffer.push(0);
+
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_int() {
+        let message = OscMessage::new("/avatar/parameters/Mode", vec![OscValue::Int(3)]);
+
+        let mut expected = b"/avatar/parameters/Mode\0".to_vec();
+        expected.extend_from_slice(b",i\0\0");
+        expected.extend_from_slice(&[0, 0, 0, 3]);
+
+        assert_eq!(message.encode(), expected);
+    }
+
+    #[test]
+    fn test_encode_bool_has_no_payload() {
+        let message = OscMessage::new("/a", vec![OscValue::Bool(true)]);
+
+        assert_eq!(message.encode(), b"/a\0\0,T\0\0".to_vec());
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        let message = OscMessage::new(
+            "/avatar/parameters/Mode",
+            vec![
+                OscValue::Float(0.5),
+                OscValue::Bool(false),
+                OscValue::Int(-1),
+            ],
+        );
+
+        assert_eq!(OscMessage::decode(&message.encode()).unwrap(), message);
+    }
+
+    #[test]
+    fn test_decode_bundle() {
+        let first = OscMessage::new("/a", vec![OscValue::Int(1)]);
+        let second = OscMessage::new("/b", vec![OscValue::Bool(true)]);
+
+        let mut bundle = b"#bundle\0".to_vec();
+        bundle.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+
+        for message in [&first, &second] {
+            let encoded = message.encode();
+            bundle.extend_from_slice(&i32::try_from(encoded.len()).unwrap().to_be_bytes());
+            bundle.extend_from_slice(&encoded);
+        }
+
+        assert_eq!(decode_packet(&bundle).unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        assert!(OscMessage::decode(b"/a\0\0,i\0\0\0").is_err());
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpListener, TcpStream, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+use super::dns::{DnsMessage, Record, RecordData, TYPE_PTR};
+
+const MDNS_ADDRESS: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(224, 0, 0, 251), 5353);
+const OSC_JSON_SERVICE: &str = "_oscjson._tcp.local";
+const OSC_SERVICE: &str = "_osc._udp.local";
+const VRCHAT_INSTANCE_PREFIX: &str = "VRChat-Client-";
+const RECORD_TTL: u32 = 120;
+
+/// Answer to `?HOST_INFO` as defined by the `OSCQuery` proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct HostInfo {
+    pub name: String,
+    pub osc_ip: String,
+    pub osc_port: u16,
+    #[serde(default = "default_transport")]
+    pub osc_transport: String,
+}
+
+fn default_transport() -> String {
+    "UDP".to_string()
+}
+
+/// Looks for a `VRChat` client over mDNS and asks it where it receives OSC.
+pub fn discover_vrchat(timeout: Duration) -> Result<Option<SocketAddr>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.send_to(
+        &DnsMessage::query(OSC_JSON_SERVICE, TYPE_PTR).encode(),
+        MDNS_ADDRESS,
+    )?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buffer = [0; 4096];
+
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        socket.set_read_timeout(Some(remaining.max(Duration::from_millis(1))))?;
+
+        let (size, source) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let Ok(message) = DnsMessage::decode(&buffer[..size]) else {
+            continue;
+        };
+
+        let Some(http_address) = find_vrchat_service(&message, source) else {
+            continue;
+        };
+
+        let host_info = fetch_host_info(http_address)?;
+
+        log::info!(
+            "Discovered {} at {}:{}",
+            host_info.name,
+            host_info.osc_ip,
+            host_info.osc_port
+        );
+
+        let ip = host_info.osc_ip.parse()?;
+
+        return Ok(Some(SocketAddr::new(ip, host_info.osc_port)));
+    }
+
+    Ok(None)
+}
+
+/// Resolves the HTTP address of a `VRChat` `OSCQuery` service announced in `message`.
+fn find_vrchat_service(message: &DnsMessage, source: SocketAddr) -> Option<SocketAddr> {
+    let instance = message
+        .records
+        .iter()
+        .find_map(|record| match &record.data {
+            RecordData::Ptr(instance)
+                if record.name == OSC_JSON_SERVICE
+                    && instance.starts_with(VRCHAT_INSTANCE_PREFIX) =>
+            {
+                Some(instance)
+            }
+            _ => None,
+        })?;
+
+    let (port, target) = message
+        .records
+        .iter()
+        .find_map(|record| match &record.data {
+            RecordData::Srv { port, target } if &record.name == instance => Some((*port, target)),
+            _ => None,
+        })?;
+
+    let ip = message
+        .records
+        .iter()
+        .find_map(|record| match record.data {
+            RecordData::A(ip) if &record.name == target => Some(ip.into()),
+            _ => None,
+        })
+        .unwrap_or(source.ip());
+
+    Some(SocketAddr::new(ip, port))
+}
+
+fn fetch_host_info(address: SocketAddr) -> Result<HostInfo> {
+    let mut stream = TcpStream::connect_timeout(&address, Duration::from_secs(2))?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    write!(
+        stream,
+        "GET /?HOST_INFO HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n"
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    // Take the JSON object directly, so that chunked responses need no special handling
+    let body = response
+        .find('{')
+        .zip(response.rfind('}'))
+        .map(|(start, end)| &response[start..=end])
+        .ok_or_else(|| anyhow!("No host info in response from {address}"))?;
+
+    Ok(serde_json::from_str(body)?)
+}
+
+/// Announces our OSC receiver so that `OSCQuery` clients such as `VRChat` send parameters to it.
+///
+/// Serves the host info and node tree over HTTP, and answers mDNS queries for the service on a
+/// background thread.
+pub fn advertise(name: &str, osc_port: u16) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let http_port = listener.local_addr()?.port();
+
+    let host_info = HostInfo {
+        name: name.to_string(),
+        osc_ip: Ipv4Addr::LOCALHOST.to_string(),
+        osc_port,
+        osc_transport: default_transport(),
+    };
+
+    std::thread::spawn(move || {
+        crate::power::register_current_thread("oscquery_http");
+
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = serve_http(stream, &host_info) {
+                log::debug!("Failed to serve OSCQuery request: {e}");
+            }
+        }
+    });
+
+    let socket = bind_shared(SocketAddrV4::new(
+        Ipv4Addr::UNSPECIFIED,
+        MDNS_ADDRESS.port(),
+    ))?;
+    socket.join_multicast_v4(MDNS_ADDRESS.ip(), &Ipv4Addr::UNSPECIFIED)?;
+
+    let answers = service_records(name, http_port, osc_port);
+
+    std::thread::spawn(move || {
+        crate::power::register_current_thread("oscquery_mdns");
+
+        let mut buffer = [0; 4096];
+
+        loop {
+            let Ok((size, source)) = socket.recv_from(&mut buffer) else {
+                continue;
+            };
+
+            let Ok(query) = DnsMessage::decode(&buffer[..size]) else {
+                continue;
+            };
+
+            let is_asking_for_us = !query.is_response
+                && query.questions.iter().any(|question| {
+                    question.name == OSC_JSON_SERVICE || question.name == OSC_SERVICE
+                });
+
+            if !is_asking_for_us {
+                continue;
+            }
+
+            let response = DnsMessage {
+                is_response: true,
+                questions: vec![],
+                records: answers.clone(),
+            }
+            .encode();
+
+            let destination = if source.port() == MDNS_ADDRESS.port() {
+                SocketAddr::V4(MDNS_ADDRESS)
+            } else {
+                source
+            };
+
+            if let Err(e) = socket.send_to(&response, destination) {
+                log::debug!("Failed to answer mDNS query: {e}");
+            }
+        }
+    });
+
+    log::info!("Advertising OSCQuery service '{name}' on HTTP port {http_port}");
+
+    Ok(())
+}
+
+fn service_records(name: &str, http_port: u16, osc_port: u16) -> Vec<Record> {
+    let host = format!("{name}.local");
+
+    let mut records = vec![];
+
+    for (service, port) in [(OSC_JSON_SERVICE, http_port), (OSC_SERVICE, osc_port)] {
+        let instance = format!("{name}.{service}");
+
+        records.push(Record {
+            name: service.to_string(),
+            ttl: RECORD_TTL,
+            data: RecordData::Ptr(instance.clone()),
+        });
+        records.push(Record {
+            name: instance.clone(),
+            ttl: RECORD_TTL,
+            data: RecordData::Srv {
+                port,
+                target: host.clone(),
+            },
+        });
+        records.push(Record {
+            name: instance,
+            ttl: RECORD_TTL,
+            data: RecordData::Txt(vec!["txtvers=1".to_string()]),
+        });
+    }
+
+    records.push(Record {
+        name: host,
+        ttl: RECORD_TTL,
+        data: RecordData::A(Ipv4Addr::LOCALHOST),
+    });
+
+    records
+}
+
+fn serve_http(stream: TcpStream, host_info: &HostInfo) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let body = if target.ends_with("?HOST_INFO") {
+        serde_json::to_string(host_info)?
+    } else {
+        serde_json::to_string(&root_node())?
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    Ok(())
+}
+
+/// Node tree telling clients that we want to receive avatar parameters.
+fn root_node() -> serde_json::Value {
+    let avatar = serde_json::json!({
+        "FULL_PATH": "/avatar",
+        "ACCESS": 2,
+        "CONTENTS": HashMap::<String, serde_json::Value>::new(),
+    });
+
+    serde_json::json!({
+        "FULL_PATH": "/",
+        "ACCESS": 0,
+        "CONTENTS": { "avatar": avatar },
+    })
+}
+
+/// Binds a port which other mDNS responders on the machine hold as well, such as the DNS Client
+/// service on Windows, Bonjour or VRChat itself.
+fn bind_shared(address: SocketAddrV4) -> std::io::Result<UdpSocket> {
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV4,
+        socket2::Type::DGRAM,
+        Some(socket2::Protocol::UDP),
+    )?;
+    socket.set_reuse_address(true)?;
+    // Needed on top of the address on macOS and the BSDs
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::V4(address).into())?;
+
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_shared_twice() {
+        let first = bind_shared(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let SocketAddr::V4(address) = first.local_addr().unwrap() else {
+            panic!("Bound to an IPv6 address");
+        };
+
+        assert!(bind_shared(address).is_ok());
+    }
+
+    #[test]
+    fn test_find_vrchat_service() {
+        let message = DnsMessage {
+            is_response: true,
+            questions: vec![],
+            records: service_records("VRChat-Client-ABC123", 45678, 9000),
+        };
+
+        assert_eq!(
+            find_vrchat_service(&message, "192.168.0.2:5353".parse().unwrap()),
+            Some("127.0.0.1:45678".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_ignores_other_services() {
+        let message = DnsMessage {
+            is_response: true,
+            questions: vec![],
+            records: service_records("oscpie", 45678, 9001),
+        };
+
+        assert_eq!(
+            find_vrchat_service(&message, "192.168.0.2:5353".parse().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_host_info() {
+        let host_info: HostInfo = serde_json::from_str(
+            r#"{"NAME":"VRChat-Client-ABC123","OSC_IP":"127.0.0.1","OSC_PORT":9000,"OSC_TRANSPORT":"UDP"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(host_info.osc_port, 9000);
+    }
+
+    #[test]
+    fn test_serve_host_info() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let host_info = HostInfo {
+            name: "oscpie".to_string(),
+            osc_ip: "127.0.0.1".to_string(),
+            osc_port: 9001,
+            osc_transport: default_transport(),
+        };
+
+        let served = host_info.clone();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve_http(stream, &served).unwrap();
+        });
+
+        assert_eq!(fetch_host_info(address).unwrap(), host_info);
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::HashMap,
+    net::UdpSocket,
+    sync::{Arc, Mutex},
+};
+
+use super::{packet, OscValue};
+
+/// Listens for OSC messages on a background thread and keeps the latest value per address.
+pub struct OscReceiver {
+    parameters: Arc<Mutex<HashMap<String, OscValue>>>,
+}
+
+impl OscReceiver {
+    pub fn listen(port: u16) -> Result<Self, std::io::Error> {
+        let socket = UdpSocket::bind(("127.0.0.1", port))?;
+        let parameters = Arc::new(Mutex::new(HashMap::new()));
+
+        let thread_parameters = parameters.clone();
+
+        std::thread::spawn(move || {
+            crate::power::register_current_thread("osc_receiver");
+
+            let mut buffer = [0; 4096];
+
+            loop {
+                let size = match socket.recv(&mut buffer) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        log::warn!("Failed to receive OSC: {e}");
+                        continue;
+                    }
+                };
+
+                let messages = match packet::decode_packet(&buffer[..size]) {
+                    Ok(messages) => messages,
+                    Err(e) => {
+                        log::debug!("Ignoring malformed OSC packet: {e}");
+                        continue;
+                    }
+                };
+
+                let mut parameters = thread_parameters.lock().unwrap();
+
+                for message in messages {
+                    if let Some(value) = message.args.first() {
+                        parameters.insert(message.address, *value);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { parameters })
+    }
+
+    /// Latest value received for `address`, if any.
+    pub fn get(&self, address: &str) -> Option<OscValue> {
+        self.parameters.lock().unwrap().get(address).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc::OscSender;
+
+    #[test]
+    fn test_receive() {
+        let port = UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let receiver = OscReceiver::listen(port).unwrap();
+        let sender = OscSender::new("127.0.0.1", port).unwrap();
+
+        sender.send("/a", OscValue::Int(7)).unwrap();
+
+        for _ in 0..100 {
+            if let Some(value) = receiver.get("/a") {
+                assert_eq!(value, OscValue::Int(7));
+                return;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        panic!("Message was not received");
+    }
+}
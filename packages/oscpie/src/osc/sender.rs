@@ -0,0 +1,60 @@
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use super::{OscMessage, OscValue};
+
+#[derive(Debug)]
+pub struct OscSender {
+    socket: UdpSocket,
+    target: std::net::SocketAddr,
+}
+
+impl OscSender {
+    pub fn new(host: &str, port: u16) -> Result<Self, std::io::Error> {
+        let target = (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Could not resolve {host}"),
+            )
+        })?;
+
+        let socket = UdpSocket::bind(if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        })?;
+
+        Ok(Self { socket, target })
+    }
+
+    pub fn send(&self, address: &str, value: OscValue) -> Result<(), std::io::Error> {
+        let packet = OscMessage::new(address, vec![value]).encode();
+
+        self.socket.send_to(&packet, self.target)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(std::time::Duration::from_secs(1)))
+            .unwrap();
+
+        let sender = OscSender::new("127.0.0.1", receiver.local_addr().unwrap().port()).unwrap();
+        sender.send("/a", OscValue::Float(1.0)).unwrap();
+
+        let mut buffer = [0; 64];
+        let size = receiver.recv(&mut buffer).unwrap();
+
+        assert_eq!(
+            &buffer[..size],
+            OscMessage::new("/a", vec![OscValue::Float(1.0)]).encode()
+        );
+    }
+}
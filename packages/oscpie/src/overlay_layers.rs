@@ -0,0 +1,106 @@
+use crate::prelude::*;
+
+/// Kinds of overlays shown by oscpie, from bottom to top.
+///
+/// Overlays attached to the same controller overlap, so each layer gets a sort order and is moved
+/// slightly toward the viewer to keep them from z-fighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayLayer {
+    StatusWidget,
+    ImageViewer,
+    Menu,
+    Toast,
+}
+
+/// Distance between two adjacent layers in meters.
+const LAYER_SPACING: f32 = 0.002;
+
+impl OverlayLayer {
+    pub const ALL: [OverlayLayer; 4] = [
+        OverlayLayer::StatusWidget,
+        OverlayLayer::ImageViewer,
+        OverlayLayer::Menu,
+        OverlayLayer::Toast,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|layer| *layer == self).unwrap()
+    }
+
+    pub fn sort_order(self) -> u32 {
+        u32::try_from(self.index()).unwrap()
+    }
+
+    /// Moves `transform` toward the viewer by the layer's share of the spacing.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn offset(self, transform: Affine3A) -> Affine3A {
+        transform * Affine3A::from_translation(glam::Vec3::Z * LAYER_SPACING * self.index() as f32)
+    }
+
+    /// Layers which are hidden while this one is shown.
+    fn suppresses(self) -> &'static [OverlayLayer] {
+        match self {
+            // Both take the place the widgets are shown at
+            OverlayLayer::Menu | OverlayLayer::ImageViewer => &[OverlayLayer::StatusWidget],
+            OverlayLayer::StatusWidget | OverlayLayer::Toast => &[],
+        }
+    }
+}
+
+/// Decides which of the requested overlays are actually shown.
+#[derive(Debug, Default)]
+pub struct OverlayStack {
+    requested: [bool; OverlayLayer::ALL.len()],
+}
+
+impl OverlayStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request(&mut self, layer: OverlayLayer, visible: bool) {
+        self.requested[layer.index()] = visible;
+    }
+
+    pub fn is_visible(&self, layer: OverlayLayer) -> bool {
+        self.requested[layer.index()]
+            && !OverlayLayer::ALL
+                .iter()
+                .any(|other| self.requested[other.index()] && other.suppresses().contains(&layer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_order() {
+        assert!(OverlayLayer::StatusWidget.sort_order() < OverlayLayer::Menu.sort_order());
+        assert!(OverlayLayer::Menu.sort_order() < OverlayLayer::Toast.sort_order());
+    }
+
+    #[test]
+    fn test_offset_moves_toward_viewer() {
+        let menu = OverlayLayer::Menu.offset(Affine3A::IDENTITY).translation.z;
+        let toast = OverlayLayer::Toast.offset(Affine3A::IDENTITY).translation.z;
+
+        assert!(toast > menu);
+    }
+
+    #[test]
+    fn test_menu_hides_status_widget() {
+        let mut stack = OverlayStack::new();
+        stack.request(OverlayLayer::StatusWidget, true);
+        stack.request(OverlayLayer::Toast, true);
+        assert!(stack.is_visible(OverlayLayer::StatusWidget));
+
+        stack.request(OverlayLayer::Menu, true);
+        assert!(stack.is_visible(OverlayLayer::Menu));
+        assert!(stack.is_visible(OverlayLayer::Toast));
+        assert!(!stack.is_visible(OverlayLayer::StatusWidget));
+
+        stack.request(OverlayLayer::Menu, false);
+        assert!(stack.is_visible(OverlayLayer::StatusWidget));
+    }
+}
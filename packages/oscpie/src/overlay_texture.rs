@@ -0,0 +1,64 @@
+//! Handing the rendered menu to SteamVR as the overlay's texture.
+
+use tiny_skia::Pixmap;
+
+use crate::{
+    config::types::{OverlayConfig, OverlayTexture},
+    d3d11,
+    dirty_rows::DirtyRows,
+    openvr::{self, CompositorInterface, Handle, Overlay, SystemInterface},
+    prelude::*,
+    vulkan,
+};
+
+/// Uploads the menu into a texture and shows it on the overlay.
+pub trait OverlayTextureUploader {
+    /// Uploads `rows` of `pixmap` and sets the texture on `overlay`. The rest of the texture keeps
+    /// what was uploaded before.
+    fn upload(&mut self, overlay: &Overlay, pixmap: &Pixmap, rows: DirtyRows) -> Result<()>;
+
+    /// Replaces the texture with one the size of `pixmap`, which is uploaded from now on.
+    fn resize(&mut self, pixmap: &Pixmap, mipmaps: bool) -> Result<()>;
+}
+
+impl OverlayTextureUploader for vulkan::ImageUploader {
+    fn upload(&mut self, overlay: &Overlay, pixmap: &Pixmap, rows: DirtyRows) -> Result<()> {
+        let image = vulkan::ImageUploader::upload(self, pixmap, rows)?;
+
+        overlay.set_overlay_texture(&mut openvr::Texture {
+            handle: openvr::TextureHandle::Vulkan(image.as_ref(), self.queue()),
+            texture_type: openvr::TextureType::Vulkan,
+            color_space: openvr::ColorSpace::Auto,
+        })
+    }
+
+    fn resize(&mut self, pixmap: &Pixmap, mipmaps: bool) -> Result<()> {
+        vulkan::ImageUploader::resize(self, pixmap, mipmaps)
+    }
+}
+
+/// Creates the uploader `config` asks for. `pixmap` is the one the menu is rendered into.
+pub fn create(
+    config: &OverlayConfig,
+    pixmap: &Pixmap,
+    compositor: &Handle<CompositorInterface>,
+    system: &Handle<SystemInterface>,
+) -> Result<Box<dyn OverlayTextureUploader>> {
+    let mipmaps = config.supersampling > 1;
+
+    match config.texture {
+        OverlayTexture::Vulkan => Ok(Box::new(vulkan::ImageUploader::new(
+            vulkan::Context::new(
+                &vulkan::OpenVrRequirements { compositor, system },
+                config.gpu.as_ref(),
+            )?,
+            pixmap,
+            mipmaps,
+        )?)),
+        OverlayTexture::DirectX => Ok(Box::new(d3d11::D3d11Uploader::new(
+            pixmap,
+            system.dxgi_adapter_index(),
+            mipmaps,
+        )?)),
+    }
+}
@@ -0,0 +1,46 @@
+//! Extrapolates the controller pose, so that the menu does not trail behind a fast moving hand.
+
+use glam::{Affine3A, Mat3A, Quat, Vec3, Vec3A};
+
+/// Moves `transform` along `velocity` and turns it by `angular_velocity` for `secs`. Both
+/// velocities are in tracking space, as OpenVR reports them.
+pub fn predict(
+    transform: Affine3A,
+    velocity: Vec3A,
+    angular_velocity: Vec3A,
+    secs: f32,
+) -> Affine3A {
+    let rotation = Quat::from_scaled_axis(Vec3::from(angular_velocity * secs));
+
+    Affine3A {
+        matrix3: Mat3A::from_quat(rotation) * transform.matrix3,
+        translation: transform.translation + velocity * secs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn test_predict() {
+        let transform = Affine3A::from_translation(Vec3::new(1.0, 2.0, 3.0));
+
+        let still = predict(transform, Vec3A::ZERO, Vec3A::ZERO, 0.05);
+        assert!(still.abs_diff_eq(transform, 1e-6));
+
+        let moved = predict(transform, Vec3A::new(2.0, 0.0, 0.0), Vec3A::ZERO, 0.05);
+        assert!(moved
+            .translation
+            .abs_diff_eq(Vec3A::new(1.1, 2.0, 3.0), 1e-6));
+
+        // A quarter turn per second around Y, for a second: forward (-Z) becomes left (-X)
+        let turned = predict(transform, Vec3A::ZERO, Vec3A::new(0.0, FRAC_PI_2, 0.0), 1.0);
+        assert!(turned.translation.abs_diff_eq(transform.translation, 1e-6));
+        assert!(turned
+            .transform_vector3a(Vec3A::NEG_Z)
+            .abs_diff_eq(Vec3A::NEG_X, 1e-6));
+    }
+}
@@ -0,0 +1,159 @@
+//! CPU usage accounting for the long running threads, and the idle detection used to put the
+//! overlay to sleep while nobody wears the headset.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, FILETIME, HANDLE},
+    System::Threading::{
+        GetCurrentThreadId, GetThreadTimes, OpenThread, THREAD_QUERY_LIMITED_INFORMATION,
+    },
+};
+
+use crate::openvr::DeviceActivityLevel;
+
+/// Polling interval while asleep.
+pub const SLEEP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+struct ThreadHandle(HANDLE);
+
+// The handle is only used to query thread times, which is allowed from any thread
+unsafe impl Send for ThreadHandle {}
+
+impl Drop for ThreadHandle {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.0);
+        }
+    }
+}
+
+struct ServiceThread {
+    name: &'static str,
+    handle: ThreadHandle,
+}
+
+static THREADS: Mutex<Vec<ServiceThread>> = Mutex::new(Vec::new());
+
+/// Adds the calling thread to the power report under `name`.
+pub fn register_current_thread(name: &'static str) {
+    let handle = unsafe { OpenThread(THREAD_QUERY_LIMITED_INFORMATION, 0, GetCurrentThreadId()) };
+
+    if handle.is_null() {
+        log::debug!("Failed to open thread '{name}' for CPU accounting");
+        return;
+    }
+
+    THREADS.lock().unwrap().push(ServiceThread {
+        name,
+        handle: ThreadHandle(handle),
+    });
+}
+
+/// Kernel and user time the thread has spent so far.
+fn cpu_time(handle: &ThreadHandle) -> Option<Duration> {
+    let mut creation = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let mut exit = creation;
+    let mut kernel = creation;
+    let mut user = creation;
+
+    let ok = unsafe { GetThreadTimes(handle.0, &mut creation, &mut exit, &mut kernel, &mut user) };
+
+    (ok != 0).then(|| filetime_to_duration(kernel) + filetime_to_duration(user))
+}
+
+/// `FILETIME` counts in 100ns units.
+fn filetime_to_duration(time: FILETIME) -> Duration {
+    let ticks = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Duration::from_nanos(ticks * 100)
+}
+
+fn cpu_times() -> Vec<(&'static str, Option<Duration>)> {
+    THREADS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|thread| (thread.name, cpu_time(&thread.handle)))
+        .collect()
+}
+
+/// Share of one core used over `wall`, in percent.
+pub fn usage_percent(cpu: Duration, wall: Duration) -> f64 {
+    if wall.is_zero() {
+        return 0.0;
+    }
+
+    cpu.as_secs_f64() / wall.as_secs_f64() * 100.0
+}
+
+/// Measures the CPU usage of every registered thread over `duration` and prints it.
+pub fn report(duration: Duration) {
+    let before = cpu_times();
+    let start = Instant::now();
+
+    std::thread::sleep(duration);
+
+    let after = cpu_times();
+    let wall = start.elapsed();
+
+    println!("CPU usage over {:.1}s:", wall.as_secs_f64());
+
+    for ((name, before), (_, after)) in before.iter().zip(after.iter()) {
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                let cpu = after.saturating_sub(*before);
+                println!(
+                    "  {name}: {:.2}% ({}ms)",
+                    usage_percent(cpu, wall),
+                    cpu.as_millis()
+                );
+            }
+            _ => println!("  {name}: unavailable"),
+        }
+    }
+}
+
+/// Prints the power report after `duration` on a background thread, then exits the process.
+pub fn spawn_report(duration: Duration) {
+    std::thread::spawn(move || {
+        report(duration);
+        std::process::exit(0);
+    });
+}
+
+/// Whether the headset is not being worn, so that the overlay can stop rendering.
+pub fn is_idle(level: DeviceActivityLevel) -> bool {
+    matches!(
+        level,
+        DeviceActivityLevel::Idle | DeviceActivityLevel::Standby | DeviceActivityLevel::IdleTimeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_percent() {
+        assert!(
+            (usage_percent(Duration::from_millis(250), Duration::from_secs(1)) - 25.0).abs() < 1e-9
+        );
+        assert!(usage_percent(Duration::from_millis(250), Duration::ZERO).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_filetime_to_duration() {
+        let time = FILETIME {
+            dwLowDateTime: 10_000_000,
+            dwHighDateTime: 0,
+        };
+
+        assert_eq!(filetime_to_duration(time), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use oscpie_ui::{sprite::SpriteSheet, story::render_png};
+
+use crate::{
+    components::{pie_menu::Props, MenuComponent, REFERENCE_SIZE},
+    config,
+    prelude::*,
+    resource::{Animations, ANIMATIONS, SPRITE_SHEET},
+    theme::Theme,
+};
+
+/// Frames simulated before capturing, so that item animations have settled.
+const SETTLE_FRAMES: u32 = 20;
+const FRAME_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// Renders every menu of the config at `config_path` into `output_dir` as PNGs.
+///
+/// Menus are visited from the root through their sub menus, so that each stack level is rendered
+/// as it appears in VR, e.g. `root-tools.png` with a back item. Returns the number of images.
+pub fn render_previews(config_path: &str, output_dir: &Path) -> Result<usize> {
+    let config = config::load(config_path)?;
+
+    SPRITE_SHEET
+        .set(
+            SpriteSheet::load(resolve_path(config_path, &config.sprite_sheet))
+                .map_err(|e| anyhow!(e))?,
+        )
+        .map_err(|_| anyhow!("Sprite sheet is already loaded"))?;
+
+    ANIMATIONS
+        .set(Animations::from_config(&config.animations))
+        .map_err(|_| anyhow!("Animations are already loaded"))?;
+
+    std::fs::create_dir_all(output_dir)?;
+
+    let theme = Theme::from_config(&config.theme);
+
+    let mut stacks = vec![];
+    collect_stacks(&config, std::slice::from_ref(&config.root), &mut stacks);
+
+    let mut profiles: Vec<_> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| *name);
+
+    for (_, profile) in profiles {
+        collect_stacks(&config, std::slice::from_ref(&profile.root), &mut stacks);
+    }
+
+    for id in config.menus.keys() {
+        if !stacks.iter().any(|stack| stack.contains(id)) {
+            log::warn!("Menu '{}' is not reachable from the root", id.inner());
+            stacks.push(vec![id.clone()]);
+        }
+    }
+
+    for stack in &stacks {
+        let name = stack
+            .iter()
+            .map(config::types::MenuId::inner)
+            .collect::<Vec<_>>()
+            .join("-");
+
+        let menu = Menu::from_config(&config.menus[stack.last().unwrap()]);
+        let menu = if stack.len() > 1 {
+            menu.with_back_item()
+        } else {
+            menu
+        };
+
+        let path = output_dir.join(format!("{name}.png"));
+
+        render_png(&path, |pixmap| render_menu(&menu, theme, pixmap))?;
+
+        println!("{}", path.display());
+    }
+
+    Ok(stacks.len())
+}
+
+fn collect_stacks(
+    config: &config::Config,
+    stack: &[config::types::MenuId],
+    stacks: &mut Vec<Vec<config::types::MenuId>>,
+) {
+    let Some(menu) = config.menus.get(stack.last().unwrap()) else {
+        log::warn!("Menu '{}' does not exist", stack.last().unwrap().inner());
+        return;
+    };
+
+    if stacks.iter().any(|known| known == stack) {
+        return;
+    }
+
+    stacks.push(stack.to_vec());
+
+    for item in &menu.items {
+        if let config::types::MenuItemAction::SubMenu { to } = &item.action {
+            // Menus may refer to their ancestors, which would never end
+            if stack.contains(to) {
+                continue;
+            }
+
+            let mut next = stack.to_vec();
+            next.push(to.clone());
+            collect_stacks(config, &next, stacks);
+        }
+    }
+}
+
+fn render_menu(menu: &Menu, theme: Theme, pixmap: &mut tiny_skia::Pixmap) {
+    let mut pie_menu = MenuComponent::new(
+        menu,
+        &ActionContext::new(std::sync::mpsc::channel().0),
+        theme,
+        REFERENCE_SIZE,
+    );
+
+    for _ in 0..SETTLE_FRAMES {
+        pie_menu.update(&Props::new(PieMenuInput::new(0.0, 0.0, 0.0)));
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+
+    pie_menu.render(pixmap);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_stacks() {
+        let config: config::Config = serde_json::from_str(
+            r#"{
+                "root": "root",
+                "sprite_sheet": "",
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "tools" } },
+                            { "action": { "type": "SubMenu", "to": "tools" } },
+                            { "action": { "type": "SubMenu", "to": "root" } }
+                        ]
+                    },
+                    "tools": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "root" } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut stacks = vec![];
+        collect_stacks(&config, std::slice::from_ref(&config.root), &mut stacks);
+
+        let names: Vec<Vec<&str>> = stacks
+            .iter()
+            .map(|stack| stack.iter().map(config::types::MenuId::inner).collect())
+            .collect();
+
+        assert_eq!(names, vec![vec!["root"], vec!["root", "tools"]]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Registers oscpie with SteamVR, so that it shows up in its settings and can launch along with it.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::openvr::{self, ApplicationsInterface, Handle};
+
+pub const APP_KEY: &str = "cathiecode.oscpie";
+
+/// Manifest next to the executable, written on registration since it names the executable's path.
+fn manifest_path() -> Result<PathBuf> {
+    Ok(std::env::current_exe()?.with_file_name("oscpie.vrmanifest"))
+}
+
+fn manifest_json(binary_path: &Path) -> Result<String> {
+    let manifest = serde_json::json!({
+        "source": "builtin",
+        "applications": [{
+            "app_key": APP_KEY,
+            "launch_type": "binary",
+            "binary_path_windows": binary_path.to_string_lossy(),
+            "is_dashboard_overlay": true,
+            "strings": {
+                "en_us": {
+                    "name": "OSCPie",
+                    "description": "Pie menu overlay for VR",
+                },
+            },
+        }],
+    });
+
+    Ok(serde_json::to_string_pretty(&manifest)?)
+}
+
+fn write_manifest() -> Result<PathBuf> {
+    let path = manifest_path()?;
+    std::fs::write(&path, manifest_json(&std::env::current_exe()?)?)?;
+
+    Ok(path)
+}
+
+/// Registers the application when SteamVR does not know it yet, leaving auto start as the user
+/// set it otherwise.
+pub fn register_on_first_run(applications: &Handle<ApplicationsInterface>) -> Result<()> {
+    if applications.is_application_installed(APP_KEY)? {
+        return Ok(());
+    }
+
+    log::info!("Registering {APP_KEY} with SteamVR");
+    applications.add_application_manifest(&write_manifest()?, false)
+}
+
+/// Registers the application and launches it along with SteamVR from now on.
+pub fn register(applications: &Handle<ApplicationsInterface>) -> Result<()> {
+    applications.add_application_manifest(&write_manifest()?, false)?;
+    applications.set_auto_start(APP_KEY, true)
+}
+
+pub fn unregister(applications: &Handle<ApplicationsInterface>) -> Result<()> {
+    if applications.is_application_installed(APP_KEY)? {
+        applications.set_auto_start(APP_KEY, false)?;
+    }
+
+    applications.remove_application_manifest(&manifest_path()?)
+}
+
+/// Runs `--register` or `--unregister` without starting the overlay.
+pub fn run(should_register: bool) -> Result<()> {
+    let openvr = openvr::Handle::<openvr::OpenVr>::new(openvr::EVRApplicationType::Utility)?;
+    let applications = openvr.applications()?;
+
+    if should_register {
+        register(&applications)
+    } else {
+        unregister(&applications)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_names_the_binary() {
+        let json = manifest_json(Path::new("C:\\oscpie\\oscpie.exe")).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let application = &manifest["applications"][0];
+
+        assert_eq!(application["app_key"], APP_KEY);
+        assert_eq!(application["binary_path_windows"], "C:\\oscpie\\oscpie.exe");
+    }
+}
@@ -1,9 +1,107 @@
-use std::sync::OnceLock;
-
-use crate::sprite::SpriteSheet;
-
-pub static SPRITE_SHEET: OnceLock<SpriteSheet> = OnceLock::new();
-
-pub fn get_sprite_sheet() -> Option<&'static SpriteSheet> {
-    SPRITE_SHEET.get()
-}
+use std::sync::OnceLock;
+
+use oscpie_ui::{
+    animation::{Easing, Transition},
+    font::Font,
+    sprite::SpriteSheet,
+};
+
+use crate::{action_behaviours::custom::BehaviourRegistry, config};
+
+pub static SPRITE_SHEET: OnceLock<SpriteSheet> = OnceLock::new();
+pub static ANIMATIONS: OnceLock<Animations> = OnceLock::new();
+pub static BEHAVIOURS: OnceLock<BehaviourRegistry> = OnceLock::new();
+pub static LABELS: OnceLock<Labels> = OnceLock::new();
+
+pub fn get_sprite_sheet() -> Option<&'static SpriteSheet> {
+    SPRITE_SHEET.get()
+}
+
+/// Transitions used by the components, the defaults until the config has been loaded.
+pub fn get_animations() -> &'static Animations {
+    ANIMATIONS.get_or_init(Animations::default)
+}
+
+/// Font of the item labels, unset when none could be loaded and labels are hidden.
+pub fn get_labels() -> Option<&'static Labels> {
+    LABELS.get()
+}
+
+/// Behaviours registered with [`crate::CoreBuilder`], the built-in ones until it runs.
+pub fn get_behaviours() -> &'static BehaviourRegistry {
+    BEHAVIOURS.get_or_init(BehaviourRegistry::with_builtins)
+}
+
+pub struct Labels {
+    pub font: Font,
+    /// Size of the labels under the icons in pixels.
+    pub size: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Animations {
+    pub icon_grow: Transition,
+    pub spin_icon: Transition,
+    pub menu_transition: Transition,
+}
+
+impl Default for Animations {
+    fn default() -> Self {
+        Self {
+            icon_grow: Transition::Smooth { speed: 20.0 },
+            spin_icon: Transition::Smooth { speed: 10.0 },
+            menu_transition: Transition::Tween {
+                duration: 0.25,
+                easing: Easing::EASE_OUT,
+            },
+        }
+    }
+}
+
+impl Animations {
+    pub fn from_config(animations: &config::types::Animations) -> Self {
+        let default = Self::default();
+
+        Self {
+            icon_grow: animations
+                .icon_grow
+                .map_or(default.icon_grow, transition_from_config),
+            spin_icon: animations
+                .spin_icon
+                .map_or(default.spin_icon, transition_from_config),
+            menu_transition: animations
+                .menu_transition
+                .map_or(default.menu_transition, transition_from_config),
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn transition_from_config(transition: config::types::Transition) -> Transition {
+    match transition {
+        config::types::Transition::Smooth { speed } => Transition::Smooth { speed },
+        config::types::Transition::Tween {
+            duration_ms,
+            easing,
+        } => Transition::Tween {
+            duration: duration_ms as f32 / 1000.0,
+            easing: easing_from_config(easing),
+        },
+    }
+}
+
+fn easing_from_config(easing: config::types::Easing) -> Easing {
+    match easing {
+        config::types::Easing::Linear => Easing::Linear,
+        config::types::Easing::Ease => Easing::EASE,
+        config::types::Easing::EaseIn => Easing::EASE_IN,
+        config::types::Easing::EaseOut => Easing::EASE_OUT,
+        config::types::Easing::EaseInOut => Easing::EASE_IN_OUT,
+        config::types::Easing::CubicBezier { x1, y1, x2, y2 } => {
+            Easing::CubicBezier { x1, y1, x2, y2 }
+        }
+        config::types::Easing::Spring { stiffness, damping } => {
+            Easing::Spring { stiffness, damping }
+        }
+    }
+}
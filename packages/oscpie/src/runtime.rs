@@ -0,0 +1,128 @@
+//! VR runtimes oscpie runs on. The main loop only talks to [`XrRuntime`], so that it is not tied
+//! to SteamVR.
+
+mod openvr_runtime;
+#[cfg(feature = "openxr")]
+mod openxr_runtime;
+
+use std::collections::HashMap;
+
+use tiny_skia::Pixmap;
+
+use crate::{
+    config::{types::OverlayConfig, Config},
+    devices::DeviceInfo,
+    dirty_rows::DirtyRows,
+    hand::Hand,
+    openvr::OverlayEvent,
+    prelude::*,
+};
+
+pub use openvr_runtime::OpenVrRuntime;
+#[cfg(feature = "openxr")]
+pub use openxr_runtime::OpenXrRuntime;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuntimeEvent {
+    /// The runtime is shutting down, oscpie has to exit.
+    Quit,
+    /// Sent to the menu overlay, such as mouse input from a laser pointer.
+    Overlay(OverlayEvent),
+}
+
+/// Pose of a controller in tracking space.
+#[derive(Debug, Clone, Copy)]
+pub struct HandPose {
+    pub transform: Affine3A,
+    /// In meters per second.
+    pub velocity: Vec3A,
+    /// In radians per second.
+    pub angular_velocity: Vec3A,
+}
+
+/// Controller state of the hand driving the menu.
+#[derive(Debug, Clone, Copy)]
+pub struct HandInput {
+    pub click: bool,
+    /// Stick position, from `-1.0` to `1.0` on both axes.
+    pub select: Vec2,
+    /// `None` while the controller is not tracked.
+    pub pose: Option<HandPose>,
+}
+
+/// What oscpie needs from a VR runtime. Features a runtime lacks, such as notifications, default
+/// to doing nothing.
+pub trait XrRuntime {
+    fn poll_events(&mut self) -> Result<Vec<RuntimeEvent>>;
+
+    /// Whether the headset is unused, which puts oscpie to sleep.
+    fn is_idle(&self) -> bool {
+        false
+    }
+
+    /// Reads the controllers, returning the state of the
+    /// [digital actions](crate::input_expression::DIGITAL_ACTIONS).
+    fn update_input(&mut self) -> Result<HashMap<&'static str, bool>>;
+
+    /// Controller state of `hand` as of the last [`XrRuntime::update_input`].
+    fn hand_input(&self, hand: Hand) -> Result<HandInput>;
+
+    fn hmd_pose(&self) -> Option<Affine3A>;
+
+    /// Moves the menu along with `hand`, whose predicted pose is `transform`. Returns where the
+    /// menu is in tracking space, which runtimes attaching it to a device decide themselves.
+    fn place_menu(&mut self, hand: Hand, transform: Option<Affine3A>) -> Result<Option<Affine3A>>;
+
+    /// Application key of the app rendering the scene, for switching profiles.
+    fn scene_application_key(&self) -> Option<String> {
+        None
+    }
+
+    fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
+    /// Shows a notification outside of the menu.
+    fn notify(&self, _message: &str, _icon: Option<&Pixmap>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the menu shows or hides, so that the controls it uses, such as the stick, are
+    /// only taken from the game while it is open.
+    fn set_menu_open(&mut self, _is_open: bool) -> Result<()> {
+        Ok(())
+    }
+
+    /// Shows `pixmap` as the menu, or hides the menu. Only `dirty` rows changed since the previous
+    /// frame, none when it is `None`.
+    fn submit(&mut self, pixmap: &Pixmap, dirty: Option<DirtyRows>, visible: bool) -> Result<()>;
+
+    /// Called after `pixmap` was replaced with one of a different size, when the resolution in the
+    /// config changed to `config`.
+    fn resize(&mut self, pixmap: &Pixmap, config: &OverlayConfig) -> Result<()>;
+
+    /// Called when the overlay section of the config changed to `config` on a reload, to update
+    /// the size and look of the menu.
+    fn apply_overlay_config(&mut self, _config: &OverlayConfig) -> Result<()> {
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<()>;
+
+    /// Waits until the runtime is ready for the next frame.
+    fn wait_frame(&mut self) -> Result<()>;
+}
+
+/// Starts the runtime selected in `config`. `pixmap` is the one the menu is rendered into every
+/// frame.
+pub fn create(config: &Config, pixmap: &Pixmap) -> Result<Box<dyn XrRuntime>> {
+    match config.runtime {
+        crate::config::types::Runtime::OpenVr => Ok(Box::new(OpenVrRuntime::new(config, pixmap)?)),
+        #[cfg(feature = "openxr")]
+        crate::config::types::Runtime::OpenXr => Ok(Box::new(OpenXrRuntime::new(config, pixmap)?)),
+        #[cfg(not(feature = "openxr"))]
+        crate::config::types::Runtime::OpenXr => Err(anyhow!(
+            "OpenXR support is not built in, rebuild with the `openxr` feature"
+        )),
+    }
+}
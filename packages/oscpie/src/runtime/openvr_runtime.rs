@@ -0,0 +1,260 @@
+//! SteamVR, through OpenVR overlays.
+
+use std::collections::HashMap;
+
+use tiny_skia::Pixmap;
+
+use super::{HandInput, HandPose, RuntimeEvent, XrRuntime};
+use crate::{
+    attachment::Attachment,
+    config::{self, Config},
+    devices::{self, DeviceInfo},
+    dirty_rows::DirtyRows,
+    gesture_input::GestureInput,
+    hand::Hand,
+    openvr::{
+        self, input::Input, ApplicationsInterface, Handle, NotificationsInterface, Overlay,
+        SystemInterface,
+    },
+    overlay_layers::OverlayLayer,
+    overlay_texture::{self, OverlayTextureUploader},
+    power,
+    prelude::*,
+    registration, theme,
+};
+
+pub struct OpenVrRuntime {
+    overlay: Overlay,
+    system: Handle<SystemInterface>,
+    applications: Handle<ApplicationsInterface>,
+    notifications: Handle<NotificationsInterface>,
+    input: Input,
+    uploader: Box<dyn OverlayTextureUploader>,
+    attachment: Option<Attachment>,
+    /// Whether SteamVR's laser pointer sends mouse events, whose scale follows the menu's size.
+    has_mouse_input: bool,
+}
+
+impl OpenVrRuntime {
+    pub fn new(config: &Config, pixmap: &Pixmap) -> Result<Self> {
+        let openvr = openvr::Handle::<openvr::OpenVr>::new(openvr::EVRApplicationType::Overlay)?;
+        let overlay_interface = openvr.overlay()?;
+        let compositor = openvr.compositor()?;
+        let system = openvr.system()?;
+        let applications = openvr.applications()?;
+        let notifications = openvr.notifications()?;
+
+        if let Err(e) = registration::register_on_first_run(&applications) {
+            log::warn!("Failed to register with SteamVR: {e}");
+        }
+
+        let action_manifest_path = resolve_path("config", "action_manifests.json");
+
+        let mut input = openvr.input(&action_manifest_path)?;
+
+        input.activate_set("main", 0)?;
+        let overlay = overlay_interface.create("oscpie_overlay", "OSCPie Overlay")?;
+        apply_overlay_config(&overlay, &config.overlay)?;
+        overlay.show()?;
+        let uploader = overlay_texture::create(&config.overlay, pixmap, &compositor, &system)?;
+
+        let has_mouse_input = config.selection.mode == config::types::SelectionMode::Laser;
+
+        if has_mouse_input {
+            enable_mouse_input(&overlay, pixmap)?;
+        }
+
+        Ok(Self {
+            overlay,
+            system,
+            applications,
+            notifications,
+            input,
+            uploader,
+            attachment: config.attachment.as_ref().map(Attachment::from_config),
+            has_mouse_input,
+        })
+    }
+}
+
+impl XrRuntime for OpenVrRuntime {
+    fn poll_events(&mut self) -> Result<Vec<RuntimeEvent>> {
+        let mut events = Vec::new();
+
+        while let Some(event) = self.system.poll_next_event() {
+            if event == openvr::SystemEvent::Quit {
+                self.system.acknowledge_quit_exiting();
+                events.push(RuntimeEvent::Quit);
+            }
+        }
+
+        while let Some(event) = self.overlay.poll_next_event() {
+            events.push(RuntimeEvent::Overlay(event));
+        }
+
+        Ok(events)
+    }
+
+    fn is_idle(&self) -> bool {
+        power::is_idle(self.system.hmd_activity_level())
+    }
+
+    fn update_input(&mut self) -> Result<HashMap<&'static str, bool>> {
+        self.input.update()?;
+
+        let input = &self.input;
+        let left = GestureInput::from_skeleton(&input.skeleton("SkeletonLeft")?);
+        let right = GestureInput::from_skeleton(&input.skeleton("SkeletonRight")?);
+
+        Ok(HashMap::from([
+            ("OpenLeft", input.digital("OpenLeft")?.state),
+            ("ClickLeft", input.digital("ClickLeft")?.state),
+            ("GripLeft", input.digital("GripLeft")?.state),
+            ("FistLeft", left.is_fist),
+            ("FingerGunLeft", left.is_finger_gun),
+            ("OpenRight", input.digital("OpenRight")?.state),
+            ("ClickRight", input.digital("ClickRight")?.state),
+            ("GripRight", input.digital("GripRight")?.state),
+            ("FistRight", right.is_fist),
+            ("FingerGunRight", right.is_finger_gun),
+        ]))
+    }
+
+    fn hand_input(&self, hand: Hand) -> Result<HandInput> {
+        let input = &self.input;
+
+        let (click, select, pose) = match hand {
+            Hand::Left => (
+                input.digital("ClickLeft")?,
+                input.vector2("SelectLeft")?,
+                input.pose(
+                    "PoseLeft",
+                    openvr::TrackingUniverseOrigin::RawAndUncalibrated,
+                )?,
+            ),
+            Hand::Right => (
+                input.digital("ClickRight")?,
+                input.vector2("SelectRight")?,
+                input.pose(
+                    "PoseRight",
+                    openvr::TrackingUniverseOrigin::RawAndUncalibrated,
+                )?,
+            ),
+        };
+
+        Ok(HandInput {
+            click: click.state,
+            select: select.value,
+            pose: pose.pose.filter(|_| pose.active).map(|transform| HandPose {
+                transform,
+                velocity: pose.velocity,
+                angular_velocity: pose.angular_velocity,
+            }),
+        })
+    }
+
+    fn hmd_pose(&self) -> Option<Affine3A> {
+        self.system
+            .hmd_pose(openvr::TrackingUniverseOrigin::RawAndUncalibrated)
+    }
+
+    fn place_menu(&mut self, hand: Hand, transform: Option<Affine3A>) -> Result<Option<Affine3A>> {
+        if let Some(attachment) = &mut self.attachment {
+            return attachment.update(&self.overlay, &self.system, hand);
+        }
+
+        let Some(transform) = transform else {
+            return Ok(None);
+        };
+
+        let transform = OverlayLayer::Menu.offset(transform);
+
+        self.overlay.set_overlay_transform_absolute(
+            openvr::TrackingUniverseOrigin::RawAndUncalibrated,
+            transform,
+        )?;
+
+        Ok(Some(transform))
+    }
+
+    fn scene_application_key(&self) -> Option<String> {
+        self.applications.scene_application_key()
+    }
+
+    fn devices(&self) -> Vec<DeviceInfo> {
+        devices::enumerate(&self.system)
+    }
+
+    fn notify(&self, message: &str, icon: Option<&Pixmap>) -> Result<()> {
+        let icon = icon.map(openvr::NotificationBitmap::from_pixmap);
+
+        self.notifications
+            .create_notification(&self.overlay, message, icon.as_ref())
+    }
+
+    fn set_menu_open(&mut self, is_open: bool) -> Result<()> {
+        if is_open {
+            self.input
+                .activate_set("menu", openvr::input::OVERLAY_GLOBAL_PRIORITY)
+        } else {
+            self.input.deactivate_set("menu")
+        }
+    }
+
+    fn submit(&mut self, pixmap: &Pixmap, dirty: Option<DirtyRows>, visible: bool) -> Result<()> {
+        // SteamVR keeps showing the texture it was given last
+        if let Some(rows) = dirty {
+            self.uploader.upload(&self.overlay, pixmap, rows)?;
+        }
+
+        if visible {
+            self.overlay.show()
+        } else {
+            self.overlay.hide()
+        }
+    }
+
+    fn resize(&mut self, pixmap: &Pixmap, config: &config::types::OverlayConfig) -> Result<()> {
+        self.uploader.resize(pixmap, config.supersampling > 1)?;
+
+        if self.has_mouse_input {
+            enable_mouse_input(&self.overlay, pixmap)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_overlay_config(&mut self, config: &config::types::OverlayConfig) -> Result<()> {
+        apply_overlay_config(&self.overlay, config)
+    }
+
+    fn hide(&mut self) -> Result<()> {
+        self.overlay.hide()
+    }
+
+    fn wait_frame(&mut self) -> Result<()> {
+        self.overlay.wait_frame_sync(100)
+    }
+}
+
+/// Scales the laser pointer's mouse events to the pixels of `pixmap`.
+#[allow(clippy::cast_precision_loss)]
+fn enable_mouse_input(overlay: &Overlay, pixmap: &Pixmap) -> Result<()> {
+    overlay.enable_mouse_input(pixmap.width() as f32, pixmap.height() as f32)
+}
+
+fn apply_overlay_config(overlay: &Overlay, config: &config::types::OverlayConfig) -> Result<()> {
+    overlay.set_sort_order(config.sort_order.unwrap_or(OverlayLayer::Menu.sort_order()))?;
+    overlay.set_width_in_meters(config.width_m)?;
+    overlay.set_alpha(config.alpha.clamp(0.0, 1.0))?;
+    overlay.set_curvature(config.curvature.clamp(0.0, 1.0))?;
+
+    if let Some(tint) = &config.tint {
+        match theme::parse_hex_color(tint) {
+            Some(color) => overlay.set_color(color.red(), color.green(), color.blue())?,
+            None => log::warn!("Invalid overlay tint '{tint}', expected one like #ffcc88"),
+        }
+    }
+
+    Ok(())
+}
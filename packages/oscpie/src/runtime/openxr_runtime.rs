@@ -0,0 +1,617 @@
+//! Any OpenXR runtime with the `XR_EXTX_overlay` extension. The menu is drawn as a quad layer of an
+//! overlay session, on top of whatever application renders the scene.
+
+use std::{collections::HashMap, sync::Arc};
+
+use openxr as xr;
+use tiny_skia::Pixmap;
+use vulkano::{
+    device::{physical::PhysicalDevice, Device},
+    format::Format,
+    image::{sys::RawImage, Image, ImageCreateInfo, ImageType, ImageUsage},
+    instance::Instance,
+    Handle, VulkanObject,
+};
+
+use super::{HandInput, HandPose, RuntimeEvent, XrRuntime};
+use crate::{
+    config::{types::OverlayConfig, Config},
+    dirty_rows::DirtyRows,
+    hand::Hand,
+    input_expression::DIGITAL_ACTIONS,
+    overlay_layers::OverlayLayer,
+    prelude::*,
+    vulkan,
+};
+
+/// `VK_FORMAT_R8G8B8A8_SRGB`, the format the menu is uploaded in.
+const SWAPCHAIN_FORMAT: u32 = 43;
+
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+
+/// Vulkan extensions and device the runtime asks for, through `XR_KHR_vulkan_enable`.
+struct Requirements<'a> {
+    instance: &'a xr::Instance,
+    system: xr::SystemId,
+}
+
+impl vulkan::VulkanRequirements for Requirements<'_> {
+    fn instance_extensions(&self) -> Result<Vec<String>> {
+        let extensions = self
+            .instance
+            .vulkan_legacy_instance_extensions(self.system)?;
+
+        Ok(extensions.split_whitespace().map(String::from).collect())
+    }
+
+    fn device_extensions(&self, _physical_device: &PhysicalDevice) -> Result<Vec<String>> {
+        let extensions = self.instance.vulkan_legacy_device_extensions(self.system)?;
+
+        Ok(extensions.split_whitespace().map(String::from).collect())
+    }
+
+    fn physical_device(&self, instance: &Arc<Instance>) -> Result<Option<Arc<PhysicalDevice>>> {
+        let required = self
+            .instance
+            .vulkan_graphics_device(self.system, instance.handle().as_raw() as usize as _)?;
+
+        Ok(instance
+            .enumerate_physical_devices()?
+            .find(|p| p.handle().as_raw() == required as usize as u64))
+    }
+}
+
+struct Actions {
+    set: xr::ActionSet,
+    open: xr::Action<bool>,
+    click: xr::Action<bool>,
+    grip: xr::Action<bool>,
+    select: xr::Action<xr::Vector2f>,
+    pose: xr::Action<xr::Posef>,
+}
+
+impl Actions {
+    fn new(instance: &xr::Instance, hands: &[xr::Path; 2]) -> Result<Self> {
+        let set = instance.create_action_set("main", "Main", 0)?;
+
+        let actions = Self {
+            open: set.create_action("open", "Open menu", hands)?,
+            click: set.create_action("click", "Click", hands)?,
+            grip: set.create_action("grip", "Grip", hands)?,
+            select: set.create_action("select", "Select", hands)?,
+            pose: set.create_action("pose", "Pose", hands)?,
+            set,
+        };
+
+        let path = |path: &str| instance.string_to_path(path);
+        let mut bindings = Vec::new();
+
+        for hand in ["left", "right"] {
+            bindings.extend([
+                xr::Binding::new(
+                    &actions.open,
+                    path(&format!("/user/hand/{hand}/input/a/click"))?,
+                ),
+                xr::Binding::new(
+                    &actions.click,
+                    path(&format!("/user/hand/{hand}/input/trigger/click"))?,
+                ),
+                xr::Binding::new(
+                    &actions.grip,
+                    path(&format!("/user/hand/{hand}/input/squeeze/value"))?,
+                ),
+                xr::Binding::new(
+                    &actions.select,
+                    path(&format!("/user/hand/{hand}/input/thumbstick"))?,
+                ),
+                xr::Binding::new(
+                    &actions.pose,
+                    path(&format!("/user/hand/{hand}/input/grip/pose"))?,
+                ),
+            ]);
+        }
+
+        instance.suggest_interaction_profile_bindings(
+            path("/interactions/valve/index_controller")?,
+            &bindings,
+        )?;
+
+        Ok(actions)
+    }
+}
+
+pub struct OpenXrRuntime {
+    instance: xr::Instance,
+    session: xr::Session<xr::Vulkan>,
+    frame_waiter: xr::FrameWaiter,
+    frame_stream: xr::FrameStream<xr::Vulkan>,
+    /// State of the frame waited for and not yet submitted.
+    frame_state: Option<xr::FrameState>,
+    /// Predicted display time of the latest frame, which poses are read at.
+    display_time: xr::Time,
+    is_running: bool,
+    hands: [xr::Path; 2],
+    actions: Actions,
+    hand_spaces: [xr::Space; 2],
+    stage: xr::Space,
+    view: xr::Space,
+    swapchain: xr::Swapchain<xr::Vulkan>,
+    images: Vec<Arc<Image>>,
+    extent: xr::Extent2Di,
+    uploader: vulkan::ImageUploader,
+    /// Where the menu is drawn, `None` until a hand has been tracked.
+    menu_pose: Option<xr::Posef>,
+    width_m: f32,
+}
+
+impl OpenXrRuntime {
+    #[allow(clippy::too_many_lines)]
+    pub fn new(config: &Config, pixmap: &Pixmap) -> Result<Self> {
+        let entry = unsafe { xr::Entry::load() }
+            .map_err(|e| anyhow!("Failed to load the OpenXR loader: {e}"))?;
+
+        let available = entry.enumerate_extensions()?;
+        if !available.extx_overlay {
+            return Err(anyhow!(
+                "The OpenXR runtime does not support overlays (XR_EXTX_overlay)"
+            ));
+        }
+        if !available.khr_vulkan_enable {
+            return Err(anyhow!(
+                "The OpenXR runtime does not support Vulkan (XR_KHR_vulkan_enable)"
+            ));
+        }
+
+        let mut extensions = xr::ExtensionSet::default();
+        extensions.extx_overlay = true;
+        extensions.khr_vulkan_enable = true;
+
+        let instance = entry.create_instance(
+            &xr::ApplicationInfo {
+                application_name: "OSCPie",
+                application_version: 0,
+                engine_name: "OSCPie",
+                engine_version: 0,
+                api_version: xr::Version::new(1, 0, 0),
+            },
+            &extensions,
+            &[],
+        )?;
+        let system = instance.system(xr::FormFactor::HEAD_MOUNTED_DISPLAY)?;
+
+        // Has to be queried before creating a session, even though any Vulkan version will do
+        instance.graphics_requirements::<xr::Vulkan>(system)?;
+
+        let context = vulkan::Context::new(
+            &Requirements {
+                instance: &instance,
+                system,
+            },
+            config.overlay.gpu.as_ref(),
+        )?;
+        let uploader = vulkan::ImageUploader::new(context, pixmap, false)?;
+
+        let (session, frame_waiter, frame_stream) =
+            create_overlay_session(&instance, system, uploader.queue())?;
+
+        let hands = [
+            instance.string_to_path("/user/hand/left")?,
+            instance.string_to_path("/user/hand/right")?,
+        ];
+        let actions = Actions::new(&instance, &hands)?;
+        session.attach_action_sets(&[&actions.set])?;
+
+        let hand_spaces = [
+            actions
+                .pose
+                .create_space(session.clone(), hands[LEFT], xr::Posef::IDENTITY)?,
+            actions
+                .pose
+                .create_space(session.clone(), hands[RIGHT], xr::Posef::IDENTITY)?,
+        ];
+        let stage =
+            session.create_reference_space(xr::ReferenceSpaceType::STAGE, xr::Posef::IDENTITY)?;
+        let view =
+            session.create_reference_space(xr::ReferenceSpaceType::VIEW, xr::Posef::IDENTITY)?;
+
+        let (swapchain, images) = create_swapchain(&session, &uploader, pixmap)?;
+
+        Ok(Self {
+            instance,
+            session,
+            frame_waiter,
+            frame_stream,
+            frame_state: None,
+            display_time: xr::Time::from_nanos(0),
+            is_running: false,
+            hands,
+            actions,
+            hand_spaces,
+            stage,
+            view,
+            swapchain,
+            images,
+            extent: extent(pixmap)?,
+            uploader,
+            menu_pose: None,
+            width_m: config.overlay.width_m,
+        })
+    }
+
+    fn hand_index(hand: Hand) -> usize {
+        match hand {
+            Hand::Left => LEFT,
+            Hand::Right => RIGHT,
+        }
+    }
+
+    fn begin_frame(&mut self) -> Result<xr::FrameState> {
+        let frame_state = match self.frame_state.take() {
+            Some(frame_state) => frame_state,
+            None => self.frame_waiter.wait()?,
+        };
+
+        self.display_time = frame_state.predicted_display_time;
+        self.frame_stream.begin()?;
+
+        Ok(frame_state)
+    }
+
+    fn draw_menu(&mut self, pixmap: &Pixmap, pose: xr::Posef, time: xr::Time) -> Result<()> {
+        let index = self.swapchain.acquire_image()? as usize;
+        self.swapchain.wait_image(xr::Duration::INFINITE)?;
+        let uploaded = self.uploader.upload_to(pixmap, self.images[index].clone());
+        self.swapchain.release_image()?;
+        uploaded?;
+
+        #[allow(clippy::cast_precision_loss)]
+        let aspect = self.extent.height as f32 / self.extent.width as f32;
+
+        let layer = xr::CompositionLayerQuad::new()
+            .space(&self.stage)
+            .layer_flags(xr::CompositionLayerFlags::BLEND_TEXTURE_SOURCE_ALPHA)
+            .eye_visibility(xr::EyeVisibility::BOTH)
+            .sub_image(
+                xr::SwapchainSubImage::new()
+                    .swapchain(&self.swapchain)
+                    .image_rect(xr::Rect2Di {
+                        offset: xr::Offset2Di { x: 0, y: 0 },
+                        extent: self.extent,
+                    }),
+            )
+            .pose(pose)
+            .size(xr::Extent2Df {
+                width: self.width_m,
+                height: self.width_m * aspect,
+            });
+
+        self.frame_stream
+            .end(time, xr::EnvironmentBlendMode::OPAQUE, &[&layer])?;
+
+        Ok(())
+    }
+}
+
+impl XrRuntime for OpenXrRuntime {
+    fn poll_events(&mut self) -> Result<Vec<RuntimeEvent>> {
+        let mut events = Vec::new();
+        let mut buffer = xr::EventDataBuffer::new();
+
+        while let Some(event) = self.instance.poll_event(&mut buffer)? {
+            match event {
+                xr::Event::SessionStateChanged(change) => match change.state() {
+                    xr::SessionState::READY => {
+                        self.session
+                            .begin(xr::ViewConfigurationType::PRIMARY_STEREO)?;
+                        self.is_running = true;
+                    }
+                    xr::SessionState::STOPPING => {
+                        self.session.end()?;
+                        self.is_running = false;
+                        self.frame_state = None;
+                    }
+                    xr::SessionState::EXITING | xr::SessionState::LOSS_PENDING => {
+                        events.push(RuntimeEvent::Quit);
+                    }
+                    _ => {}
+                },
+                xr::Event::InstanceLossPending(_) => events.push(RuntimeEvent::Quit),
+                _ => {}
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn is_idle(&self) -> bool {
+        !self.is_running
+    }
+
+    fn update_input(&mut self) -> Result<HashMap<&'static str, bool>> {
+        let mut digital_actions: HashMap<&'static str, bool> =
+            DIGITAL_ACTIONS.iter().map(|name| (*name, false)).collect();
+
+        if !self.is_running {
+            return Ok(digital_actions);
+        }
+
+        self.session.sync_actions(&[(&self.actions.set).into()])?;
+
+        // Hand poses need finger tracking, which is only read through OpenVR so far
+        for (index, names) in [
+            (LEFT, ["OpenLeft", "ClickLeft", "GripLeft"]),
+            (RIGHT, ["OpenRight", "ClickRight", "GripRight"]),
+        ] {
+            let hand = self.hands[index];
+            let actions = [&self.actions.open, &self.actions.click, &self.actions.grip];
+
+            for (name, action) in names.into_iter().zip(actions) {
+                let state = action.state(&self.session, hand)?;
+                digital_actions.insert(name, state.current_state);
+            }
+        }
+
+        Ok(digital_actions)
+    }
+
+    fn hand_input(&self, hand: Hand) -> Result<HandInput> {
+        if !self.is_running {
+            return Ok(HandInput {
+                click: false,
+                select: Vec2::ZERO,
+                pose: None,
+            });
+        }
+
+        let index = Self::hand_index(hand);
+        let subaction = self.hands[index];
+
+        let click = self.actions.click.state(&self.session, subaction)?;
+        let select = self.actions.select.state(&self.session, subaction)?;
+        let (location, velocity) =
+            self.hand_spaces[index].relate(&self.stage, self.display_time)?;
+
+        let is_tracked = location.location_flags.contains(
+            xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
+        );
+
+        Ok(HandInput {
+            click: click.current_state,
+            select: Vec2::new(select.current_state.x, select.current_state.y),
+            pose: is_tracked.then(|| HandPose {
+                transform: from_posef(location.pose),
+                velocity: from_vector3f(velocity.linear_velocity),
+                angular_velocity: from_vector3f(velocity.angular_velocity),
+            }),
+        })
+    }
+
+    fn hmd_pose(&self) -> Option<Affine3A> {
+        let location = self.view.locate(&self.stage, self.display_time).ok()?;
+
+        location
+            .location_flags
+            .contains(
+                xr::SpaceLocationFlags::POSITION_VALID | xr::SpaceLocationFlags::ORIENTATION_VALID,
+            )
+            .then(|| from_posef(location.pose))
+    }
+
+    fn place_menu(&mut self, _hand: Hand, transform: Option<Affine3A>) -> Result<Option<Affine3A>> {
+        let Some(transform) = transform else {
+            return Ok(None);
+        };
+
+        let transform = OverlayLayer::Menu.offset(transform);
+        self.menu_pose = Some(to_posef(transform));
+
+        Ok(Some(transform))
+    }
+
+    /// Swapchain images take turns, so the whole menu is uploaded into every one of them.
+    fn submit(&mut self, pixmap: &Pixmap, _dirty: Option<DirtyRows>, visible: bool) -> Result<()> {
+        if !self.is_running {
+            return Ok(());
+        }
+
+        let frame_state = self.begin_frame()?;
+        let time = frame_state.predicted_display_time;
+
+        match self
+            .menu_pose
+            .filter(|_| visible && frame_state.should_render)
+        {
+            Some(pose) => self.draw_menu(pixmap, pose, time),
+            None => Ok(self
+                .frame_stream
+                .end(time, xr::EnvironmentBlendMode::OPAQUE, &[])?),
+        }
+    }
+
+    fn resize(&mut self, pixmap: &Pixmap, _config: &OverlayConfig) -> Result<()> {
+        // The quad layer is sampled as it is, without mipmaps
+        self.uploader.resize(pixmap, false)?;
+        (self.swapchain, self.images) = create_swapchain(&self.session, &self.uploader, pixmap)?;
+        self.extent = extent(pixmap)?;
+
+        Ok(())
+    }
+
+    fn apply_overlay_config(&mut self, config: &OverlayConfig) -> Result<()> {
+        // The quad layer has no alpha, curvature or tint to set
+        self.width_m = config.width_m;
+
+        Ok(())
+    }
+
+    fn hide(&mut self) -> Result<()> {
+        // Layers only show for the frame they are submitted with, so there is nothing to take down
+        Ok(())
+    }
+
+    fn wait_frame(&mut self) -> Result<()> {
+        if !self.is_running {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            return Ok(());
+        }
+
+        let frame_state = self.frame_waiter.wait()?;
+        self.display_time = frame_state.predicted_display_time;
+        self.frame_state = Some(frame_state);
+
+        Ok(())
+    }
+}
+
+/// Creates a session chained with `XrSessionCreateInfoOverlayEXTX`, which the `openxr` crate has
+/// no safe wrapper for.
+fn create_overlay_session(
+    instance: &xr::Instance,
+    system: xr::SystemId,
+    queue: &vulkano::device::Queue,
+) -> Result<(
+    xr::Session<xr::Vulkan>,
+    xr::FrameWaiter,
+    xr::FrameStream<xr::Vulkan>,
+)> {
+    let device = queue.device();
+
+    let binding = xr::sys::GraphicsBindingVulkanKHR {
+        ty: xr::sys::GraphicsBindingVulkanKHR::TYPE,
+        next: std::ptr::null(),
+        instance: device.instance().handle().as_raw() as usize as _,
+        physical_device: device.physical_device().handle().as_raw() as usize as _,
+        device: device.handle().as_raw() as usize as _,
+        queue_family_index: queue.queue_family_index(),
+        queue_index: queue.queue_index(),
+    };
+
+    let overlay = xr::sys::SessionCreateInfoOverlayEXTX {
+        ty: xr::sys::SessionCreateInfoOverlayEXTX::TYPE,
+        next: std::ptr::from_ref(&binding).cast(),
+        create_flags: xr::sys::OverlaySessionCreateFlagsEXTX::EMPTY,
+        // Above the layers of other overlay applications
+        session_layers_placement: 1,
+    };
+
+    let info = xr::sys::SessionCreateInfo {
+        ty: xr::sys::SessionCreateInfo::TYPE,
+        next: std::ptr::from_ref(&overlay).cast(),
+        create_flags: xr::sys::SessionCreateFlags::EMPTY,
+        system_id: system,
+    };
+
+    let mut handle = xr::sys::Session::NULL;
+    let error = unsafe { (instance.fp().create_session)(instance.as_raw(), &info, &mut handle) };
+    if error.into_raw() < 0 {
+        return Err(anyhow!("Failed to create the overlay session: {}", error));
+    }
+
+    Ok(unsafe { xr::Session::from_raw(instance.clone(), handle, Box::new(())) })
+}
+
+/// Creates a swapchain the size of `pixmap`, and wraps its images for `uploader`.
+fn create_swapchain(
+    session: &xr::Session<xr::Vulkan>,
+    uploader: &vulkan::ImageUploader,
+    pixmap: &Pixmap,
+) -> Result<(xr::Swapchain<xr::Vulkan>, Vec<Arc<Image>>)> {
+    let swapchain = session.create_swapchain(&xr::SwapchainCreateInfo {
+        create_flags: xr::SwapchainCreateFlags::EMPTY,
+        usage_flags: xr::SwapchainUsageFlags::TRANSFER_DST | xr::SwapchainUsageFlags::SAMPLED,
+        format: SWAPCHAIN_FORMAT,
+        sample_count: 1,
+        width: pixmap.width(),
+        height: pixmap.height(),
+        face_count: 1,
+        array_size: 1,
+        mip_count: 1,
+    })?;
+
+    let device = uploader.queue().device();
+    let images = swapchain
+        .enumerate_images()?
+        .into_iter()
+        .map(|image| wrap_swapchain_image(device, image, pixmap))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((swapchain, images))
+}
+
+fn extent(pixmap: &Pixmap) -> Result<xr::Extent2Di> {
+    Ok(xr::Extent2Di {
+        width: i32::try_from(pixmap.width())?,
+        height: i32::try_from(pixmap.height())?,
+    })
+}
+
+/// Wraps an image of the swapchain for uploading to. The runtime owns its memory.
+fn wrap_swapchain_image(device: &Arc<Device>, image: u64, pixmap: &Pixmap) -> Result<Arc<Image>> {
+    let image = unsafe {
+        RawImage::from_handle_borrowed(
+            device.clone(),
+            Handle::from_raw(image),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: Format::R8G8B8A8_SRGB,
+                extent: [pixmap.width(), pixmap.height(), 1],
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+        )?
+        .assume_bound()
+    };
+
+    Ok(Arc::new(image))
+}
+
+fn from_vector3f(vector: xr::Vector3f) -> Vec3A {
+    Vec3A::new(vector.x, vector.y, vector.z)
+}
+
+fn from_posef(pose: xr::Posef) -> Affine3A {
+    let rotation = glam::Quat::from_xyzw(
+        pose.orientation.x,
+        pose.orientation.y,
+        pose.orientation.z,
+        pose.orientation.w,
+    );
+
+    Affine3A::from_rotation_translation(rotation, from_vector3f(pose.position).into())
+}
+
+fn to_posef(transform: Affine3A) -> xr::Posef {
+    let (_, rotation, translation) = transform.to_scale_rotation_translation();
+
+    xr::Posef {
+        orientation: xr::Quaternionf {
+            x: rotation.x,
+            y: rotation.y,
+            z: rotation.z,
+            w: rotation.w,
+        },
+        position: xr::Vector3f {
+            x: translation.x,
+            y: translation.y,
+            z: translation.z,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posef_round_trip() {
+        let transform = Affine3A::from_rotation_translation(
+            glam::Quat::from_rotation_y(0.5),
+            glam::Vec3::new(0.1, 1.2, -0.3),
+        );
+
+        let round_trip = from_posef(to_posef(transform));
+
+        assert!(round_trip.abs_diff_eq(transform, 1e-6));
+    }
+}
@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+
+#[cfg(feature = "osc")]
+use crate::osc::OscValue;
+
+/// Something an action does to the world outside oscpie.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SideEffect {
+    KeyStroke {
+        description: String,
+    },
+    Exec {
+        program_path: String,
+        args: Vec<String>,
+    },
+    #[cfg(feature = "osc")]
+    OscSend {
+        address: String,
+        value: OscValue,
+    },
+    SystemVolume {
+        volume: f32,
+    },
+}
+
+impl std::fmt::Display for SideEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SideEffect::KeyStroke { description } => write!(f, "{description}"),
+            SideEffect::Exec { program_path, args } => {
+                write!(f, "Execute {program_path} {}", args.join(" "))
+            }
+            #[cfg(feature = "osc")]
+            SideEffect::OscSend { address, value } => write!(f, "Send {value:?} to {address}"),
+            SideEffect::SystemVolume { volume } => {
+                write!(f, "Set system volume to {:.0}%", volume * 100.0)
+            }
+        }
+    }
+}
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<SideEffect>>> = const { RefCell::new(None) };
+}
+
+/// Captures the effect instead of letting it happen while [`capture`] runs on this thread.
+///
+/// Returns whether the effect was captured, in which case the caller must not perform it.
+pub fn intercept(effect: impl FnOnce() -> SideEffect) -> bool {
+    CAPTURED.with_borrow_mut(|captured| {
+        let Some(captured) = captured else {
+            return false;
+        };
+
+        let effect = effect();
+        log::debug!("Captured side effect: {effect}");
+        captured.push(effect);
+
+        true
+    })
+}
+
+/// Runs `f` with the side effects of actions on this thread captured instead of performed.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<SideEffect>) {
+    CAPTURED.with_borrow_mut(|captured| *captured = Some(vec![]));
+
+    let result = f();
+    let effects = CAPTURED.with_borrow_mut(Option::take).unwrap_or_default();
+
+    (result, effects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture() {
+        let effect = || SideEffect::Exec {
+            program_path: "notepad.exe".to_string(),
+            args: vec![],
+        };
+
+        assert!(!intercept(effect));
+
+        let (intercepted, effects) = capture(|| intercept(effect));
+        assert!(intercepted);
+        assert_eq!(effects, vec![effect()]);
+
+        assert!(!intercept(effect));
+    }
+}
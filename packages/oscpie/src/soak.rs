@@ -0,0 +1,247 @@
+//! Long running stability check. The app is driven with random input for hours, without VR, and
+//! memory, handle counts and frame times are sampled to catch leaks before a release.
+
+use std::{
+    f32::consts::PI,
+    time::{Duration, Instant},
+};
+
+use oscpie_ui::sprite::SpriteSheet;
+use tiny_skia::Pixmap;
+use windows_sys::Win32::System::{
+    ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+    Threading::{GetCurrentProcess, GetProcessHandleCount},
+};
+
+use crate::{
+    config,
+    prelude::*,
+    resource::{Animations, ANIMATIONS, SPRITE_SHEET},
+    side_effects, App, AppImpl, AppInput, CONFIG_PATH,
+};
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(11);
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Growth over the first sample which counts as a leak.
+const MAX_RSS_GROWTH_BYTES: u64 = 64 * 1024 * 1024;
+const MAX_HANDLE_GROWTH: u32 = 64;
+/// Frames may get this much slower than in the first sample, plus [`FRAME_TIME_SLACK_NS`] for
+/// noise on fast machines.
+const MAX_FRAME_TIME_RATIO: f64 = 1.5;
+const FRAME_TIME_SLACK_NS: f64 = 500_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub rss_bytes: u64,
+    pub handles: u32,
+    /// Average time spent updating and rendering a frame since the previous sample.
+    pub frame_ns: f64,
+}
+
+impl Sample {
+    fn take(frame_ns: f64) -> Sample {
+        Sample {
+            rss_bytes: rss_bytes(),
+            handles: handle_count(),
+            frame_ns,
+        }
+    }
+}
+
+/// Compares `sample` to the first one, describing every bound it exceeds.
+pub fn check(baseline: &Sample, sample: &Sample) -> Vec<String> {
+    let mut problems = vec![];
+
+    let rss_growth = sample.rss_bytes.saturating_sub(baseline.rss_bytes);
+    if rss_growth > MAX_RSS_GROWTH_BYTES {
+        problems.push(format!(
+            "memory grew by {} KiB to {} KiB",
+            rss_growth / 1024,
+            sample.rss_bytes / 1024
+        ));
+    }
+
+    let handle_growth = sample.handles.saturating_sub(baseline.handles);
+    if handle_growth > MAX_HANDLE_GROWTH {
+        problems.push(format!(
+            "handle count grew by {handle_growth} to {}",
+            sample.handles
+        ));
+    }
+
+    if sample.frame_ns > baseline.frame_ns * MAX_FRAME_TIME_RATIO + FRAME_TIME_SLACK_NS {
+        problems.push(format!(
+            "frames take {:.0}ns, up from {:.0}ns",
+            sample.frame_ns, baseline.frame_ns
+        ));
+    }
+
+    problems
+}
+
+/// Drives the app with the config at [`CONFIG_PATH`] for `duration`. Actions are captured instead
+/// of performed. Returns whether every sample stayed within bounds.
+pub fn run(duration: Duration) -> Result<bool> {
+    let mut config = config::load(CONFIG_PATH)?;
+
+    // Nothing the random input does may be written back to the config
+    config.tutorial_completed = true;
+    config.analytics = false;
+
+    SPRITE_SHEET
+        .set(
+            SpriteSheet::load(resolve_path(CONFIG_PATH, &config.sprite_sheet))
+                .map_err(|e| anyhow!(e))?,
+        )
+        .map_err(|_| anyhow!("Sprite sheet is already loaded"))?;
+
+    ANIMATIONS
+        .set(Animations::from_config(&config.animations))
+        .map_err(|_| anyhow!("Animations are already loaded"))?;
+
+    let mut app = AppImpl::new(&config);
+    let mut pixmap = Pixmap::new(512, 512).unwrap();
+    let mut random = Random::new(0x0053_4f41_4b00_0001);
+
+    let started_at = Instant::now();
+    let mut sampled_at = started_at;
+    let mut frames = 0u32;
+    let mut frame_time = Duration::ZERO;
+    let mut baseline = None;
+    let mut within_bounds = true;
+
+    while started_at.elapsed() < duration {
+        let frame_started_at = Instant::now();
+
+        let (result, _) = side_effects::capture(|| {
+            app.on_update(random.input())?;
+            app.on_render(&mut pixmap)
+        });
+        result?;
+
+        let elapsed = frame_started_at.elapsed();
+        frame_time += elapsed;
+        frames += 1;
+
+        std::thread::sleep(FRAME_INTERVAL.saturating_sub(elapsed));
+
+        if sampled_at.elapsed() < SAMPLE_INTERVAL {
+            continue;
+        }
+
+        let sample = Sample::take(frame_time.as_secs_f64() * 1e9 / f64::from(frames));
+        let minutes = started_at.elapsed().as_secs() / 60;
+
+        println!(
+            "{minutes}min: {} KiB, {} handles, {:.0}ns per frame",
+            sample.rss_bytes / 1024,
+            sample.handles,
+            sample.frame_ns
+        );
+
+        let baseline = *baseline.get_or_insert(sample);
+
+        for problem in check(&baseline, &sample) {
+            log::error!("{minutes}min: {problem}");
+            within_bounds = false;
+        }
+
+        sampled_at = Instant::now();
+        frames = 0;
+        frame_time = Duration::ZERO;
+    }
+
+    Ok(within_bounds)
+}
+
+fn rss_bytes() -> u64 {
+    let mut counters: PROCESS_MEMORY_COUNTERS = unsafe { std::mem::zeroed() };
+    let size = u32::try_from(std::mem::size_of::<PROCESS_MEMORY_COUNTERS>()).unwrap();
+
+    if unsafe { K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) } == 0 {
+        log::warn!("Failed to get the memory usage");
+        return 0;
+    }
+
+    counters.WorkingSetSize as u64
+}
+
+fn handle_count() -> u32 {
+    let mut count = 0;
+
+    if unsafe { GetProcessHandleCount(GetCurrentProcess(), &mut count) } == 0 {
+        log::warn!("Failed to get the handle count");
+    }
+
+    count
+}
+
+/// Xorshift generator, so that every soak run replays the same input.
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random(seed)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn input(&mut self) -> AppInput {
+        AppInput {
+            angle: self.next_f32() * PI * 2.0,
+            magnitude: self.next_f32(),
+            click: if self.next_f32() < 0.05 { 1.0 } else { 0.0 },
+            open_menu: self.next_f32() < 0.01,
+            pose: None,
+            is_tracked: self.next_f32() > 0.001,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASELINE: Sample = Sample {
+        rss_bytes: 100 * 1024 * 1024,
+        handles: 200,
+        frame_ns: 2_000_000.0,
+    };
+
+    #[test]
+    fn test_check_within_bounds() {
+        let sample = Sample {
+            rss_bytes: BASELINE.rss_bytes + 1024 * 1024,
+            handles: BASELINE.handles - 10,
+            frame_ns: 3_200_000.0,
+        };
+
+        assert!(check(&BASELINE, &sample).is_empty());
+    }
+
+    #[test]
+    fn test_check_leaks() {
+        let sample = Sample {
+            rss_bytes: BASELINE.rss_bytes + MAX_RSS_GROWTH_BYTES + 1,
+            handles: BASELINE.handles + MAX_HANDLE_GROWTH + 1,
+            frame_ns: 4_000_000.0,
+        };
+
+        assert_eq!(check(&BASELINE, &sample).len(), 3);
+    }
+
+    #[test]
+    fn test_random_is_in_range() {
+        let mut random = Random::new(1);
+
+        assert!((0..1000).all(|_| (0.0..1.0).contains(&random.next_f32())));
+    }
+}
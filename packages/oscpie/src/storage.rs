@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{prelude::*, versioned::Versioned};
+
+/// Reads and writes a versioned JSON document with rotated backups.
+///
+/// Every write is verified by parsing the written file back before it replaces the current one,
+/// and a document that fails to parse on read is restored from the newest backup that does.
+pub struct Storage<T> {
+    path: PathBuf,
+    backups: usize,
+    phantom: std::marker::PhantomData<T>,
+}
+
+impl<T> Storage<T>
+where
+    T: Serialize + DeserializeOwned + Versioned<u32>,
+{
+    pub fn new(path: impl Into<PathBuf>, backups: usize) -> Self {
+        Self {
+            path: path.into(),
+            backups,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn read(&self) -> Result<T> {
+        let error = match Self::parse_file(&self.path) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        log::warn!("Failed to read {}: {error}", self.path.display());
+
+        for index in 1..=self.backups {
+            let backup_path = self.backup_path(index);
+
+            let Ok(value) = Self::parse_file(&backup_path) else {
+                continue;
+            };
+
+            log::warn!(
+                "Restoring {} from {}",
+                self.path.display(),
+                backup_path.display()
+            );
+
+            if self.path.exists() {
+                std::fs::rename(&self.path, self.sibling_path("corrupt"))?;
+            }
+
+            std::fs::copy(&backup_path, &self.path)?;
+
+            return Ok(value);
+        }
+
+        Err(error)
+    }
+
+    /// Reads the document without falling back to backups, leaving a broken file untouched.
+    pub fn read_current(&self) -> Result<T> {
+        Self::parse_file(&self.path)
+    }
+
+    pub fn write(&self, value: &T) -> Result<()> {
+        let temporary_path = self.sibling_path("tmp");
+
+        std::fs::write(&temporary_path, serde_json::to_string_pretty(value)?)?;
+
+        let written = Self::parse_file(&temporary_path)?;
+
+        if written.version() != value.version() {
+            return Err(anyhow!(
+                "Version mismatch after writing {}: expected {}, got {}",
+                self.path.display(),
+                value.version(),
+                written.version()
+            ));
+        }
+
+        self.rotate_backups()?;
+
+        std::fs::rename(&temporary_path, &self.path)?;
+
+        Ok(())
+    }
+
+    fn rotate_backups(&self) -> Result<()> {
+        if self.backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+
+        for index in (1..self.backups).rev() {
+            let from = self.backup_path(index);
+
+            if from.exists() {
+                std::fs::rename(&from, self.backup_path(index + 1))?;
+            }
+        }
+
+        std::fs::copy(&self.path, self.backup_path(1))?;
+
+        Ok(())
+    }
+
+    fn parse_file(path: &Path) -> Result<T> {
+        let file = std::fs::File::open(path)?;
+
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        self.sibling_path(&format!("{index}.bak"))
+    }
+
+    fn sibling_path(&self, extension: &str) -> PathBuf {
+        let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".");
+        file_name.push(extension);
+
+        self.path.with_file_name(file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "version", content = "data")]
+    enum Document {
+        V1(u32),
+    }
+
+    impl Versioned<u32> for Document {
+        fn version(&self) -> u32 {
+            match self {
+                Document::V1(_) => 1,
+            }
+        }
+    }
+
+    fn storage(name: &str) -> Storage<Document> {
+        let directory = std::env::temp_dir().join(format!("oscpie_storage_{name}"));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        Storage::new(directory.join("document.json"), 2)
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let storage = storage("write_and_read");
+
+        storage.write(&Document::V1(42)).unwrap();
+
+        assert_eq!(storage.read().unwrap(), Document::V1(42));
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let storage = storage("rotate_backups");
+
+        for value in 0..4 {
+            storage.write(&Document::V1(value)).unwrap();
+        }
+
+        assert_eq!(
+            Storage::<Document>::parse_file(&storage.backup_path(1)).unwrap(),
+            Document::V1(2)
+        );
+        assert_eq!(
+            Storage::<Document>::parse_file(&storage.backup_path(2)).unwrap(),
+            Document::V1(1)
+        );
+        assert!(!storage.backup_path(3).exists());
+    }
+
+    #[test]
+    fn test_restore_from_backup() {
+        let storage = storage("restore_from_backup");
+
+        storage.write(&Document::V1(1)).unwrap();
+        storage.write(&Document::V1(2)).unwrap();
+
+        std::fs::write(&storage.path, "{ broken").unwrap();
+
+        assert_eq!(storage.read().unwrap(), Document::V1(1));
+        assert_eq!(
+            Storage::<Document>::parse_file(&storage.path).unwrap(),
+            Document::V1(1)
+        );
+        assert!(storage.sibling_path("corrupt").exists());
+    }
+}
@@ -0,0 +1,174 @@
+//! Colors and proportions of the menus, from one of the presets with the config's overrides.
+
+use tiny_skia::Color;
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    /// Fill of the center circle.
+    pub center: Color,
+    /// Hovered items, slider values and hold progress.
+    pub highlight: Color,
+    pub separator: Color,
+    pub separator_width: f32,
+    /// Radius of the center circle, relative to the menu radius.
+    pub center_radius: f32,
+    /// Distance of the icons from the center, relative to the menu radius.
+    pub item_radius: f32,
+    pub label: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            background: Color::from_rgba(0.1, 0.1, 0.2, 0.8).unwrap(),
+            center: Color::from_rgba(0.1, 0.1, 0.2, 1.0).unwrap(),
+            highlight: Color::from_rgba(0.4, 0.8, 1.0, 1.0).unwrap(),
+            separator: Color::WHITE,
+            separator_width: 4.0,
+            center_radius: 0.3,
+            item_radius: 0.7,
+            label: Color::WHITE,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            background: Color::from_rgba(0.95, 0.95, 0.97, 0.85).unwrap(),
+            center: Color::from_rgba(0.85, 0.85, 0.9, 1.0).unwrap(),
+            highlight: Color::from_rgba(0.1, 0.4, 0.9, 1.0).unwrap(),
+            separator: Color::from_rgba(0.2, 0.2, 0.3, 1.0).unwrap(),
+            separator_width: 3.0,
+            center_radius: 0.3,
+            item_radius: 0.7,
+            label: Color::from_rgba(0.1, 0.1, 0.15, 1.0).unwrap(),
+        }
+    }
+
+    /// Opaque black with bright accents, for bright scenes and poor eyesight.
+    pub fn high_contrast() -> Self {
+        Self {
+            background: Color::from_rgba(0.0, 0.0, 0.0, 0.95).unwrap(),
+            center: Color::BLACK,
+            highlight: Color::from_rgba(1.0, 0.85, 0.0, 1.0).unwrap(),
+            separator: Color::WHITE,
+            separator_width: 6.0,
+            center_radius: 0.25,
+            item_radius: 0.68,
+            label: Color::WHITE,
+        }
+    }
+
+    pub fn from_config(theme: &config::types::ThemeConfig) -> Self {
+        let preset = match theme.preset {
+            config::types::ThemePreset::Dark => Self::dark(),
+            config::types::ThemePreset::Light => Self::light(),
+            config::types::ThemePreset::HighContrast => Self::high_contrast(),
+        };
+
+        Self {
+            background: color_or(theme.background.as_deref(), preset.background),
+            center: color_or(theme.center.as_deref(), preset.center),
+            highlight: color_or(theme.highlight.as_deref(), preset.highlight),
+            separator: color_or(theme.separator.as_deref(), preset.separator),
+            separator_width: theme.separator_width.unwrap_or(preset.separator_width),
+            center_radius: theme.center_radius.unwrap_or(preset.center_radius),
+            item_radius: theme.item_radius.unwrap_or(preset.item_radius),
+            label: color_or(theme.label.as_deref(), preset.label),
+        }
+    }
+
+    /// Background with the contrast from `0.0` (as is) to `1.0` (opaque and darker) applied.
+    pub fn background_with_contrast(&self, contrast: f32) -> Color {
+        let shade = 1.0 - contrast * 0.5;
+        let background = self.background;
+
+        Color::from_rgba(
+            background.red() * shade,
+            background.green() * shade,
+            background.blue() * shade,
+            background.alpha() + (1.0 - background.alpha()) * contrast,
+        )
+        .unwrap_or(background)
+    }
+}
+
+fn color_or(hex: Option<&str>, default: Color) -> Color {
+    let Some(hex) = hex else {
+        return default;
+    };
+
+    parse_hex_color(hex).unwrap_or_else(|| {
+        log::warn!("Invalid theme color '{hex}', expected one like #1a1a33 or #1a1a33cc");
+        default
+    })
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa`.
+pub fn parse_hex_color(hex: &str) -> Option<Color> {
+    let digits = hex.strip_prefix('#')?;
+
+    if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+        return None;
+    }
+
+    let channel = |index: usize| u8::from_str_radix(digits.get(index * 2..index * 2 + 2)?, 16).ok();
+    let alpha = if digits.len() == 8 { channel(3)? } else { 255 };
+
+    Some(Color::from_rgba8(
+        channel(0)?,
+        channel(1)?,
+        channel(2)?,
+        alpha,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(
+            parse_hex_color("#ff8800"),
+            Some(Color::from_rgba8(255, 136, 0, 255))
+        );
+        assert_eq!(
+            parse_hex_color("#ff880080"),
+            Some(Color::from_rgba8(255, 136, 0, 128))
+        );
+        assert_eq!(parse_hex_color("ff8800"), None);
+        assert_eq!(parse_hex_color("#ff88"), None);
+        assert_eq!(parse_hex_color("#gg8800"), None);
+    }
+
+    #[test]
+    fn test_from_config_overrides_preset() {
+        let theme: config::types::ThemeConfig = serde_json::from_str(
+            r##"{ "preset": "HighContrast", "highlight": "#00ff00", "separator_width": 2.0 }"##,
+        )
+        .unwrap();
+
+        let theme = Theme::from_config(&theme);
+
+        assert_eq!(theme.highlight, Color::from_rgba8(0, 255, 0, 255));
+        assert!((theme.separator_width - 2.0).abs() < f32::EPSILON);
+        assert_eq!(theme.background, Theme::high_contrast().background);
+    }
+
+    #[test]
+    fn test_contrast_keeps_default_look() {
+        let theme = Theme::dark();
+
+        assert_eq!(theme.background_with_contrast(0.0), theme.background);
+        assert!((theme.background_with_contrast(1.0).alpha() - 1.0).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+/// Steps of the first launch tutorial, in the order they are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    PushStick,
+    Click,
+    OpenSubMenu,
+    GoBack,
+    Done,
+}
+
+impl TutorialStep {
+    /// Caption shown under the menu for this step.
+    pub fn caption(self) -> Option<&'static str> {
+        match self {
+            TutorialStep::PushStick => Some("Push the stick to aim at an item"),
+            TutorialStep::Click => Some("Click the stick to select it"),
+            TutorialStep::OpenSubMenu => Some("Select an item to open its menu"),
+            TutorialStep::GoBack => Some("Select back to return"),
+            TutorialStep::Done => None,
+        }
+    }
+}
+
+/// Scripted state machine which walks the user through the basic gestures.
+///
+/// It only observes input and app events, so the menu keeps working normally while it runs.
+pub struct Tutorial {
+    step: TutorialStep,
+    click_state_machine: ClickStateMachine,
+}
+
+impl Tutorial {
+    pub fn new() -> Self {
+        Self {
+            step: TutorialStep::PushStick,
+            click_state_machine: ClickStateMachine::new(),
+        }
+    }
+
+    pub fn step(&self) -> TutorialStep {
+        self.step
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.step == TutorialStep::Done
+    }
+
+    pub fn on_input(&mut self, input: &PieMenuInput) {
+        let clicked = matches!(
+            self.click_state_machine
+                .update(input.click > 0.5 && input.magnitude > 0.5),
+            Some(ClickStateMachineEvent::Click)
+        );
+
+        match self.step {
+            TutorialStep::PushStick if input.magnitude > 0.5 => self.advance(),
+            TutorialStep::Click if clicked => self.advance(),
+            _ => {}
+        }
+    }
+
+    pub fn on_event(&mut self, event: &AppEvent) {
+        match (self.step, event) {
+            (TutorialStep::OpenSubMenu, AppEvent::PushStack(_))
+            | (TutorialStep::GoBack, AppEvent::PopStack) => self.advance(),
+            _ => {}
+        }
+    }
+
+    fn advance(&mut self) {
+        self.step = match self.step {
+            TutorialStep::PushStick => TutorialStep::Click,
+            TutorialStep::Click => TutorialStep::OpenSubMenu,
+            TutorialStep::OpenSubMenu => TutorialStep::GoBack,
+            TutorialStep::GoBack | TutorialStep::Done => TutorialStep::Done,
+        };
+
+        log::info!("Tutorial step: {:?}", self.step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tutorial_script() {
+        let mut tutorial = Tutorial::new();
+
+        tutorial.on_input(&PieMenuInput::new(0.0, 0.0, 0.0));
+        assert_eq!(tutorial.step(), TutorialStep::PushStick);
+
+        tutorial.on_input(&PieMenuInput::new(0.0, 1.0, 0.0));
+        assert_eq!(tutorial.step(), TutorialStep::Click);
+
+        tutorial.on_input(&PieMenuInput::new(0.0, 1.0, 1.0));
+        tutorial.on_input(&PieMenuInput::new(0.0, 1.0, 0.0));
+        assert_eq!(tutorial.step(), TutorialStep::OpenSubMenu);
+
+        tutorial.on_event(&AppEvent::PopStack);
+        assert_eq!(tutorial.step(), TutorialStep::OpenSubMenu);
+
+        tutorial.on_event(&AppEvent::PushStack(MenuId::new("sub".to_string())));
+        tutorial.on_event(&AppEvent::PopStack);
+        assert!(tutorial.is_done());
+    }
+
+    #[test]
+    fn test_every_step_has_a_caption_until_done() {
+        for step in [
+            TutorialStep::PushStick,
+            TutorialStep::Click,
+            TutorialStep::OpenSubMenu,
+            TutorialStep::GoBack,
+        ] {
+            assert!(step.caption().is_some(), "{step:?} has no caption");
+        }
+
+        assert_eq!(TutorialStep::Done.caption(), None);
+    }
+}
@@ -156,7 +156,7 @@ pub struct TimeDelta {
 impl TimeDelta {
     pub fn new() -> Self {
         Self {
-            last_time: std::time::Instant::now(),
+            last_time: clock_now(),
             last_delta: 0.0,
         }
     }
@@ -166,7 +166,7 @@ impl TimeDelta {
     }
 
     pub fn update_and_get_secs(&mut self) -> f32 {
-        let now = std::time::Instant::now();
+        let now = clock_now();
         let delta = now.duration_since(self.last_time).as_secs_f32();
         self.last_delta = delta;
         self.last_time = now;
@@ -181,7 +181,37 @@ pub fn get_start_time() -> &'static std::time::Instant {
 }
 
 pub fn get_time_since_start_secs_f64() -> f64 {
-    get_start_time().elapsed().as_secs_f64()
+    clock_now().duration_since(*get_start_time()).as_secs_f64()
+}
+
+thread_local! {
+    /// Time since start on this thread, while its clock is frozen.
+    static FROZEN_CLOCK: std::cell::Cell<Option<std::time::Duration>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Current time of the clock driving animations.
+pub fn clock_now() -> std::time::Instant {
+    if let Some(since_start) = FROZEN_CLOCK.get() {
+        return *get_start_time() + since_start;
+    }
+
+    std::time::Instant::now()
+}
+
+/// Stops the animation clock of this thread at the start time, so that what is rendered does not
+/// depend on how fast it runs. Move it on with [`advance_clock`].
+pub fn freeze_clock() {
+    FROZEN_CLOCK.set(Some(std::time::Duration::ZERO));
+}
+
+pub fn advance_clock(duration: std::time::Duration) {
+    FROZEN_CLOCK.set(Some(FROZEN_CLOCK.get().unwrap_or_default() + duration));
+}
+
+/// Lets the animation clock of this thread follow the real time again.
+pub fn unfreeze_clock() {
+    FROZEN_CLOCK.set(None);
 }
 
 #[cfg(test)]
@@ -199,6 +229,19 @@ mod tests {
             "Time since start is not accurate"
         );
     }
+
+    #[test]
+    fn test_frozen_clock() {
+        super::freeze_clock();
+        let mut time_delta = super::TimeDelta::new();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(time_delta.update_and_get_secs().abs() < f32::EPSILON);
+
+        super::advance_clock(std::time::Duration::from_millis(250));
+        assert!((time_delta.update_and_get_secs() - 0.25).abs() < 1e-6);
+        assert!((super::get_time_since_start_secs_f64() - 0.25).abs() < 1e-9);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
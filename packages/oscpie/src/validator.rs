@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap};
+
+use oscpie_ui::sprite::SpriteSheet;
+
+use crate::{config, input_expression::InputExpression, prelude::*};
+
+/// Checks the config at `config_path` and prints a report.
+///
+/// With `dry_run_actions`, every action behaviour is instantiated and validated without running
+/// it. Returns whether no problem was found.
+pub fn validate_config(config_path: &str, dry_run_actions: bool) -> Result<bool> {
+    let file = std::fs::File::open(config_path)?;
+    let mut config = config::read(serde_json::from_reader(file)?)?;
+    config::resolve_includes(&mut config, std::path::Path::new(config_path))?;
+    config::resolve_library(&mut config);
+
+    println!("{config_path}: parsed");
+
+    let sprite_sheet = match SpriteSheet::load(resolve_path(config_path, &config.sprite_sheet)) {
+        Ok(sprite_sheet) => sprite_sheet,
+        Err(e) => {
+            println!("  error: sprite_sheet: {e}");
+            return Ok(false);
+        }
+    };
+
+    let problems = config::validate(&config, &sprite_sheet);
+
+    for problem in &problems {
+        println!("  {problem}");
+    }
+
+    if problems.iter().any(config::Problem::is_error) {
+        return Ok(false);
+    }
+
+    if let Err(e) = InputExpression::from_config(&config.open_gesture) {
+        println!("  open_gesture: {e}");
+        return Ok(false);
+    }
+
+    for warning in lint(&config) {
+        println!("  warning: {warning}");
+    }
+
+    if !dry_run_actions {
+        return Ok(true);
+    }
+
+    let mut menu_ids: Vec<_> = config.menus.keys().collect();
+    menu_ids.sort_by_key(|id| id.inner());
+
+    let mut valid = true;
+
+    for menu_id in menu_ids {
+        let menu_config = &config.menus[menu_id];
+        let menu = Menu::from_config(menu_config);
+
+        for (index, (item, item_config)) in menu.items.iter().zip(&menu_config.items).enumerate() {
+            let (description, result) = dry_run(item.action());
+            let description = match &item_config.label {
+                Some(label) => format!("{label} ({description})"),
+                None => description,
+            };
+
+            match result {
+                Ok(()) => println!("  {}[{index}] {description}: OK", menu_id.inner()),
+                Err(e) => {
+                    valid = false;
+                    println!("  {}[{index}] {description}: {e}", menu_id.inner());
+                }
+            }
+        }
+    }
+
+    Ok(valid)
+}
+
+/// Finds items which are valid on their own but probably not what was meant.
+fn lint(config: &config::Config) -> Vec<String> {
+    let mut key_strokes: HashMap<&config::types::KeyStroke, Vec<String>> = HashMap::new();
+    let mut osc_types: BTreeMap<String, Vec<(String, &'static str)>> = BTreeMap::new();
+    let mut warnings = vec![];
+
+    let mut menu_ids: Vec<_> = config.menus.keys().collect();
+    menu_ids.sort_by_key(|id| id.inner());
+
+    for menu_id in menu_ids {
+        for (index, item) in config.menus[menu_id].items.iter().enumerate() {
+            let path = format!("{}[{index}]", menu_id.inner());
+
+            match &item.action {
+                config::types::MenuItemAction::SubMenu { to } if to == menu_id => {
+                    warnings.push(format!("{path} opens the menu it is in"));
+                }
+                config::types::MenuItemAction::KeyStroke { key_stroke } => {
+                    key_strokes.entry(key_stroke).or_default().push(path);
+                }
+                config::types::MenuItemAction::Custom(action) if action.kind == "OscSend" => {
+                    let Ok(osc_send) = serde_json::from_value::<config::types::OscSendAction>(
+                        serde_json::Value::Object(action.params.clone()),
+                    ) else {
+                        continue;
+                    };
+
+                    osc_types
+                        .entry(osc_send.parameter)
+                        .or_default()
+                        .push((path, osc_type_name(osc_send.value)));
+                }
+                config::types::MenuItemAction::Slider {
+                    target: config::types::SliderTarget::OscFloat { parameter },
+                } => {
+                    osc_types
+                        .entry(parameter.clone())
+                        .or_default()
+                        .push((path, "Float"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut duplicates: Vec<_> = key_strokes
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .collect();
+    duplicates.sort();
+
+    for paths in duplicates {
+        warnings.push(format!("{} send the same key stroke", paths.join(", ")));
+    }
+
+    for (parameter, uses) in osc_types {
+        if uses.iter().any(|(_, type_name)| *type_name != uses[0].1) {
+            let uses: Vec<_> = uses
+                .iter()
+                .map(|(path, type_name)| format!("{path} ({type_name})"))
+                .collect();
+
+            warnings.push(format!(
+                "{} send {parameter} with different types",
+                uses.join(", ")
+            ));
+        }
+    }
+
+    warnings
+}
+
+fn osc_type_name(value: config::types::OscValue) -> &'static str {
+    match value {
+        config::types::OscValue::Int(_) => "Int",
+        config::types::OscValue::Float(_) => "Float",
+        config::types::OscValue::Bool(_) => "Bool",
+    }
+}
+
+fn dry_run(action: &MenuItemAction) -> (String, Result<()>) {
+    match action {
+        MenuItemAction::Noop => ("Noop".to_string(), Ok(())),
+        MenuItemAction::OneShotButton(behaviour)
+        | MenuItemAction::Button(behaviour)
+        | MenuItemAction::HoldButton(behaviour, _) => {
+            let behaviour = behaviour.borrow();
+            (behaviour.describe(), behaviour.validate())
+        }
+        MenuItemAction::Slider(behaviour) => {
+            let behaviour = behaviour.borrow();
+            (behaviour.describe(), behaviour.validate())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        let config: config::Config = serde_json::from_str(
+            r#"{
+                "root": "root",
+                "sprite_sheet": "",
+                "menus": {
+                    "root": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "root" } },
+                            { "action": { "type": "KeyStroke", "key_stroke": [{ "type": "Down", "key": 30 }] } },
+                            { "action": { "type": "OscSend", "parameter": "Hat", "value": { "type": "Bool", "value": true } } }
+                        ]
+                    },
+                    "tools": {
+                        "items": [
+                            { "action": { "type": "SubMenu", "to": "root" } },
+                            { "action": { "type": "KeyStroke", "key_stroke": [{ "type": "Down", "key": 30 }] } },
+                            { "action": { "type": "KeyStroke", "key_stroke": [{ "type": "Down", "key": 31 }] } },
+                            { "action": { "type": "OscSend", "parameter": "Hat", "value": { "type": "Int", "value": 1 } } }
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            lint(&config),
+            vec![
+                "root[0] opens the menu it is in",
+                "root[1], tools[1] send the same key stroke",
+                "root[2] (Bool), tools[3] (Int) send Hat with different types",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_config_with_library_item() {
+        let directory = std::env::temp_dir().join("oscpie_test_validate_library");
+        let _ = std::fs::remove_dir_all(&directory);
+        let path = directory.join("config.json");
+        let path = path.to_str().unwrap();
+
+        config::write_default(path).unwrap();
+        config::update(path, |config| {
+            config.item_library = serde_json::from_value(serde_json::json!({
+                "tutorial": { "action": { "type": "StartTutorial" }, "label": "Tutorial" }
+            }))
+            .unwrap();
+            config.menus.get_mut(&config.root).unwrap().items.push(
+                serde_json::from_value(serde_json::json!({
+                    "action": { "type": "Library", "item": "tutorial" }
+                }))
+                .unwrap(),
+            );
+        })
+        .unwrap();
+
+        assert!(validate_config(path, false).unwrap());
+    }
+}
@@ -1,20 +1,22 @@
 use anyhow::Result;
-use log::{debug, log_enabled, trace};
+use log::{debug, info, log_enabled, trace, warn};
 use std::{default::Default, mem::forget, sync::Arc};
 use tiny_skia::Pixmap;
 use vulkano::{
     buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
     command_buffer::{
         allocator::{StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo},
-        AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo,
-        PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract,
+        AutoCommandBufferBuilder, BlitImageInfo, BufferImageCopy, CommandBufferUsage,
+        CopyBufferToImageInfo, ImageBlit, PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract,
     },
     device::{
-        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, Queue,
-        QueueCreateInfo, QueueFlags,
+        physical::{PhysicalDevice, PhysicalDeviceType},
+        Device, DeviceCreateInfo, DeviceExtensions, Queue, QueueCreateInfo, QueueFlags,
     },
     format::Format,
-    image::{Image, ImageCreateInfo, ImageType, ImageUsage},
+    image::{
+        sampler::Filter, Image, ImageCreateInfo, ImageSubresourceLayers, ImageType, ImageUsage,
+    },
     instance::{
         debug::{
             DebugUtilsMessageSeverity, DebugUtilsMessageType, DebugUtilsMessenger,
@@ -29,10 +31,54 @@ use vulkano::{
         },
         MemoryProperties, MemoryPropertyFlags,
     },
+    sync::GpuFuture,
     DeviceSize, VulkanLibrary,
 };
 
-use crate::openvr::{CompositorInterface, Handle};
+use crate::{
+    config::types::GpuSelector,
+    dirty_rows::DirtyRows,
+    openvr::{CompositorInterface, Handle, SystemInterface},
+};
+
+/// What the VR runtime needs from the Vulkan instance and device the menu is uploaded with.
+pub trait VulkanRequirements {
+    fn instance_extensions(&self) -> Result<Vec<String>>;
+
+    fn device_extensions(&self, physical_device: &PhysicalDevice) -> Result<Vec<String>>;
+
+    /// Device the runtime composites on, when it requires a specific one.
+    fn physical_device(&self, _instance: &Arc<Instance>) -> Result<Option<Arc<PhysicalDevice>>> {
+        Ok(None)
+    }
+}
+
+/// What SteamVR needs, preferring the GPU the headset is connected to.
+pub struct OpenVrRequirements<'a> {
+    pub compositor: &'a Handle<CompositorInterface>,
+    pub system: &'a Handle<SystemInterface>,
+}
+
+impl VulkanRequirements for OpenVrRequirements<'_> {
+    fn instance_extensions(&self) -> Result<Vec<String>> {
+        self.compositor.get_vulkan_instance_extensions_required()
+    }
+
+    fn device_extensions(&self, physical_device: &PhysicalDevice) -> Result<Vec<String>> {
+        self.compositor
+            .get_vulkan_device_extensions_required(physical_device)
+    }
+
+    fn physical_device(&self, instance: &Arc<Instance>) -> Result<Option<Arc<PhysicalDevice>>> {
+        let Some(luid) = self.system.output_device_luid() else {
+            return Ok(None);
+        };
+
+        Ok(instance
+            .enumerate_physical_devices()?
+            .find(|p| p.properties().device_luid == Some(luid.to_le_bytes())))
+    }
+}
 
 type StandardLiteMemoryAllocator = GenericMemoryAllocator<FreeListAllocator>;
 
@@ -87,28 +133,32 @@ impl StandardLiteMemoryAllocatorExt for StandardLiteMemoryAllocator {
     }
 }
 
+/// Vulkan instance and device with their allocators, created once and shared by every uploader.
+pub struct Context {
+    queue: Arc<Queue>,
+    memory_allocator: Arc<StandardLiteMemoryAllocator>,
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+}
+
 pub struct ImageUploader {
+    context: Arc<Context>,
     upload_buffer: Subbuffer<[u8]>,
-    queue: Arc<Queue>,
     image: Arc<Image>,
     command_buffer: Arc<PrimaryAutoCommandBuffer>,
     pixmap: *const Pixmap,
 }
 
-impl ImageUploader {
+impl Context {
+    /// Creates the instance and device, with what `requirements` asks for. `gpu` overrides the
+    /// device the runtime prefers.
     #[allow(clippy::too_many_lines)]
     pub fn new(
-        pixmap: &Pixmap,
-        compositor_interface: &Handle<CompositorInterface>,
-    ) -> Result<Self> {
-        let width = pixmap.width();
-        let height = pixmap.height();
-        let extent = [width, height, 1];
-
+        requirements: &dyn VulkanRequirements,
+        gpu: Option<&GpuSelector>,
+    ) -> Result<Arc<Self>> {
         let library = VulkanLibrary::new().unwrap();
 
-        let instance_flags_request =
-            compositor_interface.get_vulkan_instance_extensions_required()?;
+        let instance_flags_request = requirements.instance_extensions()?;
         let mut instance_extensions: InstanceExtensions = instance_flags_request
             .iter()
             .map(std::string::String::as_str)
@@ -143,9 +193,38 @@ impl ImageUploader {
             Self::setup_debug_layer(&instance);
         }
 
-        let (physical_device, queue_family_index) = instance
-            .enumerate_physical_devices()
-            .unwrap()
+        let physical_devices: Vec<_> = instance.enumerate_physical_devices()?.collect();
+
+        for (index, p) in physical_devices.iter().enumerate() {
+            info!("Vulkan device {index}: {}", p.properties().device_name);
+        }
+
+        let selected_device = gpu.and_then(|gpu| {
+            let selected = physical_devices
+                .iter()
+                .enumerate()
+                .find(|(index, p)| gpu.matches(*index, &p.properties().device_name))
+                .map(|(_, p)| p.clone());
+
+            if selected.is_none() {
+                warn!("No Vulkan device matches {gpu:?}, choosing one automatically");
+            }
+
+            selected
+        });
+
+        let required_device = match selected_device {
+            Some(selected) => Some(selected),
+            None => requirements.physical_device(&instance)?,
+        };
+
+        let (physical_device, queue_family_index) = physical_devices
+            .into_iter()
+            .filter(|p| {
+                required_device
+                    .as_ref()
+                    .is_none_or(|required| required.handle() == p.handle())
+            })
             // No need for swapchain extension support.
             .filter_map(|p| {
                 p.queue_family_properties()
@@ -169,8 +248,7 @@ impl ImageUploader {
             physical_device.properties().device_type,
         );
 
-        let device_extensions_request =
-            compositor_interface.get_vulkan_device_extensions_required(&physical_device)?;
+        let device_extensions_request = requirements.device_extensions(&physical_device)?;
 
         let device_extensions: DeviceExtensions = device_extensions_request
             .iter()
@@ -203,59 +281,15 @@ impl ImageUploader {
             StandardCommandBufferAllocatorCreateInfo::default(),
         ));
 
-        let upload_buffer: vulkano::buffer::Subbuffer<[u8]> = Buffer::new_slice(
-            memory_allocator.clone(),
-            BufferCreateInfo {
-                usage: BufferUsage::TRANSFER_SRC,
-                ..Default::default()
-            },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_HOST
-                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                ..Default::default()
-            },
-            DeviceSize::from(width * height * 4),
-        )
-        .unwrap();
-
-        let image = Image::new(
+        Ok(Arc::new(Context {
+            queue,
             memory_allocator,
-            ImageCreateInfo {
-                image_type: ImageType::Dim2d,
-                format: Format::R8G8B8A8_SRGB,
-                extent,
-                usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
-                ..Default::default()
-            },
-            AllocationCreateInfo::default(),
-        )
-        .unwrap();
-
-        let mut uploads = AutoCommandBufferBuilder::primary(
-            command_buffer_allocator.clone(),
-            queue.queue_family_index(),
-            CommandBufferUsage::MultipleSubmit,
-        )
-        .unwrap();
-
-        {
-            uploads
-                .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
-                    upload_buffer.clone(),
-                    image.clone(),
-                ))
-                .unwrap();
-        }
-
-        let command_buffer = uploads.build().unwrap();
+            command_buffer_allocator,
+        }))
+    }
 
-        Ok(ImageUploader {
-            queue,
-            upload_buffer,
-            image,
-            command_buffer,
-            pixmap: std::ptr::from_ref::<Pixmap>(pixmap),
-        })
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
     }
 
     fn setup_debug_layer(instance: &Arc<Instance>) {
@@ -321,28 +355,217 @@ impl ImageUploader {
             ));
         };
     }
+}
 
-    pub fn upload(&mut self, pixmap: &Pixmap) -> Arc<Image> {
+impl ImageUploader {
+    /// See [`ImageUploader::resize`] for `mipmaps`.
+    pub fn new(context: Arc<Context>, pixmap: &Pixmap, mipmaps: bool) -> Result<Self> {
+        let (upload_buffer, image, command_buffer) = Self::create_image(&context, pixmap, mipmaps)?;
+
+        Ok(ImageUploader {
+            context,
+            upload_buffer,
+            image,
+            command_buffer,
+            pixmap: std::ptr::from_ref::<Pixmap>(pixmap),
+        })
+    }
+
+    /// Replaces the image with one the size of `pixmap`, which is uploaded from now on. With
+    /// `mipmaps` the image gets a full mip chain, so that a supersampled menu filters down smoothly.
+    pub fn resize(&mut self, pixmap: &Pixmap, mipmaps: bool) -> Result<()> {
+        let (upload_buffer, image, command_buffer) =
+            Self::create_image(&self.context, pixmap, mipmaps)?;
+
+        self.upload_buffer = upload_buffer;
+        self.image = image;
+        self.command_buffer = command_buffer;
+        self.pixmap = std::ptr::from_ref::<Pixmap>(pixmap);
+
+        Ok(())
+    }
+
+    /// Creates the image for `pixmap`, the buffer it is uploaded through and the command buffer
+    /// uploading all of it.
+    fn create_image(
+        context: &Context,
+        pixmap: &Pixmap,
+        mipmaps: bool,
+    ) -> Result<(Subbuffer<[u8]>, Arc<Image>, Arc<PrimaryAutoCommandBuffer>)> {
+        let width = pixmap.width();
+        let height = pixmap.height();
+        let extent = [width, height, 1];
+
+        let upload_buffer: vulkano::buffer::Subbuffer<[u8]> = Buffer::new_slice(
+            context.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            DeviceSize::from(width * height * 4),
+        )?;
+
+        let image = Image::new(
+            context.memory_allocator.clone(),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: Format::R8G8B8A8_SRGB,
+                extent,
+                mip_levels: if mipmaps {
+                    u32::BITS - width.max(height).leading_zeros()
+                } else {
+                    1
+                },
+                usage: ImageUsage::TRANSFER_SRC | ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )?;
+
+        let mut uploads = AutoCommandBufferBuilder::primary(
+            context.command_buffer_allocator.clone(),
+            context.queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+        )
+        .unwrap();
+
+        {
+            uploads
+                .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+                    upload_buffer.clone(),
+                    image.clone(),
+                ))
+                .unwrap();
+        }
+
+        Self::generate_mipmaps(&mut uploads, &image)?;
+
+        let command_buffer = uploads.build()?;
+
+        Ok((upload_buffer, image, command_buffer))
+    }
+
+    /// Fills each mip level of `image` from the one above it.
+    fn generate_mipmaps(
+        uploads: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        image: &Arc<Image>,
+    ) -> Result<()> {
+        let [mut width, mut height, _] = image.extent();
+
+        for mip_level in 1..image.mip_levels() {
+            let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+
+            uploads.blit_image(BlitImageInfo {
+                regions: [ImageBlit {
+                    src_subresource: ImageSubresourceLayers {
+                        mip_level: mip_level - 1,
+                        ..image.subresource_layers()
+                    },
+                    src_offsets: [[0, 0, 0], [width, height, 1]],
+                    dst_subresource: ImageSubresourceLayers {
+                        mip_level,
+                        ..image.subresource_layers()
+                    },
+                    dst_offsets: [[0, 0, 0], [next_width, next_height, 1]],
+                    ..Default::default()
+                }]
+                .into(),
+                filter: Filter::Linear,
+                ..BlitImageInfo::images(image.clone(), image.clone())
+            })?;
+
+            (width, height) = (next_width, next_height);
+        }
+
+        Ok(())
+    }
+
+    /// Uploads `rows` of `pixmap`, the rest of the image keeps what was uploaded before.
+    pub fn upload(&mut self, pixmap: &Pixmap, rows: DirtyRows) -> Result<Arc<Image>> {
         assert!(
             std::ptr::from_ref::<Pixmap>(pixmap) == self.pixmap,
             "pixmap mismatch"
         );
 
+        let bytes = rows.byte_range(pixmap);
+
         {
-            let mut writer = self.upload_buffer.write().unwrap();
-            writer.copy_from_slice(pixmap.data());
+            let mut writer = self.upload_buffer.write()?;
+            writer[bytes.clone()].copy_from_slice(&pixmap.data()[bytes.clone()]);
+        }
+
+        if rows.is_full(pixmap) {
+            let _ = self
+                .command_buffer
+                .clone()
+                .execute(self.context.queue.clone())?;
+
+            return Ok(self.image.clone());
         }
 
-        let _ = self
-            .command_buffer
-            .clone()
-            .execute(self.queue.clone())
-            .unwrap();
+        let mut uploads = AutoCommandBufferBuilder::primary(
+            self.context.command_buffer_allocator.clone(),
+            self.context.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        uploads.copy_buffer_to_image(CopyBufferToImageInfo {
+            regions: [BufferImageCopy {
+                buffer_offset: bytes.start as DeviceSize,
+                image_subresource: self.image.subresource_layers(),
+                image_offset: [0, rows.start, 0],
+                image_extent: [pixmap.width(), rows.height(), 1],
+                ..Default::default()
+            }]
+            .into(),
+            ..CopyBufferToImageInfo::buffer_image(self.upload_buffer.clone(), self.image.clone())
+        })?;
+
+        Self::generate_mipmaps(&mut uploads, &self.image)?;
+
+        let _ = uploads.build()?.execute(self.context.queue.clone())?;
+
+        Ok(self.image.clone())
+    }
 
-        self.image.clone()
+    /// Uploads `pixmap` straight into `target`, such as an image of a runtime's swapchain.
+    pub fn upload_to(&mut self, pixmap: &Pixmap, target: Arc<Image>) -> Result<()> {
+        assert!(
+            std::ptr::from_ref::<Pixmap>(pixmap) == self.pixmap,
+            "pixmap mismatch"
+        );
+
+        {
+            let mut writer = self.upload_buffer.write()?;
+            writer.copy_from_slice(pixmap.data());
+        }
+
+        let mut uploads = AutoCommandBufferBuilder::primary(
+            self.context.command_buffer_allocator.clone(),
+            self.context.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        uploads.copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+            self.upload_buffer.clone(),
+            target,
+        ))?;
+
+        uploads
+            .build()?
+            .execute(self.context.queue.clone())?
+            .then_signal_fence_and_flush()?
+            .wait(None)?;
+
+        Ok(())
     }
 
     pub fn queue(&self) -> &Queue {
-        self.queue.as_ref()
+        self.context.queue.as_ref()
     }
 }
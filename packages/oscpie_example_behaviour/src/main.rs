@@ -0,0 +1,50 @@
+//! oscpie with a `Say` action compiled in, which shows a message when selected:
+//!
+//! ```json
+//! { "action": { "type": "Say", "message": "Hello!" }, "icon": "hello" }
+//! ```
+//!
+//! Run it from `packages/oscpie` so that `config/config.json` is found:
+//!
+//! ```sh
+//! cargo run -p oscpie_example_behaviour
+//! ```
+
+use oscpie::{ActionContext, ActionResult, MenuActionBehaviour, Severity};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Say {
+    message: String,
+}
+
+impl MenuActionBehaviour<bool> for Say {
+    fn value(&self) -> bool {
+        false
+    }
+
+    fn on_change(&mut self, _value: bool, ctx: &mut ActionContext) -> ActionResult {
+        log::info!("Saying {}", self.message);
+        ctx.toast(Severity::Info, self.message.clone());
+
+        ActionResult::Success
+    }
+
+    fn describe(&self) -> String {
+        format!("Say '{}'", self.message)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.message.is_empty() {
+            anyhow::bail!("Nothing to say");
+        }
+
+        Ok(())
+    }
+}
+
+fn main() {
+    oscpie::CoreBuilder::new()
+        .register_behaviour("Say", |params| Ok(serde_json::from_value::<Say>(params)?))
+        .run();
+}
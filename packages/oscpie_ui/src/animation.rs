@@ -0,0 +1,254 @@
+//! Easing curves and the animated values driven by them.
+
+/// Maps the progress of an animation, from `0.0` to `1.0`, to the progress of the value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// Same as CSS `cubic-bezier()`, from `(0, 0)` to `(1, 1)` through the two control points.
+    CubicBezier {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    /// Damped oscillation which overshoots the target before settling.
+    Spring {
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+impl Easing {
+    pub const EASE: Easing = Easing::CubicBezier {
+        x1: 0.25,
+        y1: 0.1,
+        x2: 0.25,
+        y2: 1.0,
+    };
+    pub const EASE_IN: Easing = Easing::CubicBezier {
+        x1: 0.42,
+        y1: 0.0,
+        x2: 1.0,
+        y2: 1.0,
+    };
+    pub const EASE_OUT: Easing = Easing::CubicBezier {
+        x1: 0.0,
+        y1: 0.0,
+        x2: 0.58,
+        y2: 1.0,
+    };
+    pub const EASE_IN_OUT: Easing = Easing::CubicBezier {
+        x1: 0.42,
+        y1: 0.0,
+        x2: 0.58,
+        y2: 1.0,
+    };
+    pub const SPRING: Easing = Easing::Spring {
+        stiffness: 12.0,
+        damping: 6.0,
+    };
+
+    pub fn ease(self, t: f32) -> f32 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+
+        if t >= 1.0 {
+            return 1.0;
+        }
+
+        match self {
+            Easing::Linear => t,
+            Easing::CubicBezier { x1, y1, x2, y2 } => {
+                let s = solve_bezier(t, x1, x2);
+                bezier(s, y1, y2)
+            }
+            Easing::Spring { stiffness, damping } => {
+                1.0 - (-damping * t).exp() * (stiffness * t).cos()
+            }
+        }
+    }
+}
+
+/// One dimension of a cubic bezier from 0 to 1 with the control points `p1` and `p2`.
+fn bezier(s: f32, p1: f32, p2: f32) -> f32 {
+    let inv = 1.0 - s;
+    3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+}
+
+/// Finds the curve parameter where the x coordinate is `x`. x is monotonic for control points in
+/// `0.0..=1.0`, so bisection always converges.
+fn solve_bezier(x: f32, x1: f32, x2: f32) -> f32 {
+    let (mut low, mut high) = (0.0, 1.0);
+
+    for _ in 0..24 {
+        let mid = f32::midpoint(low, high);
+
+        if bezier(mid, x1, x2) < x {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    f32::midpoint(low, high)
+}
+
+/// How an [`Animated`] value follows its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    /// Exponential smoothing, which never quite arrives and has no fixed duration.
+    Smooth { speed: f32 },
+    /// Runs from the current value to the new target in a fixed time along `easing`.
+    Tween { duration: f32, easing: Easing },
+}
+
+/// A value animated towards a target which may change at any time.
+#[derive(Debug, Clone)]
+pub struct Animated {
+    transition: Transition,
+    current: f32,
+    from: f32,
+    to: f32,
+    elapsed: f32,
+}
+
+impl Animated {
+    pub fn new(value: f32, transition: Transition) -> Self {
+        Self {
+            transition,
+            current: value,
+            from: value,
+            to: value,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn get_current(&self) -> f32 {
+        self.current
+    }
+
+    #[allow(clippy::float_cmp)]
+    pub fn update(&mut self, target: f32, dt: f32) -> f32 {
+        match self.transition {
+            Transition::Smooth { speed } => {
+                self.current += (target - self.current) * (1.0 - (-speed * dt).exp());
+            }
+            Transition::Tween { duration, easing } => {
+                if target != self.to {
+                    self.from = self.current;
+                    self.to = target;
+                    self.elapsed = 0.0;
+                }
+
+                self.elapsed += dt;
+
+                let t = if duration > 0.0 {
+                    self.elapsed / duration
+                } else {
+                    1.0
+                };
+
+                self.current = self.from + (self.to - self.from) * easing.ease(t);
+            }
+        }
+
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ease_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EASE,
+            Easing::EASE_IN,
+            Easing::EASE_OUT,
+            Easing::EASE_IN_OUT,
+            Easing::SPRING,
+        ] {
+            assert!(easing.ease(0.0).abs() < 1e-6, "{easing:?}");
+            assert!((easing.ease(1.0) - 1.0).abs() < 1e-6, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn test_cubic_bezier_matches_css() {
+        // Reference values of CSS `ease-in-out`
+        assert!((Easing::EASE_IN_OUT.ease(0.5) - 0.5).abs() < 1e-3);
+        assert!(Easing::EASE_IN.ease(0.25) < 0.25);
+        assert!(Easing::EASE_OUT.ease(0.25) > 0.25);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    #[test]
+    fn test_spring_overshoots() {
+        let peak = (1..100)
+            .map(|i| Easing::SPRING.ease(i as f32 / 100.0))
+            .fold(0.0, f32::max);
+
+        assert!(peak > 1.0);
+    }
+
+    #[test]
+    fn test_tween_reaches_target() {
+        let mut animated = Animated::new(
+            0.0,
+            Transition::Tween {
+                duration: 0.5,
+                easing: Easing::EASE,
+            },
+        );
+
+        animated.update(2.0, 0.25);
+        assert!(animated.get_current() > 0.0 && animated.get_current() < 2.0);
+
+        animated.update(2.0, 0.25);
+        assert!((animated.get_current() - 2.0).abs() < 1e-6);
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use tiny_skia::Color;
+
+    use super::*;
+    use crate::{shapes, story::story};
+
+    /// Plots the curve as dots, with the value going up, so that its shape can be checked by eye.
+    #[allow(clippy::cast_precision_loss)]
+    fn plot(name: &str, easing: Easing) {
+        story(name, |pixmap| {
+            let (left, bottom, size) = (64.0, 384.0, 384.0);
+
+            shapes::fill_circle(pixmap, left, bottom, 4.0, Color::BLACK);
+            shapes::fill_circle(pixmap, left + size, bottom - size * 0.75, 4.0, Color::BLACK);
+
+            for i in 0..=64 {
+                let t = i as f32 / 64.0;
+
+                shapes::fill_circle(
+                    pixmap,
+                    left + size * t,
+                    bottom - size * 0.75 * easing.ease(t),
+                    3.0,
+                    Color::from_rgba8(40, 90, 220, 255),
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn story_easing_curves() {
+        plot("easing_linear", Easing::Linear);
+        plot("easing_ease", Easing::EASE);
+        plot("easing_ease_in", Easing::EASE_IN);
+        plot("easing_ease_out", Easing::EASE_OUT);
+        plot("easing_ease_in_out", Easing::EASE_IN_OUT);
+        plot("easing_spring", Easing::SPRING);
+    }
+}
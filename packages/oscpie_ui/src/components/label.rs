@@ -0,0 +1,100 @@
+use tiny_skia::{Color, FilterQuality, Pixmap, PixmapPaint, Transform};
+
+use crate::font::Font;
+
+pub struct Props {
+    /// Center of the text.
+    pub x: f32,
+    pub y: f32,
+    pub scale: f32,
+    /// Width the text is shrunk to fit in.
+    pub max_width: f32,
+}
+
+/// Single line of text, rasterized once when it is created.
+pub struct LabelComponent {
+    pixmap: Option<Pixmap>,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+#[allow(clippy::cast_precision_loss)]
+impl LabelComponent {
+    pub fn new(font: &Font, text: &str, size: f32, color: Color) -> Self {
+        Self {
+            pixmap: font.rasterize(text, size, color),
+            x: 0.0,
+            y: 0.0,
+            scale: 1.0,
+        }
+    }
+
+    pub fn update(&mut self, props: &Props) {
+        let Some(pixmap) = &self.pixmap else {
+            return;
+        };
+
+        let width = pixmap.width() as f32;
+        let height = pixmap.height() as f32;
+
+        self.scale = props.scale.min(props.max_width / width);
+        self.x = props.x - width * self.scale / 2.0;
+        self.y = props.y - height * self.scale / 2.0;
+    }
+
+    pub fn render(&self, target: &mut Pixmap) {
+        let Some(pixmap) = &self.pixmap else {
+            return;
+        };
+
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bilinear,
+            ..PixmapPaint::default()
+        };
+
+        target.draw_pixmap(
+            0,
+            0,
+            pixmap.as_ref(),
+            &paint,
+            Transform::from_scale(self.scale, self.scale).post_translate(self.x, self.y),
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use std::path::PathBuf;
+
+    use crate::story::story;
+
+    use super::*;
+
+    #[test]
+    fn story_label_component() {
+        story("label", |pixmap| {
+            let font = Font::load(PathBuf::from("test_files/fonts/DejaVuSansMono.ttf")).unwrap();
+
+            for (index, max_width) in [512.0, 200.0, 100.0].into_iter().enumerate() {
+                #[allow(clippy::cast_precision_loss)]
+                let y = 128.0 + 128.0 * index as f32;
+
+                let mut label = LabelComponent::new(
+                    &font,
+                    "Notifications",
+                    48.0,
+                    Color::from_rgba8(26, 26, 51, 255),
+                );
+                label.update(&Props {
+                    x: 256.0,
+                    y,
+                    scale: 1.0,
+                    max_width,
+                });
+                label.render(pixmap);
+            }
+        });
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use tiny_skia::{Color, Pixmap};
+
+pub struct Font {
+    font: fontdue::Font,
+}
+
+impl Font {
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        log::info!("Loading font: {}", path.display());
+
+        let data = std::fs::read(&path).map_err(|e| format!("{}: {}", e, path.display()))?;
+
+        Self::from_bytes(&data)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
+
+        Ok(Self { font })
+    }
+
+    /// Draws `text` on a single line into a pixmap just large enough for it. Returns `None` for
+    /// text which draws nothing.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss
+    )]
+    pub fn rasterize(&self, text: &str, size: f32, color: Color) -> Option<Pixmap> {
+        let line_metrics = self.font.horizontal_line_metrics(size)?;
+
+        // Pen position of each glyph, with kerning
+        let mut pen = 0.0;
+        let mut previous = None;
+        let glyphs: Vec<_> = text
+            .chars()
+            .map(|character| {
+                if let Some(previous) = previous {
+                    pen += self
+                        .font
+                        .horizontal_kern(previous, character, size)
+                        .unwrap_or(0.0);
+                }
+
+                let x = pen;
+                pen += self.font.metrics(character, size).advance_width;
+                previous = Some(character);
+
+                (character, x)
+            })
+            .collect();
+
+        let width = pen.ceil() as u32;
+        let height = (line_metrics.ascent - line_metrics.descent).ceil() as u32;
+        let mut pixmap = Pixmap::new(width, height)?;
+        let color = color.to_color_u8();
+
+        for (character, x) in glyphs {
+            let (metrics, coverage) = self.font.rasterize(character, size);
+
+            let left = (x + metrics.xmin as f32).round() as i32;
+            let top =
+                (line_metrics.ascent - metrics.ymin as f32).round() as i32 - metrics.height as i32;
+
+            for (index, &coverage) in coverage.iter().enumerate() {
+                let px = left + (index % metrics.width) as i32;
+                let py = top + (index / metrics.width) as i32;
+
+                if coverage == 0 || px < 0 || py < 0 || px >= width as i32 || py >= height as i32 {
+                    continue;
+                }
+
+                let alpha = (u16::from(color.alpha()) * u16::from(coverage) / 255) as u8;
+                let pixel = &mut pixmap.pixels_mut()[py as usize * width as usize + px as usize];
+
+                // Glyphs may overlap slightly, keep the more opaque one
+                if alpha > pixel.alpha() {
+                    *pixel = tiny_skia::ColorU8::from_rgba(
+                        color.red(),
+                        color.green(),
+                        color.blue(),
+                        alpha,
+                    )
+                    .premultiply();
+                }
+            }
+        }
+
+        Some(pixmap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_test_font() -> Font {
+        Font::load(PathBuf::from("test_files/fonts/DejaVuSansMono.ttf")).unwrap()
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let font = load_test_font();
+
+        let short = font.rasterize("ab", 24.0, Color::WHITE).unwrap();
+        let long = font.rasterize("abcd", 24.0, Color::WHITE).unwrap();
+
+        assert!(long.width() > short.width());
+        assert_eq!(long.height(), short.height());
+        assert!(short.pixels().iter().any(|pixel| pixel.alpha() > 0));
+    }
+
+    #[test]
+    fn test_rasterize_empty() {
+        assert!(load_test_font().rasterize("", 24.0, Color::WHITE).is_none());
+    }
+
+    #[test]
+    fn test_load_missing() {
+        assert!(Font::load(PathBuf::from("test_files/fonts/missing.ttf")).is_err());
+    }
+}
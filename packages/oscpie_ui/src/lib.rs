@@ -0,0 +1,14 @@
+//! Widgets and drawing helpers shared by everything that renders the pie menu with tiny-skia.
+//!
+//! Nothing in here knows about OpenVR or the GPU, so the overlay, the previews and any desktop
+//! tool draw through the same code.
+
+pub mod animation;
+pub mod component;
+pub mod components;
+pub mod font;
+pub mod shapes;
+pub mod sprite;
+pub mod story;
+
+pub use component::Component;
@@ -0,0 +1,235 @@
+use std::f32::consts::FRAC_PI_2;
+
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+/// Fills a circle centered at `(x, y)`. Nothing is drawn for a non positive radius.
+pub fn fill_circle(pixmap: &mut Pixmap, x: f32, y: f32, radius: f32, color: Color) {
+    let Some(path) = PathBuilder::from_circle(x, y, radius) else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+/// Fills the rectangle with its top left corner at `(x, y)`. Nothing is drawn for a non positive
+/// size.
+pub fn fill_rect(pixmap: &mut Pixmap, x: f32, y: f32, width: f32, height: f32, color: Color) {
+    let Some(rect) = Rect::from_xywh(x, y, width, height) else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+
+    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+}
+
+/// Fills the ring segment around `(center_x, center_y)` between the distances `inner` and `outer`,
+/// from `start_angle` to `end_angle`. Nothing is drawn for an empty segment.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_arc(
+    pixmap: &mut Pixmap,
+    center_x: f32,
+    center_y: f32,
+    inner: f32,
+    outer: f32,
+    start_angle: f32,
+    end_angle: f32,
+    color: Color,
+) {
+    let path = {
+        let mut pb = PathBuilder::new();
+
+        pb.move_to(start_angle.cos() * outer, start_angle.sin() * outer);
+        arc_to(&mut pb, outer, start_angle, end_angle);
+        pb.line_to(end_angle.cos() * inner, end_angle.sin() * inner);
+        arc_to(&mut pb, inner, end_angle, start_angle);
+        pb.close();
+
+        pb.finish()
+    };
+
+    let Some(path) = path else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::from_translate(center_x, center_y),
+        None,
+    );
+}
+
+/// Continues the path along a circle around the origin from `start_angle` to `end_angle`, with one
+/// cubic curve per quarter turn at most.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn arc_to(pb: &mut PathBuilder, radius: f32, start_angle: f32, end_angle: f32) {
+    let sweep = end_angle - start_angle;
+    let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as f32;
+
+    // Distance of the control points along the tangents, which makes the curve closest to a circle
+    let handle = radius * 4.0 / 3.0 * (step / 4.0).tan();
+
+    for i in 0..segments {
+        let from = start_angle + step * i as f32;
+        let to = from + step;
+
+        pb.cubic_to(
+            from.cos() * radius - from.sin() * handle,
+            from.sin() * radius + from.cos() * handle,
+            to.cos() * radius + to.sin() * handle,
+            to.sin() * radius - to.cos() * handle,
+            to.cos() * radius,
+            to.sin() * radius,
+        );
+    }
+}
+
+/// Strokes a line pointing away from `(center_x, center_y)` at `angle`, between the distances
+/// `inner` and `outer`.
+#[allow(clippy::too_many_arguments)]
+pub fn stroke_radial_line(
+    pixmap: &mut Pixmap,
+    center_x: f32,
+    center_y: f32,
+    angle: f32,
+    inner: f32,
+    outer: f32,
+    width: f32,
+    color: Color,
+) {
+    let path = {
+        let mut pb = PathBuilder::new();
+
+        pb.move_to(angle.cos() * inner, angle.sin() * inner);
+        pb.line_to(angle.cos() * outer, angle.sin() * outer);
+
+        pb.finish()
+    };
+
+    let Some(path) = path else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+
+    let stroke = Stroke {
+        width,
+        ..Stroke::default()
+    };
+
+    pixmap.stroke_path(
+        &path,
+        &paint,
+        &stroke,
+        Transform::from_translate(center_x, center_y),
+        None,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn alpha_at(pixmap: &Pixmap, angle: f32, distance: f32) -> u8 {
+        let x = (50.0 + angle.cos() * distance) as u32;
+        let y = (50.0 + angle.sin() * distance) as u32;
+
+        pixmap.pixel(x, y).unwrap().alpha()
+    }
+
+    #[test]
+    fn test_fill_arc_covers_segment() {
+        let mut pixmap = Pixmap::new(100, 100).unwrap();
+
+        fill_arc(
+            &mut pixmap,
+            50.0,
+            50.0,
+            20.0,
+            40.0,
+            0.0,
+            PI * 0.75,
+            Color::WHITE,
+        );
+
+        // Inside, along the rounded outer edge and past the quarter turn
+        assert_eq!(alpha_at(&pixmap, PI * 0.25, 30.0), 255);
+        assert_eq!(alpha_at(&pixmap, PI * 0.6, 38.0), 255);
+        // Beyond the end, in the hole and outside
+        assert_eq!(alpha_at(&pixmap, PI, 30.0), 0);
+        assert_eq!(alpha_at(&pixmap, PI * 0.25, 10.0), 0);
+        assert_eq!(alpha_at(&pixmap, PI * 0.25, 45.0), 0);
+    }
+}
+
+#[cfg(test)]
+mod stories {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::story::story;
+
+    #[test]
+    fn story_shapes() {
+        story("shapes", |pixmap| {
+            fill_circle(
+                pixmap,
+                256.0,
+                256.0,
+                200.0,
+                Color::from_rgba8(26, 26, 51, 204),
+            );
+
+            for i in 0..6 {
+                #[allow(clippy::cast_precision_loss)]
+                let angle = PI * 2.0 * i as f32 / 6.0;
+
+                stroke_radial_line(pixmap, 256.0, 256.0, angle, 80.0, 180.0, 4.0, Color::WHITE);
+            }
+
+            fill_arc(
+                pixmap,
+                256.0,
+                256.0,
+                185.0,
+                200.0,
+                0.0,
+                PI * 0.75,
+                Color::WHITE,
+            );
+
+            fill_rect(
+                pixmap,
+                56.0,
+                470.0,
+                400.0,
+                24.0,
+                Color::from_rgba8(26, 26, 51, 204),
+            );
+        });
+    }
+}
@@ -48,6 +48,17 @@ impl SpriteSheet {
         })
     }
 
+    pub fn contains(&self, name: &str) -> bool {
+        self.meta.sprites.contains_key(name)
+    }
+
+    /// Names of all sprites, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.meta.sprites.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
     pub fn cutout(&self, name: &str) -> Option<Pixmap> {
         let sprite = self.meta.sprites.get(name)?;
 
@@ -90,4 +101,13 @@ mod tests {
             sprite_p.unwrap()
         );
     }
+
+    #[test]
+    fn test_contains() {
+        let sprite_sheet = load_test_sprite_sheet();
+
+        assert!(sprite_sheet.contains("s"));
+        assert!(!sprite_sheet.contains("missing"));
+        assert!(sprite_sheet.names().contains(&"p"));
+    }
 }